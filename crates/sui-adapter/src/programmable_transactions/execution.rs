@@ -83,8 +83,14 @@ pub fn execute<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     // execute commands
     let mut mode_results = Mode::empty_results();
     for (idx, command) in commands.into_iter().enumerate() {
-        execute_command::<_, _, Mode>(&mut context, &mut mode_results, command)
-            .map_err(|e| e.with_command_index(idx))?
+        if let Some(profiler) = context.gas_status.profiler_mut() {
+            profiler.open_frame(command_profile_name(idx, &command));
+        }
+        let result = execute_command::<_, _, Mode>(&mut context, &mut mode_results, command);
+        if let Some(profiler) = context.gas_status.profiler_mut() {
+            profiler.close_frame();
+        }
+        result.map_err(|e| e.with_command_index(idx))?
     }
     // apply changes
     let ExecutionResults {
@@ -104,6 +110,27 @@ pub fn execute<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     Ok(mode_results)
 }
 
+/// Name of the gas profiler frame the command at `idx` executes in
+fn command_profile_name(idx: usize, command: &Command) -> String {
+    let kind = match command {
+        Command::MoveCall(call) => {
+            let module = ModuleId::new(call.package.into(), call.module.clone());
+            return format!(
+                "{idx}:MoveCall({}::{})",
+                module.short_str_lossless(),
+                call.function
+            );
+        }
+        Command::TransferObjects(_, _) => "TransferObjects",
+        Command::SplitCoins(_, _) => "SplitCoins",
+        Command::MergeCoins(_, _) => "MergeCoins",
+        Command::MakeMoveVec(_, _) => "MakeMoveVec",
+        Command::Publish(_, _) => "Publish",
+        Command::Upgrade(_, _, _, _) => "Upgrade",
+    };
+    format!("{idx}:{kind}")
+}
+
 /// Execute a single command
 fn execute_command<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     context: &mut ExecutionContext<E, S>,
@@ -489,6 +489,8 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     metrics: None,
                     supported_protocol_versions: Some(supported_protocol_versions),
                     db_checkpoint_config: self.db_checkpoint_config.clone(),
+                    checkpoint_archive_config: None,
+                    checkpoint_archive_read_config: None,
                     indirect_objects_threshold: usize::MAX,
                 }
             })
@@ -17,6 +17,7 @@ use std::sync::Arc;
 use std::usize;
 use sui_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
 use sui_protocol_config::SupportedProtocolVersions;
+use sui_storage::archive::DEFAULT_CHECKPOINTS_PER_BUNDLE;
 use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::AuthorityPublicKeyBytes;
@@ -95,6 +96,15 @@ pub struct NodeConfig {
     #[serde(default)]
     pub db_checkpoint_config: DBCheckpointConfig,
 
+    /// If set, synced checkpoints and their contents are continuously archived to an object store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_archive_config: Option<CheckpointArchiveConfig>,
+
+    /// If set, checkpoints missing locally are synced from this archive on startup, before state
+    /// sync catches up with peers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_archive_read_config: Option<ObjectStoreConfig>,
+
     #[serde(default)]
     pub indirect_objects_threshold: usize,
 }
@@ -348,6 +358,27 @@ pub struct DBCheckpointConfig {
     pub object_store_config: Option<ObjectStoreConfig>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CheckpointArchiveConfig {
+    pub object_store_config: ObjectStoreConfig,
+    /// Number of checkpoints per bundle file when starting a new archive. An existing archive
+    /// keeps the bundle size it was created with.
+    #[serde(default = "default_checkpoints_per_bundle")]
+    pub checkpoints_per_bundle: u64,
+    /// How often to check for new checkpoints to upload.
+    #[serde(default = "default_archive_upload_interval_s")]
+    pub upload_interval_s: u64,
+}
+
+fn default_checkpoints_per_bundle() -> u64 {
+    DEFAULT_CHECKPOINTS_PER_BUNDLE
+}
+
+fn default_archive_upload_interval_s() -> u64 {
+    60
+}
+
 /// Publicly known information about a validator
 /// TODO read most of this from on-chain
 #[serde_as]
@@ -284,6 +284,8 @@ impl<'a> FullnodeConfigBuilder<'a> {
             metrics: None,
             supported_protocol_versions: Some(supported_protocol_versions),
             db_checkpoint_config: self.db_checkpoint_config,
+            checkpoint_archive_config: None,
+            checkpoint_archive_read_config: None,
             indirect_objects_threshold: usize::MAX,
         })
     }
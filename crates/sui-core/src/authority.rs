@@ -16,7 +16,7 @@ use fastcrypto::traits::KeyPair;
 use itertools::Itertools;
 use move_binary_format::compatibility::Compatibility;
 use move_binary_format::CompiledModule;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, TypeTag};
 use parking_lot::Mutex;
 use prometheus::{
     register_histogram_with_registry, register_int_counter_vec_with_registry,
@@ -46,8 +46,9 @@ use sui_adapter::{adapter, execution_mode};
 use sui_config::genesis::Genesis;
 use sui_config::node::{AuthorityStorePruningConfig, DBCheckpointConfig};
 use sui_json_rpc_types::{
-    Checkpoint, DevInspectResults, DryRunTransactionResponse, EventFilter, SuiEvent, SuiMoveValue,
-    SuiObjectDataFilter, SuiTransactionEvents,
+    Checkpoint, DevInspectResults, DryRunTransactionResponse, EventFilter, EventPage, Filter,
+    SuiEvent, SuiMoveValue, SuiObjectDataFilter, SuiTransaction, SuiTransactionEffects,
+    SuiTransactionEvents, TransactionEventContext,
 };
 use sui_macros::{fail_point, fail_point_async, nondeterministic};
 use sui_protocol_config::SupportedProtocolVersions;
@@ -207,6 +208,10 @@ pub struct AuthorityMetrics {
     pub consensus_committed_certificates: IntCounterVec,
 }
 
+/// The maximum number of events examined by a single query for an event filter which has no
+/// dedicated index.
+pub const MAX_EVENTS_SCANNED_PER_QUERY: usize = 10_000;
+
 // Override default Prom buckets for positive numbers in 0-50k range
 const POSITIVE_INT_BUCKETS: &[f64] = &[
     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000., 10000., 20000., 50000.,
@@ -1013,10 +1018,13 @@ impl AuthorityState {
             .certificate_executed(digest, epoch_store)
     }
 
+    /// Executes `transaction` without committing its effects. If `gas_profile` is set, the
+    /// response includes a breakdown of the gas charged.
     pub async fn dry_exec_transaction(
         &self,
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
+        gas_profile: bool,
     ) -> Result<
         (
             DryRunTransactionResponse,
@@ -1032,7 +1040,7 @@ impl AuthorityState {
 
         // make a gas object if one was not provided
         let mut gas_object_refs = transaction.gas().to_vec();
-        let (gas_status, input_objects) = if transaction.gas().is_empty() {
+        let (mut gas_status, input_objects) = if transaction.gas().is_empty() {
             let sender = transaction.sender();
             // use a 100M sui coin
             const MIST_TO_SUI: u64 = 1_000_000_000;
@@ -1062,6 +1070,10 @@ impl AuthorityState {
             .await?
         };
 
+        if gas_profile {
+            gas_status.enable_profiling();
+        }
+
         let shared_object_refs = input_objects.filter_shared_objects();
 
         let transaction_dependencies = input_objects.transaction_dependencies();
@@ -1115,18 +1127,21 @@ impl AuthorityState {
                 )?,
                 object_changes,
                 balance_changes,
+                gas_profile: inner_temp_store.gas_profile,
             },
             inner_temp_store.written,
             effects,
         ))
     }
 
-    /// The object ID for gas can be any object ID, even for an uncreated object
+    /// The object ID for gas can be any object ID, even for an uncreated object. If `gas_profile`
+    /// is set, the results include a breakdown of the gas charged.
     pub async fn dev_inspect_transaction(
         &self,
         sender: SuiAddress,
         transaction_kind: TransactionKind,
         gas_price: Option<u64>,
+        gas_profile: bool,
     ) -> Result<DevInspectResults, anyhow::Error> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
@@ -1177,12 +1192,15 @@ impl AuthorityState {
             transaction_digest,
             protocol_config,
         );
-        let gas_status = SuiGasStatus::new_with_budget(
+        let mut gas_status = SuiGasStatus::new_with_budget(
             max_tx_gas,
             GasPrice::from(gas_price),
             storage_gas_price.into(),
             SuiCostTable::new(protocol_config),
         );
+        if gas_profile {
+            gas_status.enable_profiling();
+        }
         let move_vm = Arc::new(
             adapter::new_move_vm(
                 epoch_store.native_functions().clone(),
@@ -1212,6 +1230,7 @@ impl AuthorityState {
             effects,
             inner_temp_store.events.clone(),
             execution_result,
+            inner_temp_store.gas_profile.clone(),
             &module_cache,
         )
     }
@@ -1462,16 +1481,7 @@ impl AuthorityState {
 
             // Emit events
             if res.is_ok() {
-                self.event_handler
-                    .process_events(
-                        &effects.clone().try_into()?,
-                        &SuiTransactionEvents::try_from(
-                            events.clone(),
-                            *tx_digest,
-                            Some(timestamp_ms),
-                            epoch_store.module_cache(),
-                        )?,
-                    )
+                self.emit_tx(certificate, effects, events, timestamp_ms, epoch_store)
                     .await
                     .tap_ok(|_| {
                         self.metrics
@@ -1493,6 +1503,41 @@ impl AuthorityState {
         Ok(())
     }
 
+    /// Streams the transaction to transaction subscribers, and its events to event subscribers.
+    /// Transactions are streamed before they are checkpointed, so without their checkpoint.
+    async fn emit_tx(
+        &self,
+        certificate: &VerifiedExecutableTransaction,
+        effects: &TransactionEffects,
+        events: &TransactionEvents,
+        timestamp_ms: u64,
+        epoch_store: &Arc<AuthorityPerEpochStore>,
+    ) -> SuiResult {
+        // The transaction is only converted for transaction subscribers.
+        let transaction = if self.event_handler.has_transaction_subscribers().await {
+            Some(
+                SuiTransaction::try_from(certificate.data().clone(), epoch_store.module_cache())
+                    .map_err(|e| SuiError::Unknown(e.to_string()))?,
+            )
+        } else {
+            None
+        };
+        self.event_handler
+            .process_tx(
+                transaction.as_ref(),
+                &effects.clone().try_into()?,
+                &SuiTransactionEvents::try_from(
+                    events.clone(),
+                    *certificate.digest(),
+                    Some(timestamp_ms),
+                    epoch_store.module_cache(),
+                )?,
+                Some(timestamp_ms),
+                None,
+            )
+            .await
+    }
+
     pub fn unixtime_now_ms() -> u64 {
         let ts_ms = Utc::now().timestamp_millis();
         u64::try_from(ts_ms).expect("Travelling in time machine")
@@ -1764,6 +1809,10 @@ impl AuthorityState {
         &self.transaction_manager
     }
 
+    pub fn indexes(&self) -> Option<Arc<IndexStore>> {
+        self.indexes.clone()
+    }
+
     /// Adds certificates to the pending certificate store and transaction manager for ordered execution.
     pub fn enqueue_certificates_for_execution(
         &self,
@@ -2236,6 +2285,14 @@ impl AuthorityState {
         Ok(self.get_indexes()?.next_sequence_number())
     }
 
+    /// The sequence number the transaction was indexed at, if it was indexed.
+    pub fn get_transaction_seq(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Result<Option<TxSequenceNumber>, anyhow::Error> {
+        Ok(self.get_indexes()?.get_transaction_seq(digest)?)
+    }
+
     pub fn get_transactions_in_range_deprecated(
         &self,
         start: TxSequenceNumber,
@@ -2335,6 +2392,23 @@ impl AuthorityState {
         }
     }
 
+    /// The last checkpoint up to which historical balances are available, if any.
+    pub fn get_balance_history_watermark(&self) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        self.get_indexes()?.get_balance_history_watermark()
+    }
+
+    /// The total balance of the coins of `coin_type` owned by `owner` after the execution of
+    /// `checkpoint`.
+    pub fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: &TypeTag,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<i128> {
+        self.get_indexes()?
+            .get_balance_at_checkpoint(owner, coin_type, checkpoint)
+    }
+
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
@@ -2343,8 +2417,19 @@ impl AuthorityState {
         limit: Option<usize>,
         reverse: bool,
     ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        // Checkpoint ranges are paginated in checkpoint order, from the checkpoint of the cursor.
+        let cursor_checkpoint = match (&filter, cursor) {
+            (
+                Some(TransactionFilter::Checkpoint { .. } | TransactionFilter::TimeRange { .. }),
+                Some(cursor),
+            ) => self
+                .database
+                .get_transaction_checkpoint(&cursor)?
+                .map(|(_, checkpoint)| checkpoint),
+            _ => None,
+        };
         self.get_indexes()?
-            .get_transactions(filter, cursor, limit, reverse)
+            .get_transactions(filter, cursor, cursor_checkpoint, limit, reverse)
     }
 
     fn get_checkpoint_store(&self) -> Arc<CheckpointStore> {
@@ -2473,7 +2558,7 @@ impl AuthorityState {
         cursor: Option<EventID>,
         limit: usize,
         descending: bool,
-    ) -> Result<Vec<SuiEvent>, anyhow::Error> {
+    ) -> Result<EventPage, anyhow::Error> {
         let index_store = self.get_indexes()?;
 
         //Get the tx_num from tx_digest
@@ -2490,9 +2575,13 @@ impl AuthorityState {
             (0, 0)
         };
 
-        let limit = limit + 1;
+        let page_limit = limit;
+        // Retrieve 1 extra item for next cursor, and 1 more for the inclusive cursor
+        let limit = limit + 2;
         let mut event_keys = match query {
-            EventFilter::All(..) => index_store.all_events(tx_num, event_num, limit, descending)?,
+            EventFilter::All(filters) if filters.is_empty() => {
+                index_store.all_events(tx_num, event_num, limit, descending)?
+            }
             EventFilter::Transaction(digest) => {
                 index_store.events_by_transaction(&digest, tx_num, event_num, limit, descending)?
             }
@@ -2516,10 +2605,21 @@ impl AuthorityState {
                 end_time,
             } => index_store
                 .event_iterator(start_time, end_time, tx_num, event_num, limit, descending)?,
+            EventFilter::Recipient(recipient) => {
+                index_store.events_by_recipient(&recipient, tx_num, event_num, limit, descending)?
+            }
+            EventFilter::AffectedObject(object_id) => index_store
+                .events_by_affected_object(&object_id, tx_num, event_num, limit, descending)?,
             _ => {
-                return Err(anyhow!(
-                    "This query type is not supported by the full node."
-                ))
+                return self.scan_events(
+                    &index_store,
+                    &query,
+                    cursor,
+                    tx_num,
+                    event_num,
+                    page_limit,
+                    descending,
+                )
             }
         };
 
@@ -2547,9 +2647,9 @@ impl AuthorityState {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut events = vec![];
+        let mut data = vec![];
         for (e, tx_digest, event_seq, timestamp) in stored_events {
-            events.push(SuiEvent::try_from(
+            data.push(SuiEvent::try_from(
                 e,
                 tx_digest,
                 event_seq as u64,
@@ -2557,7 +2657,105 @@ impl AuthorityState {
                 &**self.epoch_store.load().module_cache(),
             )?)
         }
-        Ok(events)
+        let has_next_page = data.len() > page_limit;
+        data.truncate(page_limit);
+        let next_cursor = data.last().map_or(cursor, |e| Some(e.id.clone()));
+        Ok(EventPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    /// Serve event queries which have no dedicated index, by walking all indexed events from
+    /// (tx_seq, event_seq) and matching each one against the filter.
+    /// At most [MAX_EVENTS_SCANNED_PER_QUERY] events are examined per call. When the budget runs
+    /// out first, the page holds the matches found so far and its cursor points at the last
+    /// examined event, so that the next call carries on from there.
+    #[allow(clippy::too_many_arguments)]
+    fn scan_events(
+        &self,
+        index_store: &IndexStore,
+        query: &EventFilter,
+        cursor: Option<EventID>,
+        tx_seq: TxSequenceNumber,
+        event_seq: usize,
+        limit: usize,
+        descending: bool,
+    ) -> Result<EventPage, anyhow::Error> {
+        let module_cache = self.epoch_store.load().module_cache().clone();
+        let requires_context = query.requires_transaction_context();
+        let mut context: Option<(TransactionDigest, TransactionEventContext)> = None;
+        let mut data = vec![];
+        let mut last_scanned = None;
+
+        let mut event_keys = index_store
+            .all_events_iterator(tx_seq, event_seq, descending)?
+            .skip(usize::from(cursor.is_some()))
+            .peekable();
+        let mut scanned = 0;
+        // Retrieve 1 extra item for next cursor
+        while data.len() <= limit && scanned < MAX_EVENTS_SCANNED_PER_QUERY {
+            let (digest, tx_digest, event_seq, timestamp) = match event_keys.next() {
+                Some(key) => key,
+                None => break,
+            };
+            scanned += 1;
+            let event = self
+                .database
+                .perpetual_tables
+                .events
+                .get(&(digest, event_seq))?
+                .ok_or(SuiError::TransactionEventsNotFound { digest })?;
+            let event = SuiEvent::try_from(
+                event,
+                tx_digest,
+                event_seq as u64,
+                Some(timestamp),
+                &**module_cache,
+            )?;
+            last_scanned = Some(event.id.clone());
+
+            // Events of the same transaction are adjacent, only reload the context when the
+            // transaction changes.
+            if requires_context && !matches!(&context, Some((d, _)) if d == &tx_digest) {
+                let effects = self
+                    .database
+                    .get_executed_effects(&tx_digest)?
+                    .ok_or(SuiError::TransactionNotFound { digest: tx_digest })?;
+                let effects = SuiTransactionEffects::try_from(effects)?;
+                context = Some((tx_digest, TransactionEventContext::from(&effects)));
+            }
+            let matched = match &context {
+                Some((_, context)) => query.matches_with_context(&event, context),
+                None => query.matches(&event),
+            };
+            if matched {
+                data.push(event);
+            }
+        }
+
+        if data.len() > limit {
+            data.truncate(limit);
+            let next_cursor = data.last().map(|e| e.id.clone());
+            return Ok(EventPage {
+                data,
+                next_cursor,
+                has_next_page: true,
+            });
+        }
+        // The scan budget ran out before the page was full, resume after the last examined event.
+        let has_next_page = event_keys.peek().is_some();
+        let next_cursor = if has_next_page {
+            last_scanned
+        } else {
+            data.last().map_or(cursor, |e| Some(e.id.clone()))
+        };
+        Ok(EventPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
     }
 
     pub async fn insert_genesis_object(&self, object: Object) {
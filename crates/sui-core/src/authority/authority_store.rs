@@ -8,6 +8,7 @@ use std::ops::Not;
 use std::path::Path;
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use either::Either;
 use fastcrypto::hash::MultisetHash;
 use move_bytecode_utils::module_cache::GetModule;
@@ -19,12 +20,14 @@ use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::{debug, info, trace};
 
+use sui_json_rpc_types::PackageStore;
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::mutex_table::{MutexGuard, MutexTable, RwLockGuard, RwLockTable};
 use sui_types::accumulator::Accumulator;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::error::UserInputError;
 use sui_types::message_envelope::Message;
+use sui_types::move_package::MovePackage;
 use sui_types::object::Owner;
 use sui_types::storage::{
     get_module_by_id, BackingPackageStore, ChildObjectResolver, DeleteKind, ObjectKey, ObjectStore,
@@ -784,6 +787,7 @@ impl AuthorityStore {
             deleted,
             events,
             max_binary_format_version: _,
+            gas_profile: _,
         } = inner_temporary_store;
         trace!(written =? written.values().map(|((obj_id, ver, _), _, _)| (obj_id, ver)).collect::<Vec<_>>(),
                "batch_update_objects: temp store written");
@@ -1445,6 +1449,15 @@ impl GetModule for AuthorityStore {
     }
 }
 
+#[async_trait]
+impl PackageStore for AuthorityStore {
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<Option<MovePackage>> {
+        Ok(self
+            .get_object(&id)?
+            .and_then(|object| object.data.try_as_package().cloned()))
+    }
+}
+
 /// A wrapper to make Orphan Rule happy
 pub struct ResolverWrapper<T: ModuleResolver> {
     pub resolver: Arc<T>,
@@ -26,17 +26,28 @@ use std::{
 
 use futures::stream::FuturesOrdered;
 use itertools::izip;
+use move_core_types::language_storage::TypeTag;
 use mysten_metrics::{spawn_monitored_task, MonitoredFutureExt};
 use prometheus::Registry;
 use sui_config::node::CheckpointExecutorConfig;
+use sui_storage::IndexStore;
+use sui_types::coin::Coin;
+use sui_types::gas_coin::GAS;
 use sui_types::message_envelope::Message;
 use sui_types::messages::VerifiedExecutableTransaction;
+use sui_types::object::Owner;
 use sui_types::{
-    base_types::{ExecutionDigests, TransactionDigest, TransactionEffectsDigest},
+    base_types::{
+        ExecutionDigests, MoveObjectType, ObjectID, SequenceNumber, SuiAddress, TransactionDigest,
+        TransactionEffectsDigest,
+    },
     messages::{TransactionEffects, TransactionEffectsAPI},
     messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint},
 };
-use sui_types::{error::SuiResult, messages::TransactionDataAPI};
+use sui_types::{
+    error::{SuiError, SuiResult},
+    messages::TransactionDataAPI,
+};
 use tap::{TapFallible, TapOptional};
 use tokio::{
     sync::broadcast::{self, error::RecvError},
@@ -67,6 +78,7 @@ pub struct CheckpointExecutor {
     authority_store: Arc<AuthorityStore>,
     tx_manager: Arc<TransactionManager>,
     accumulator: Arc<StateAccumulator>,
+    indexes: Option<Arc<IndexStore>>,
     config: CheckpointExecutorConfig,
     metrics: Arc<CheckpointExecutorMetrics>,
 }
@@ -78,6 +90,7 @@ impl CheckpointExecutor {
         authority_store: Arc<AuthorityStore>,
         tx_manager: Arc<TransactionManager>,
         accumulator: Arc<StateAccumulator>,
+        indexes: Option<Arc<IndexStore>>,
         config: CheckpointExecutorConfig,
         prometheus_registry: &Registry,
     ) -> Self {
@@ -87,6 +100,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            indexes,
             config,
             metrics: CheckpointExecutorMetrics::new(prometheus_registry),
         }
@@ -105,6 +119,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            indexes: None,
             config: Default::default(),
             metrics: CheckpointExecutorMetrics::new_for_tests(),
         }
@@ -237,12 +252,106 @@ impl CheckpointExecutor {
         }
         debug!("Bumping highest_executed_checkpoint watermark to {:?}", seq,);
 
+        // The indexes only serve queries, so failing to update them must not stop execution.
+        if let Err(e) = self.index_checkpoint(checkpoint) {
+            error!(checkpoint = seq, "Failed to index checkpoint: {e}");
+        }
+
         self.checkpoint_store
             .update_highest_executed_checkpoint(checkpoint)
             .unwrap();
         self.metrics.last_executed_checkpoint.set(seq as i64);
     }
 
+    /// Indexes the transactions of an executed checkpoint, and the balance changes they made.
+    fn index_checkpoint(&self, checkpoint: &VerifiedCheckpoint) -> SuiResult {
+        let indexes = match &self.indexes {
+            Some(indexes) => indexes,
+            None => return Ok(()),
+        };
+        let seq = *checkpoint.sequence_number();
+        let tx_digests: Vec<_> = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!("Contents of checkpoint {seq} not found"))
+            })?
+            .iter()
+            .map(|digests| digests.transaction)
+            .collect();
+        // Without them, the balance history stops at the previous checkpoint.
+        let balance_changes = self
+            .balance_changes(&tx_digests)
+            .tap_err(|e| {
+                error!(
+                    checkpoint = seq,
+                    "Failed to compute balance changes, the balance history stops here: {e}"
+                )
+            })
+            .ok();
+        indexes.index_checkpoint(seq, checkpoint.timestamp_ms, &tx_digests, balance_changes)
+    }
+
+    /// Changes made by executed transactions to the balances of address owned coins, computed
+    /// from the coin versions they modified and wrote.
+    fn balance_changes(
+        &self,
+        tx_digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<(SuiAddress, TypeTag, i128)>> {
+        let mut changes = vec![];
+        for (digest, effects) in tx_digests.iter().zip(
+            self.authority_store
+                .multi_get_executed_effects(tx_digests)?,
+        ) {
+            let effects = effects.unwrap_or_else(|| {
+                panic!("Checkpointed transaction {digest:?} should have been executed")
+            });
+            for (id, version) in effects.modified_at_versions() {
+                if let Some((owner, coin_type, balance)) = self.address_owned_coin(id, *version)? {
+                    changes.push((owner, coin_type, -(balance as i128)));
+                }
+            }
+            for ((id, version, _), _, _) in effects.all_changed_objects() {
+                if let Some((owner, coin_type, balance)) = self.address_owned_coin(id, *version)? {
+                    changes.push((owner, coin_type, balance as i128));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// The owner, coin type and balance of version `version` of object `id`, if it is an
+    /// address owned coin. The version must still be in the store.
+    fn address_owned_coin(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<(SuiAddress, TypeTag, u64)>> {
+        let object = self
+            .authority_store
+            .get_object_by_key(id, version)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Object {id} at version {version} was not found, it may have been pruned"
+                ))
+            })?;
+        let owner = match object.owner {
+            Owner::AddressOwner(owner) => owner,
+            _ => return Ok(None),
+        };
+        let coin_type = match object.type_() {
+            Some(MoveObjectType::GasCoin) => GAS::type_tag(),
+            Some(MoveObjectType::Coin(coin_type)) => coin_type.clone(),
+            _ => return Ok(None),
+        };
+        let balance = Coin::extract_balance_if_coin(&object)
+            .map_err(|e| SuiError::ObjectDeserializationError {
+                error: e.to_string(),
+            })?
+            .unwrap_or_default();
+        Ok(Some((owner, coin_type, balance)))
+    }
+
     fn schedule_synced_checkpoints(
         &self,
         pending: &mut CheckpointExecutionBuffer,
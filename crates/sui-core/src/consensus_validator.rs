@@ -10,7 +10,7 @@ use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::transaction_manager::TransactionManager;
 use async_trait::async_trait;
 use narwhal_worker::TransactionValidator;
-use sui_types::messages::{ConsensusTransaction, ConsensusTransactionKind};
+use sui_types::messages::{ConsensusTransaction, ConsensusTransactionKind, TransactionDataAPI};
 use tap::TapFallible;
 use tokio::runtime::Handle;
 use tracing::{info, warn};
@@ -114,6 +114,20 @@ impl TransactionValidator for SuiTxValidator {
         //     .enqueue_certificates(owned_tx_certs, &self.epoch_store)
         //     .wrap_err("Failed to schedule certificates for execution")
     }
+
+    fn validate_and_prioritize(&self, tx: &[u8]) -> Result<u64, Self::Error> {
+        // Transactions from the local sui instance are trusted like in `validate`, they are only
+        // decoded to find their priority.
+        Ok(match tx_from_bytes(tx).map(|tx| tx.kind) {
+            // User transactions are put in batches by gas price.
+            Ok(ConsensusTransactionKind::UserTransaction(certificate)) => {
+                certificate.data().transaction_data().gas_price()
+            }
+            // Validators' own messages go first.
+            Ok(_) => u64::MAX,
+            Err(_) => 0,
+        })
+    }
 }
 
 pub struct SuiTxValidatorMetrics {
@@ -151,7 +165,9 @@ mod tests {
     use narwhal_types::Batch;
     use narwhal_worker::TransactionValidator;
     use sui_types::{
-        base_types::AuthorityName, messages::ConsensusTransaction, signature::GenericSignature,
+        base_types::AuthorityName,
+        messages::{ConsensusTransaction, TransactionDataAPI},
+        signature::GenericSignature,
     };
 
     use sui_macros::sim_test;
@@ -192,6 +208,18 @@ mod tests {
         );
         let res = validator.validate(&first_transaction_bytes);
         assert!(res.is_ok(), "{res:?}");
+        assert_eq!(
+            validator
+                .validate_and_prioritize(&first_transaction_bytes)
+                .unwrap(),
+            certificates[0].data().transaction_data().gas_price()
+        );
+        assert_eq!(
+            validator
+                .validate_and_prioritize(b"not a transaction")
+                .unwrap(),
+            0
+        );
 
         let transaction_bytes: Vec<_> = certificates
             .clone()
@@ -1,12 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use tokio_stream::Stream;
+use std::sync::Arc;
+
+use tokio_stream::{Stream, StreamExt};
 use tracing::{error, instrument, trace};
 
-use sui_json_rpc_types::{EventFilter, SuiTransactionEffects, SuiTransactionEvents};
+use sui_json_rpc_types::{
+    EventFilter, SuiEventWithContext, SuiTransaction, SuiTransactionEffects, SuiTransactionEvents,
+    SuiTransactionResponse, TransactionEventContext,
+};
 use sui_json_rpc_types::{SuiEvent, SuiTransactionEffectsAPI};
 use sui_types::error::SuiResult;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::query::TransactionFilter;
 
 use crate::streamer::Streamer;
 
@@ -17,14 +24,15 @@ mod event_handler_tests;
 pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 1000;
 
 pub struct EventHandler {
-    event_streamer: Streamer<SuiEvent, EventFilter>,
+    event_streamer: Streamer<SuiEventWithContext, EventFilter>,
+    transaction_streamer: Streamer<SuiTransactionResponse, TransactionFilter>,
 }
 
 impl Default for EventHandler {
     fn default() -> Self {
-        let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE);
         Self {
-            event_streamer: streamer,
+            event_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE),
+            transaction_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE),
         }
     }
 }
@@ -42,8 +50,13 @@ impl EventHandler {
             "Finished writing events to event store"
         );
 
+        let context = Arc::new(TransactionEventContext::from(effects));
         // serially dispatch event processing to honor events' orders.
         for event in events.data.clone() {
+            let event = SuiEventWithContext {
+                event,
+                context: context.clone(),
+            };
             if let Err(e) = self.event_streamer.send(event).await {
                 error!(error =? e, "Failed to send event to dispatch");
             }
@@ -51,7 +64,47 @@ impl EventHandler {
         Ok(())
     }
 
+    /// Dispatch an executed transaction to transaction subscribers, followed by its events
+    /// to event subscribers. The transaction can be left out when there are no transaction
+    /// subscribers, see `has_transaction_subscribers`.
+    #[instrument(level = "debug", skip_all, fields(tx_digest=?effects.transaction_digest()), err)]
+    pub async fn process_tx(
+        &self,
+        transaction: Option<&SuiTransaction>,
+        effects: &SuiTransactionEffects,
+        events: &SuiTransactionEvents,
+        timestamp_ms: Option<u64>,
+        checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> SuiResult {
+        if let Some(transaction) = transaction {
+            let response = SuiTransactionResponse {
+                digest: *effects.transaction_digest(),
+                transaction: Some(transaction.clone()),
+                effects: Some(effects.clone()),
+                events: Some(events.clone()),
+                timestamp_ms,
+                checkpoint,
+                ..Default::default()
+            };
+            if let Err(e) = self.transaction_streamer.send(response).await {
+                error!(error =? e, "Failed to send transaction to dispatch");
+            }
+        }
+        self.process_events(effects, events).await
+    }
+
+    pub async fn has_transaction_subscribers(&self) -> bool {
+        self.transaction_streamer.has_subscribers().await
+    }
+
     pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = SuiEvent> {
-        self.event_streamer.subscribe(filter)
+        self.event_streamer.subscribe(filter).map(SuiEvent::from)
+    }
+
+    pub fn subscribe_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> impl Stream<Item = SuiTransactionResponse> {
+        self.transaction_streamer.subscribe(filter)
     }
 }
@@ -227,6 +227,7 @@ async fn test_dry_run_transaction() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await
         .unwrap();
@@ -258,7 +259,7 @@ async fn test_dry_run_transaction() {
         txn_data.gas_price(),
     );
     let (response, _, _) = fullnode
-        .dry_exec_transaction(txn_data, transaction_digest)
+        .dry_exec_transaction(txn_data, transaction_digest, false)
         .await
         .unwrap();
     let gas_usage_no_gas = response.effects.gas_cost_summary();
@@ -291,6 +292,7 @@ async fn test_dry_run_no_gas_big_transfer() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
@@ -577,7 +579,7 @@ async fn test_dev_inspect_dynamic_field() {
     };
     let kind = TransactionKind::programmable(pt);
     let DevInspectResults { error, .. } = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false)
         .await
         .unwrap();
     // produces an error
@@ -819,7 +821,7 @@ async fn test_dev_inspect_gas_coin_argument() {
     };
     let kind = TransactionKind::programmable(pt);
     let results = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false)
         .await
         .unwrap()
         .results
@@ -849,6 +851,95 @@ async fn test_dev_inspect_gas_coin_argument() {
     assert!(return_values.is_empty());
 }
 
+#[tokio::test]
+async fn test_dev_inspect_gas_profile() {
+    let (_validator, fullnode, object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![]).await;
+
+    let sender = SuiAddress::random_for_testing_only();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                object_basics.0,
+                Identifier::new("object_basics").unwrap(),
+                Identifier::new("create").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Pure(bcs::to_bytes(&16_u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+                ],
+            )
+            .unwrap();
+        builder.finish()
+    };
+    let kind = TransactionKind::programmable(pt);
+
+    // Profiling is opt-in.
+    let results = fullnode
+        .dev_inspect_transaction(sender, kind.clone(), Some(1), false)
+        .await
+        .unwrap();
+    assert!(results.gas_profile.is_none());
+
+    let results = fullnode
+        .dev_inspect_transaction(sender, kind, Some(1), true)
+        .await
+        .unwrap();
+    let profile = results.gas_profile.unwrap();
+
+    assert_eq!(profile.commands.len(), 1);
+    let command = &profile.commands[0];
+    assert!(command.command.starts_with("0:MoveCall("));
+    assert!(command.command.ends_with("::object_basics::create)"));
+    assert!(command.computation_units > 0);
+
+    // Every frame charged while the command ran is rooted at the command, and the callees of the
+    // entry function get frames of their own. Natives are closed once they return, like the
+    // functions calling them.
+    let command_frames: Vec<_> = profile
+        .frames
+        .iter()
+        .filter(|frame| !frame.stack.is_empty())
+        .collect();
+    let stack = |callees: &[&str]| {
+        std::iter::once(command.command.clone())
+            .chain(callees.iter().map(|callee| format!("0x2::{callee}")))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        command_frames
+            .iter()
+            .map(|frame| frame.stack.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            stack(&[]),
+            stack(&["object::new"]),
+            stack(&["object::new", "tx_context::fresh_object_address"]),
+            stack(&[
+                "object::new",
+                "tx_context::fresh_object_address",
+                "tx_context::derive_id"
+            ]),
+            stack(&["transfer::public_transfer"]),
+            stack(&["transfer::public_transfer", "transfer::transfer_impl"]),
+        ]
+    );
+    assert_eq!(
+        command_frames
+            .iter()
+            .map(|frame| frame.computation_units)
+            .sum::<u64>(),
+        command.computation_units
+    );
+
+    // The created object is charged storage, and nothing is rebated for it.
+    assert!(profile.objects.iter().any(|object| object.bytes_written > 0
+        && object.bytes_deleted == 0
+        && object.storage_cost > 0
+        && object.storage_rebate == 0));
+}
+
 fn check_coin_value(actual_value: &[u8], actual_type: &SuiTypeTag, expected_value: u64) {
     let actual_type: TypeTag = actual_type.clone().try_into().unwrap();
     assert_eq!(actual_type, TypeTag::Struct(Box::new(GasCoin::type_())));
@@ -881,7 +972,7 @@ async fn test_dev_inspect_uses_unbound_object() {
     let kind = TransactionKind::programmable(pt);
 
     let result = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false)
         .await;
     let Err(err) = result else { panic!() };
     assert!(err.to_string().contains("ObjectNotFound"));
@@ -920,6 +1011,7 @@ async fn test_dry_run_on_validator() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await;
     assert!(response.is_err());
@@ -4569,7 +4661,7 @@ pub async fn call_dev_inspect(
     ));
     let kind = TransactionKind::programmable(builder.finish());
     authority
-        .dev_inspect_transaction(*sender, kind, Some(1))
+        .dev_inspect_transaction(*sender, kind, Some(1), false)
         .await
 }
 
@@ -5129,7 +5221,7 @@ async fn test_for_inc_201_dev_inspect() {
     builder.command(Command::Publish(modules, system_package_ids()));
     let kind = TransactionKind::programmable(builder.finish());
     let DevInspectResults { events, .. } = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false)
         .await
         .unwrap();
 
@@ -5169,6 +5261,7 @@ async fn test_for_inc_201_dry_run() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
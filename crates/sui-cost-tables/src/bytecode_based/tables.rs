@@ -16,6 +16,7 @@ use move_vm_types::views::{TypeView, ValueView};
 use once_cell::sync::Lazy;
 
 use crate::bytecode_based::units_types::{CostTable, Gas, GasCost};
+use crate::gas_profiler::GasProfiler;
 use move_binary_format::{
     file_format::{
         Bytecode, ConstantPoolIndex, FieldHandleIndex, FieldInstantiationIndex,
@@ -53,6 +54,7 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    profiler: Option<Box<GasProfiler>>,
 }
 
 impl<'a> GasStatus<'a> {
@@ -65,6 +67,7 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            profiler: None,
         }
     }
 
@@ -81,6 +84,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            profiler: None,
         }
     }
 
@@ -102,16 +106,39 @@ impl<'a> GasStatus<'a> {
 
         match self.gas_left.checked_sub(amount) {
             Some(gas_left) => {
+                self.record_charge(amount);
                 self.gas_left = gas_left;
                 Ok(())
             }
             None => {
+                self.record_charge(self.gas_left);
                 self.gas_left = InternalGas::new(0);
                 Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
             }
         }
     }
 
+    /// Start attributing the gas charged from now on to the frames executing when it is charged.
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(Box::default());
+    }
+
+    /// Return the profiler if profiling is enabled.
+    pub fn profiler_mut(&mut self) -> Option<&mut GasProfiler> {
+        self.profiler.as_deref_mut()
+    }
+
+    /// Stop profiling and return what has been collected so far.
+    pub fn take_profiler(&mut self) -> Option<GasProfiler> {
+        self.profiler.take().map(|profiler| *profiler)
+    }
+
+    fn record_charge(&mut self, amount: InternalGas) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.charge(amount);
+        }
+    }
+
     fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
         self.deduct_gas(
             self.cost_table
@@ -207,24 +234,32 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
 impl<'b> GasMeter for GasStatus<'b> {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
-        self.charge_instr(get_simple_instruction_opcode(instr))
+        self.charge_instr(get_simple_instruction_opcode(instr))?;
+        if let (SimpleInstruction::Ret, Some(profiler)) = (instr, self.profiler_mut()) {
+            profiler.exit_function();
+        }
+        Ok(())
     }
 
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintenance)
-        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())
+        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())?;
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.enter_function(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -233,7 +268,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.charge_instr_with_size(
             Opcodes::CALL_GENERIC,
             ((ty_args.len() + args.len() + 1) as u64).into(),
-        )
+        )?;
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.enter_function(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
@@ -441,7 +480,12 @@ impl<'b> GasMeter for GasStatus<'b> {
         _ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintenance)
-        self.deduct_gas(amount)
+        self.deduct_gas(amount)?;
+        // Natives return without a `Ret`, their frame is closed once they are charged.
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.exit_function();
+        }
+        Ok(())
     }
 
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
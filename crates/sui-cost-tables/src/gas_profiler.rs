@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use move_core_types::gas_algebra::InternalGas;
+use move_core_types::language_storage::ModuleId;
+
+/// Attributes the gas charged by a `GasStatus` to the frames that were executing when it was
+/// charged.
+///
+/// Frames are either opened by the client around a unit of work (e.g. a command of a programmable
+/// transaction) or by the gas meter itself when the Move VM calls a function. Gas is attributed to
+/// the innermost frame only, so the cost of a frame excludes the cost of its callees.
+#[derive(Debug, Default)]
+pub struct GasProfiler {
+    /// Names of the open frames, outermost first.
+    stack: Vec<String>,
+    /// Whether the Move VM opened the frame at the same position in `stack`.
+    opened_by_vm: Vec<bool>,
+    /// Gas charged to each stack of frames, in the order the stacks were first charged.
+    costs: Vec<(Vec<String>, InternalGas)>,
+    positions: HashMap<Vec<String>, usize>,
+}
+
+impl GasProfiler {
+    /// Opens a frame named `name` on top of the current stack.
+    pub fn open_frame(&mut self, name: String) {
+        self.push(name, false);
+    }
+
+    /// Closes the innermost frame opened with `open_frame`, along with any Move function frames
+    /// left open above it, e.g. because the function aborted.
+    pub fn close_frame(&mut self) {
+        while let Some(opened_by_vm) = self.opened_by_vm.pop() {
+            self.stack.pop();
+            if !opened_by_vm {
+                break;
+            }
+        }
+    }
+
+    /// Returns the gas charged to each stack of frames, in the order the stacks were first
+    /// charged. Gas charged while no frame was open is attributed to the empty stack.
+    pub fn into_costs(self) -> Vec<(Vec<String>, InternalGas)> {
+        self.costs
+    }
+
+    pub(crate) fn enter_function(&mut self, module_id: &ModuleId, func_name: &str) {
+        self.push(
+            format!("{}::{}", module_id.short_str_lossless(), func_name),
+            true,
+        );
+    }
+
+    /// Closes the frame of the Move function returning, either through a `Ret` or, for native
+    /// functions, once they have been charged.
+    pub(crate) fn exit_function(&mut self) {
+        // The entry function of a session has no frame of its own, its return must not close the
+        // client frame it runs in.
+        if self.opened_by_vm.last() == Some(&true) {
+            self.opened_by_vm.pop();
+            self.stack.pop();
+        }
+    }
+
+    pub(crate) fn charge(&mut self, amount: InternalGas) {
+        match self.positions.get(self.stack.as_slice()) {
+            Some(position) => {
+                let cost = &mut self.costs[*position].1;
+                *cost = *cost + amount;
+            }
+            None => {
+                self.positions.insert(self.stack.clone(), self.costs.len());
+                self.costs.push((self.stack.clone(), amount));
+            }
+        }
+    }
+
+    fn push(&mut self, name: String, opened_by_vm: bool) {
+        self.stack.push(name);
+        self.opened_by_vm.push(opened_by_vm);
+    }
+}
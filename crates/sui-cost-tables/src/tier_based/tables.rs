@@ -14,6 +14,7 @@ use move_vm_types::loaded_data::runtime_types::Type;
 use move_vm_types::views::{TypeView, ValueView};
 use once_cell::sync::Lazy;
 
+use crate::gas_profiler::GasProfiler;
 use crate::tier_based::units_types::{CostTable, Gas, GasCost};
 
 /// VM flat fee
@@ -66,6 +67,8 @@ pub struct GasStatus<'a> {
     instructions_executed: u64,
     instructions_next_tier_start: Option<u64>,
     instructions_current_tier_mult: u64,
+
+    profiler: Option<Box<GasProfiler>>,
 }
 
 impl<'a> GasStatus<'a> {
@@ -95,6 +98,7 @@ impl<'a> GasStatus<'a> {
             stack_height_next_tier_start,
             stack_size_next_tier_start,
             instructions_next_tier_start,
+            profiler: None,
         }
     }
 
@@ -118,6 +122,7 @@ impl<'a> GasStatus<'a> {
             stack_height_next_tier_start: None,
             stack_size_next_tier_start: None,
             instructions_next_tier_start: None,
+            profiler: None,
         }
     }
 
@@ -252,16 +257,39 @@ impl<'a> GasStatus<'a> {
 
         match self.gas_left.checked_sub(amount) {
             Some(gas_left) => {
+                self.record_charge(amount);
                 self.gas_left = gas_left;
                 Ok(())
             }
             None => {
+                self.record_charge(self.gas_left);
                 self.gas_left = InternalGas::new(0);
                 Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
             }
         }
     }
 
+    /// Start attributing the gas charged from now on to the frames executing when it is charged.
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(Box::default());
+    }
+
+    /// Return the profiler if profiling is enabled.
+    pub fn profiler_mut(&mut self) -> Option<&mut GasProfiler> {
+        self.profiler.as_deref_mut()
+    }
+
+    /// Stop profiling and return what has been collected so far.
+    pub fn take_profiler(&mut self) -> Option<GasProfiler> {
+        self.profiler.take().map(|profiler| *profiler)
+    }
+
+    fn record_charge(&mut self, amount: InternalGas) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.charge(amount);
+        }
+    }
+
     pub fn set_metering(&mut self, enabled: bool) {
         self.charge = enabled
     }
@@ -319,7 +347,11 @@ impl<'b> GasMeter for GasStatus<'b> {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
         let (pops, pushes, pop_size, push_size) = get_simple_instruction_stack_change(instr);
-        self.charge(1, pushes, pops, push_size.into(), pop_size.into())
+        self.charge(1, pushes, pops, push_size.into(), pop_size.into())?;
+        if let (SimpleInstruction::Ret, Some(profiler)) = (instr, self.profiler_mut()) {
+            profiler.exit_function();
+        }
+        Ok(())
     }
 
     fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
@@ -350,7 +382,12 @@ impl<'b> GasMeter for GasStatus<'b> {
         // `charge_native_function_before_execution` call.
         self.charge(0, pushes, 0, size_increase.into(), 0)?;
         // Now charge the gas that the native function told us to charge.
-        self.deduct_gas(amount)
+        self.deduct_gas(amount)?;
+        // Natives return without a `Ret`, their frame is closed once they are charged.
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.exit_function();
+        }
+        Ok(())
     }
 
     fn charge_native_function_before_execution(
@@ -373,8 +410,8 @@ impl<'b> GasMeter for GasStatus<'b> {
 
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
@@ -385,13 +422,17 @@ impl<'b> GasMeter for GasStatus<'b> {
         let stack_reduction_size = args.fold(AbstractMemorySize::new(0), |acc, elem| {
             acc + elem.legacy_abstract_memory_size()
         });
-        self.charge(1, 0, pops, 0, stack_reduction_size.into())
+        self.charge(1, 0, pops, 0, stack_reduction_size.into())?;
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.enter_function(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         _ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -404,7 +445,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         });
         // Charge for the pops, no pushes, and account for the stack size decrease. Also track the
         // `CallGeneric` instruction we must have encountered for this.
-        self.charge(1, 0, pops, 0, stack_reduction_size.into())
+        self.charge(1, 0, pops, 0, stack_reduction_size.into())?;
+        if let Some(profiler) = self.profiler_mut() {
+            profiler.enter_function(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
@@ -0,0 +1,302 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use mysten_metrics::spawn_monitored_task;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::time::{timeout_at, Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{Faucet, FaucetError, FaucetReceipt};
+
+/// How long the status of a request is kept once it has been fulfilled or has failed.
+const STATUS_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(
+    tag = "status",
+    content = "details",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum BatchSendStatus {
+    InProgress,
+    Succeeded(FaucetReceipt),
+    Failed(String),
+}
+
+/// Queues requests for gas, and fulfils them in batches, sending coins to all the recipients of a
+/// batch in a single transaction.
+pub struct BatchQueue {
+    sender: Sender<(Uuid, SuiAddress)>,
+    statuses: Mutex<HashMap<Uuid, (Instant, BatchSendStatus)>>,
+}
+
+impl BatchQueue {
+    /// Starts fulfilling requests with `faucet`, sending coins of `amounts` to each recipient.
+    /// Batches are sent once they reach `max_batch_size` recipients, or `batch_interval` after
+    /// their first request, whichever comes first. At most `capacity` requests can be waiting for
+    /// a batch.
+    pub fn start<F>(
+        faucet: Arc<F>,
+        amounts: Vec<u64>,
+        max_batch_size: usize,
+        batch_interval: Duration,
+        capacity: usize,
+    ) -> Arc<Self>
+    where
+        F: Faucet + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let queue = Arc::new(Self {
+            sender,
+            statuses: Mutex::new(HashMap::new()),
+        });
+        spawn_monitored_task!(queue.clone().run(
+            faucet,
+            receiver,
+            amounts,
+            max_batch_size,
+            batch_interval
+        ));
+        queue
+    }
+
+    /// Queues a request to send coins to `recipient`, whose status can then be polled with `id`.
+    pub fn enqueue(&self, id: Uuid, recipient: SuiAddress) -> Result<(), FaucetError> {
+        self.statuses
+            .lock()
+            .unwrap()
+            .insert(id, (Instant::now(), BatchSendStatus::InProgress));
+        self.sender.try_send((id, recipient)).map_err(|_| {
+            self.statuses.lock().unwrap().remove(&id);
+            FaucetError::TooManyRequests("the batch queue is full".to_string())
+        })
+    }
+
+    pub fn status(&self, id: &Uuid) -> Option<BatchSendStatus> {
+        self.statuses
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|(_, status)| status.clone())
+    }
+
+    async fn run<F: Faucet>(
+        self: Arc<Self>,
+        faucet: Arc<F>,
+        mut receiver: Receiver<(Uuid, SuiAddress)>,
+        amounts: Vec<u64>,
+        max_batch_size: usize,
+        batch_interval: Duration,
+    ) {
+        while let Some(first) = receiver.recv().await {
+            let mut batch = vec![first];
+            let deadline = Instant::now() + batch_interval;
+            while batch.len() < max_batch_size {
+                match timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some(request)) => batch.push(request),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            // The faucet may only serve part of the batch at once, the rest is sent right after.
+            let mut pending = &batch[..];
+            while !pending.is_empty() {
+                let batch_id = Uuid::new_v4();
+                let recipients: Vec<_> = pending.iter().map(|(_, recipient)| *recipient).collect();
+                info!(uuid = ?batch_id, recipients = recipients.len(), "Sending batch");
+                let result = match faucet.batch_send(batch_id, &recipients, &amounts).await {
+                    Ok(receipts) if receipts.is_empty() => Err(FaucetError::Transfer(
+                        "no recipient of the batch could be served".to_string(),
+                    )),
+                    result => result,
+                };
+                let served = match &result {
+                    Ok(receipts) => receipts.len(),
+                    Err(e) => {
+                        warn!(uuid = ?batch_id, "Failed to send batch: {:?}", e);
+                        pending.len()
+                    }
+                };
+
+                self.record(&pending[..served], result);
+                pending = &pending[served..];
+            }
+        }
+    }
+
+    /// Records the outcome of sending `batch`, and forgets the statuses which expired.
+    fn record(
+        &self,
+        batch: &[(Uuid, SuiAddress)],
+        result: Result<Vec<FaucetReceipt>, FaucetError>,
+    ) {
+        let now = Instant::now();
+        let mut statuses = self.statuses.lock().unwrap();
+        match result {
+            Ok(receipts) => {
+                for ((id, _), receipt) in batch.iter().zip(receipts) {
+                    statuses.insert(*id, (now, BatchSendStatus::Succeeded(receipt)));
+                }
+            }
+            Err(e) => {
+                for (id, _) in batch {
+                    statuses.insert(*id, (now, BatchSendStatus::Failed(e.to_string())));
+                }
+            }
+        }
+        statuses.retain(|_, (updated, status)| {
+            matches!(status, BatchSendStatus::InProgress)
+                || now.duration_since(*updated) < STATUS_TTL
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use sui_types::base_types::{ObjectID, TransactionDigest};
+
+    use super::*;
+    use crate::CoinInfo;
+
+    /// Records the batches it is asked to send, failing those including `failing_recipient`, and
+    /// serving at most `max_served` recipients at once.
+    struct MockFaucet {
+        batches: Mutex<Vec<Vec<SuiAddress>>>,
+        failing_recipient: SuiAddress,
+        max_served: usize,
+    }
+
+    #[async_trait]
+    impl Faucet for MockFaucet {
+        async fn send(
+            &self,
+            id: Uuid,
+            recipient: SuiAddress,
+            amounts: &[u64],
+        ) -> Result<FaucetReceipt, FaucetError> {
+            Ok(self
+                .batch_send(id, &[recipient], amounts)
+                .await?
+                .swap_remove(0))
+        }
+
+        async fn batch_send(
+            &self,
+            _id: Uuid,
+            recipients: &[SuiAddress],
+            amounts: &[u64],
+        ) -> Result<Vec<FaucetReceipt>, FaucetError> {
+            self.batches.lock().unwrap().push(recipients.to_vec());
+            if recipients.contains(&self.failing_recipient) {
+                return Err(FaucetError::Transfer("failing recipient".to_string()));
+            }
+            let digest = TransactionDigest::random();
+            Ok(recipients
+                .iter()
+                .take(self.max_served)
+                .map(|_| FaucetReceipt {
+                    sent: amounts
+                        .iter()
+                        .map(|amount| CoinInfo {
+                            amount: *amount,
+                            id: ObjectID::random(),
+                            transfer_tx_digest: digest,
+                        })
+                        .collect(),
+                })
+                .collect())
+        }
+    }
+
+    async fn wait_for_status(queue: &BatchQueue, id: &Uuid) -> BatchSendStatus {
+        loop {
+            match queue.status(id) {
+                Some(BatchSendStatus::InProgress) => {
+                    tokio::time::sleep(Duration::from_millis(10)).await
+                }
+                Some(status) => return status,
+                None => panic!("Unknown request {id}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_queue() {
+        let failing_recipient = SuiAddress::random_for_testing_only();
+        let faucet = Arc::new(MockFaucet {
+            batches: Mutex::new(vec![]),
+            failing_recipient,
+            max_served: usize::MAX,
+        });
+        let queue = BatchQueue::start(
+            faucet.clone(),
+            vec![1, 2],
+            2,
+            Duration::from_millis(100),
+            10,
+        );
+
+        // Three requests make a full batch and a batch sent after the interval.
+        let requests: Vec<_> = (0..3)
+            .map(|_| (Uuid::new_v4(), SuiAddress::random_for_testing_only()))
+            .collect();
+        for (id, recipient) in &requests {
+            queue.enqueue(*id, *recipient).unwrap();
+        }
+        for (id, _) in &requests {
+            match wait_for_status(&queue, id).await {
+                BatchSendStatus::Succeeded(FaucetReceipt { sent }) => {
+                    assert_eq!(sent.iter().map(|c| c.amount).collect::<Vec<_>>(), [1, 2])
+                }
+                status => panic!("Unexpected status {status:?}"),
+            }
+        }
+        assert_eq!(
+            *faucet.batches.lock().unwrap(),
+            [vec![requests[0].1, requests[1].1], vec![requests[2].1]]
+        );
+
+        let id = Uuid::new_v4();
+        queue.enqueue(id, failing_recipient).unwrap();
+        assert!(matches!(
+            wait_for_status(&queue, &id).await,
+            BatchSendStatus::Failed(_)
+        ));
+        assert!(queue.status(&Uuid::new_v4()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_queue_sends_the_rest_of_partially_served_batches() {
+        let faucet = Arc::new(MockFaucet {
+            batches: Mutex::new(vec![]),
+            failing_recipient: SuiAddress::random_for_testing_only(),
+            max_served: 2,
+        });
+        let queue = BatchQueue::start(faucet.clone(), vec![1], 3, Duration::from_millis(100), 10);
+
+        let requests: Vec<_> = (0..3)
+            .map(|_| (Uuid::new_v4(), SuiAddress::random_for_testing_only()))
+            .collect();
+        for (id, recipient) in &requests {
+            queue.enqueue(*id, *recipient).unwrap();
+        }
+        for (id, _) in &requests {
+            assert!(matches!(
+                wait_for_status(&queue, id).await,
+                BatchSendStatus::Succeeded(_)
+            ));
+        }
+        let recipients: Vec<_> = requests.iter().map(|(_, recipient)| *recipient).collect();
+        assert_eq!(
+            *faucet.batches.lock().unwrap(),
+            [recipients.clone(), recipients[2..].to_vec()]
+        );
+    }
+}
@@ -31,6 +31,18 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Transaction rejected by the gas station policy: {0}")]
+    PolicyViolation(String),
+
+    #[error("Gas coin `{0}` is not reserved for a sponsored transaction, its reservation may have expired")]
+    UnknownReservation(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -7,7 +7,7 @@ use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
 mod simple_faucet;
-mod write_ahead_log;
+pub(crate) mod write_ahead_log;
 pub use self::simple_faucet::SimpleFaucet;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -32,4 +32,15 @@ pub trait Faucet {
         recipient: SuiAddress,
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// Send `Coin<SUI>` of the specified amounts to each of the first recipients, in a single
+    /// transaction. A batch may be larger than a coin of the faucet can cover, only the first
+    /// recipients it covers are then served. Returns what was sent to each recipient served, in
+    /// the order of `recipients`.
+    async fn batch_send(
+        &self,
+        id: Uuid,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+    ) -> Result<Vec<FaucetReceipt>, FaucetError>;
 }
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use prometheus::Registry;
 use tap::tap::TapFallible;
 
+use std::collections::HashMap;
 #[cfg(test)]
 use std::collections::HashSet;
 use std::default::Default;
@@ -51,7 +52,8 @@ enum GasCoinResponse {
     InvalidGasCoin(ObjectID),
     NoGasCoinAvailable,
     UnknownGasCoin(ObjectID),
-    ValidGasCoin(ObjectID),
+    /// A coin fit for use, with its balance.
+    ValidGasCoin(ObjectID, u64),
 }
 
 // TODO: replace this with dryrun at the SDK level
@@ -165,7 +167,7 @@ impl SimpleFaucet {
         match self.get_gas_coin_and_check_faucet_owner(coin_id).await {
             Ok(Some(gas_coin)) if gas_coin.value() >= total_amount => {
                 info!(?uuid, ?coin_id, "balance: {}", gas_coin.value());
-                GasCoinResponse::ValidGasCoin(coin_id)
+                GasCoinResponse::ValidGasCoin(coin_id, gas_coin.value())
             }
 
             Ok(Some(_)) => GasCoinResponse::GasCoinWithInsufficientBalance(coin_id),
@@ -291,21 +293,34 @@ impl SimpleFaucet {
         }
     }
 
+    /// Sends coins of `amounts` to each of the first `recipients` in a single transaction, as many
+    /// of them as one coin of the pool covers, and returns the IDs of the coins sent to each
+    /// recipient served, in the order of `recipients`.
     async fn transfer_gases(
         &self,
         amounts: &[u64],
-        recipient: SuiAddress,
+        recipients: &[SuiAddress],
         uuid: Uuid,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+    ) -> Result<(TransactionDigest, Vec<Vec<ObjectID>>), FaucetError> {
         let number_of_coins = amounts.len();
-        let total_amount: u64 = amounts.iter().sum();
-        let gas_cost = self.get_gas_cost().await?;
-
-        let gas_coin_response = self.prepare_gas_coin(total_amount + gas_cost, uuid).await;
+        let amount_per_recipient = amounts.iter().sum::<u64>();
+        let gas_cost_per_recipient = self.get_gas_cost().await?;
+        // The WAL only records one recipient per transaction, for logging purposes.
+        let recipient = recipients[0];
+
+        // The coin must cover at least one recipient, the batch is capped by its balance.
+        let gas_coin_response = self
+            .prepare_gas_coin(amount_per_recipient + gas_cost_per_recipient, uuid)
+            .await;
         match gas_coin_response {
-            GasCoinResponse::ValidGasCoin(coin_id) => {
+            GasCoinResponse::ValidGasCoin(coin_id, balance) => {
+                let served = recipients
+                    .len()
+                    .min((balance / (amount_per_recipient + gas_cost_per_recipient)) as usize);
+                let recipients = &recipients[..served];
+                let gas_cost = gas_cost_per_recipient * served as u64;
                 let tx_data = self
-                    .build_pay_sui_txn(coin_id, self.active_address, recipient, amounts, gas_cost)
+                    .build_pay_sui_txn(coin_id, self.active_address, recipients, amounts, gas_cost)
                     .await
                     .map_err(FaucetError::internal)?;
 
@@ -321,7 +336,7 @@ impl SimpleFaucet {
                     .sign_and_execute_txn(uuid, recipient, coin_id, tx_data)
                     .await?;
 
-                self.check_and_map_transfer_gas_result(response, number_of_coins, recipient)
+                self.check_and_map_transfer_gas_result(response, number_of_coins, recipients)
                     .await
             }
 
@@ -450,16 +465,18 @@ impl SimpleFaucet {
         &self,
         coin_id: ObjectID,
         signer: SuiAddress,
-        recipient: SuiAddress,
+        recipients: &[SuiAddress],
         amounts: &[u64],
         budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let recipients: Vec<SuiAddress> =
-            std::iter::repeat(recipient).take(amounts.len()).collect();
+        let (recipients, amounts): (Vec<SuiAddress>, Vec<u64>) = recipients
+            .iter()
+            .flat_map(|recipient| amounts.iter().map(|amount| (*recipient, *amount)))
+            .unzip();
         let client = self.wallet.get_client().await?;
         client
             .transaction_builder()
-            .pay_sui(signer, vec![coin_id], recipients, amounts.to_vec(), budget)
+            .pay_sui(signer, vec![coin_id], recipients, amounts, budget)
             .await
             .map_err(|e| {
                 anyhow::anyhow!(
@@ -474,8 +491,8 @@ impl SimpleFaucet {
         &self,
         res: SuiTransactionResponse,
         number_of_coins: usize,
-        recipient: SuiAddress,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        recipients: &[SuiAddress],
+    ) -> Result<(TransactionDigest, Vec<Vec<ObjectID>>), FaucetError> {
         let created = res
             .effects
             .ok_or_else(|| {
@@ -486,22 +503,71 @@ impl SimpleFaucet {
             })?
             .created()
             .to_vec();
-        if created.len() != number_of_coins {
+        if created.len() != number_of_coins * recipients.len() {
             panic!(
                 "PaySui Transaction should create exact {:?} new coins, but got {:?}",
-                number_of_coins, created
+                number_of_coins * recipients.len(),
+                created
             );
         }
-        assert!(created
-            .iter()
-            .all(|created_coin_owner_ref| created_coin_owner_ref.owner == recipient));
-        let coin_ids: Vec<ObjectID> = created
+        let mut created_by_owner: HashMap<SuiAddress, Vec<ObjectID>> = HashMap::new();
+        for created_coin_owner_ref in &created {
+            match created_coin_owner_ref.owner {
+                Owner::AddressOwner(owner) => created_by_owner
+                    .entry(owner)
+                    .or_default()
+                    .push(created_coin_owner_ref.reference.object_id),
+                owner => panic!("PaySui Transaction created a coin owned by {owner}"),
+            }
+        }
+        // A recipient may appear several times in a batch, each time receiving `number_of_coins`.
+        let coin_ids = recipients
             .iter()
-            .map(|created_coin_owner_ref| created_coin_owner_ref.reference.object_id)
+            .map(|recipient| {
+                let coins = created_by_owner.entry(*recipient).or_default();
+                assert!(
+                    coins.len() >= number_of_coins,
+                    "PaySui Transaction should send {number_of_coins} coins to {recipient}"
+                );
+                coins.split_off(coins.len() - number_of_coins)
+            })
             .collect();
         Ok((res.digest, coin_ids))
     }
 
+    /// Looks up the amounts of the coins sent to `recipient` by transaction `digest`.
+    async fn receipt(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        digest: TransactionDigest,
+        coin_ids: Vec<ObjectID>,
+    ) -> FaucetReceipt {
+        let mut sent = Vec::with_capacity(coin_ids.len());
+        let coin_results =
+            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
+        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
+            let amount = if let Ok(Some((_, coin))) = res {
+                coin.value()
+            } else {
+                info!(
+                    ?recipient,
+                    ?coin_id,
+                    uuid = ?id,
+                    "Could not find coin after successful transaction, error: {:?}",
+                    &res,
+                );
+                0
+            };
+            sent.push(CoinInfo {
+                transfer_tx_digest: digest,
+                amount,
+                id: coin_id,
+            });
+        }
+        FaucetReceipt { sent }
+    }
+
     #[cfg(test)]
     async fn drain_gas_queue(&mut self, expected_gas_count: usize) -> HashSet<ObjectID> {
         use tokio::sync::mpsc::error::TryRecvError;
@@ -538,32 +604,40 @@ impl Faucet for SimpleFaucet {
     ) -> Result<FaucetReceipt, FaucetError> {
         info!(?recipient, uuid = ?id, "Getting faucet requests");
 
-        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
+        let (digest, mut coin_ids) = self.transfer_gases(amounts, &[recipient], id).await?;
 
         info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
-        let mut sent = Vec::with_capacity(coin_ids.len());
-        let coin_results =
-            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
-        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
-            let amount = if let Ok(Some((_, coin))) = res {
-                coin.value()
-            } else {
-                info!(
-                    ?recipient,
-                    ?coin_id,
-                    uuid = ?id,
-                    "Could not find coin after successful transaction, error: {:?}",
-                    &res,
-                );
-                0
-            };
-            sent.push(CoinInfo {
-                transfer_tx_digest: digest,
-                amount,
-                id: coin_id,
-            });
+        Ok(self
+            .receipt(id, recipient, digest, coin_ids.swap_remove(0))
+            .await)
+    }
+
+    async fn batch_send(
+        &self,
+        id: Uuid,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+    ) -> Result<Vec<FaucetReceipt>, FaucetError> {
+        info!(recipients = recipients.len(), uuid = ?id, "Getting batch faucet requests");
+        if recipients.is_empty() {
+            return Ok(vec![]);
         }
-        Ok(FaucetReceipt { sent })
+
+        let (digest, coin_ids) = self.transfer_gases(amounts, recipients, id).await?;
+
+        info!(
+            uuid = ?id,
+            ?digest,
+            served = coin_ids.len(),
+            "Batch PaySui txn succeeded"
+        );
+        Ok(futures::future::join_all(
+            recipients
+                .iter()
+                .zip(coin_ids)
+                .map(|(recipient, coin_ids)| self.receipt(id, *recipient, digest, coin_ids)),
+        )
+        .await)
     }
 }
 
@@ -684,6 +758,7 @@ mod tests {
             input_coins: vec![*bad_gas.id()],
             recipient: SuiAddress::random_for_testing_only(),
             gas_budget: 50000,
+            serialize_output: false,
         }
         .execute(faucet.wallet_mut())
         .await
@@ -803,6 +878,38 @@ mod tests {
         assert!(candidates.get(&tiny_coin_id).is_none());
     }
 
+    #[tokio::test]
+    async fn test_batch_send() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+        )
+        .await
+        .unwrap();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let recipients = vec![recipient, SuiAddress::random_for_testing_only(), recipient];
+        let amounts = vec![1, 2];
+        let receipts = faucet
+            .batch_send(Uuid::new_v4(), &recipients, &amounts)
+            .await
+            .unwrap();
+
+        // All recipients are served by the same transaction, with the coins they asked for.
+        assert_eq!(receipts.len(), recipients.len());
+        let digest = receipts[0].sent[0].transfer_tx_digest;
+        for FaucetReceipt { sent } in &receipts {
+            let mut actual_amounts: Vec<u64> = sent.iter().map(|c| c.amount).collect();
+            actual_amounts.sort_unstable();
+            assert_eq!(actual_amounts, amounts);
+            assert!(sent.iter().all(|c| c.transfer_tx_digest == digest));
+        }
+    }
+
     async fn test_basic_interface(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
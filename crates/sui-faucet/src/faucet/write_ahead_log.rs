@@ -5,6 +5,7 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::SuiAddress;
+use sui_types::signature::GenericSignature;
 use sui_types::{base_types::ObjectID, messages::TransactionData};
 use typed_store::rocks::{DBMap, TypedStoreError};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
@@ -20,9 +21,13 @@ use uuid::Uuid;
 ///
 /// This allows the faucet to go down and back up, and not forget which requests were in-flight that
 /// it needs to confirm succeeded or failed.
+///
+/// The gas station keeps its sponsored transactions in a separate table, from the moment it signs
+/// them until they have been executed or their reservation of the coin has expired.
 #[derive(DBMapUtils)]
 pub struct WriteAheadLog {
     log: DBMap<ObjectID, Entry>,
+    sponsored: DBMap<ObjectID, SponsoredEntry>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -32,6 +37,14 @@ pub struct Entry {
     pub tx: TransactionData,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SponsoredEntry {
+    pub uuid: uuid::Bytes,
+    pub tx: TransactionData,
+    /// Signature of the sender, set once the gas station has been asked to execute `tx`.
+    pub sender_signature: Option<GenericSignature>,
+}
+
 impl WriteAheadLog {
     pub(crate) fn open(path: &Path) -> Self {
         Self::open_tables_read_write(
@@ -82,11 +95,69 @@ impl WriteAheadLog {
     pub(crate) fn commit(&mut self, coin: ObjectID) -> Result<(), TypedStoreError> {
         self.log.remove(&coin)
     }
+
+    /// Mark `coin` as reserved for the sponsored transaction `tx`. Fails if `coin` is already
+    /// reserved for another sponsored transaction.
+    pub(crate) fn reserve_sponsored(
+        &mut self,
+        uuid: Uuid,
+        coin: ObjectID,
+        tx: TransactionData,
+    ) -> Result<(), TypedStoreError> {
+        if self.sponsored.contains_key(&coin)? {
+            return Err(TypedStoreError::SerializationError(format!(
+                "Duplicate WAL entry for sponsored coin {coin:?}",
+            )));
+        }
+
+        let uuid = *uuid.as_bytes();
+        self.sponsored.insert(
+            &coin,
+            &SponsoredEntry {
+                uuid,
+                tx,
+                sender_signature: None,
+            },
+        )
+    }
+
+    /// Record the signature of the sender of the sponsored transaction reserving `coin`, before
+    /// executing it. Fails if `coin` is not reserved.
+    pub(crate) fn execute_sponsored(
+        &mut self,
+        coin: ObjectID,
+        sender_signature: GenericSignature,
+    ) -> Result<(), TypedStoreError> {
+        let Some(mut entry) = self.sponsored.get(&coin)? else {
+            return Err(TypedStoreError::SerializationError(format!(
+                "No WAL entry for sponsored coin {coin:?}",
+            )));
+        };
+
+        entry.sender_signature = Some(sender_signature);
+        self.sponsored.insert(&coin, &entry)
+    }
+
+    /// Check whether `coin` is reserved for a sponsored transaction, see [Self::reclaim].
+    pub(crate) fn reclaim_sponsored(
+        &self,
+        coin: ObjectID,
+    ) -> Result<Option<SponsoredEntry>, TypedStoreError> {
+        self.sponsored.get(&coin)
+    }
+
+    /// Release `coin` from its sponsored transaction, once it has landed or its reservation has
+    /// expired.
+    pub(crate) fn commit_sponsored(&mut self, coin: ObjectID) -> Result<(), TypedStoreError> {
+        self.sponsored.remove(&coin)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use shared_crypto::intent::{Intent, IntentMessage};
     use sui_types::base_types::{random_object_ref, ObjectRef};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair, Signature};
 
     use super::*;
 
@@ -187,6 +258,46 @@ mod tests {
         wal.reserve(uuid, coin.0, recv1, tx1).unwrap();
     }
 
+    #[tokio::test]
+    async fn sponsored_reserve_execute_commit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let uuid = Uuid::new_v4();
+        let coin = random_object_ref();
+        let (recv, tx) = random_request(coin);
+
+        // Sponsored transactions do not conflict with the faucet's own.
+        wal.reserve(uuid, coin.0, recv, tx.clone()).unwrap();
+        wal.reserve_sponsored(uuid, coin.0, tx.clone()).unwrap();
+        assert!(matches!(
+            wal.reserve_sponsored(uuid, coin.0, tx.clone()),
+            Err(TypedStoreError::SerializationError(_)),
+        ));
+
+        let Some(entry) = wal.reclaim_sponsored(coin.0).unwrap() else {
+            panic!("Entry not found for {}", coin.0);
+        };
+        assert_eq!(uuid, Uuid::from_bytes(entry.uuid));
+        assert_eq!(tx, entry.tx);
+        assert_eq!(None, entry.sender_signature);
+
+        let signature: GenericSignature = Signature::new_secure(
+            &IntentMessage::new(Intent::default(), &tx),
+            &get_key_pair::<AccountKeyPair>().1,
+        )
+        .into();
+        wal.execute_sponsored(coin.0, signature.clone()).unwrap();
+        let Some(entry) = wal.reclaim_sponsored(coin.0).unwrap() else {
+            panic!("Entry not found for {}", coin.0);
+        };
+        assert_eq!(Some(signature), entry.sender_signature);
+
+        wal.commit_sponsored(coin.0).unwrap();
+        assert_eq!(Ok(None), wal.reclaim_sponsored(coin.0));
+        assert!(wal.reclaim(coin.0).unwrap().is_some());
+    }
+
     fn random_request(coin: ObjectRef) -> (SuiAddress, TransactionData) {
         let send = SuiAddress::random_for_testing_only();
         let recv = SuiAddress::random_for_testing_only();
@@ -0,0 +1,952 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::faucet::write_ahead_log::{SponsoredEntry, WriteAheadLog};
+use crate::metrics::FaucetMetrics;
+use crate::FaucetError;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use mysten_metrics::spawn_monitored_task;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tap::tap::TapFallible;
+
+use shared_crypto::intent::Intent;
+use sui::client_commands::WalletContext;
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiObjectDataOptions, SuiTransactionEffectsAPI, SuiTransactionResponse,
+    SuiTransactionResponseOptions,
+};
+use sui_keys::keystore::AccountKeystore;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::Signature;
+use sui_types::gas_coin::GasCoin;
+use sui_types::messages::{
+    ExecuteTransactionRequestType, Transaction, TransactionData, TransactionDataAPI,
+    VerifiedTransaction,
+};
+use sui_types::object::Owner;
+use sui_types::signature::GenericSignature;
+use tokio::sync::{
+    mpsc::{self, Receiver, Sender},
+    Mutex,
+};
+use tokio::time::{timeout, Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+mod policy;
+pub use self::policy::{AllowedMoveCall, GasStationPolicy};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsoredTransaction {
+    /// BCS serialized `TransactionData`, paying for gas with a coin of the gas station
+    pub tx_bytes: Base64,
+    /// Signature of the gas station over `tx_bytes`
+    pub sponsor_signature: Base64,
+    /// How long the gas coin stays reserved for the transaction
+    pub reserved_for_secs: u64,
+}
+
+/// Sponsors the transactions of clients, paying for their gas with coins from a pool owned by the
+/// active address of the wallet, within the limits of a [GasStationPolicy].
+///
+/// Once a transaction is sponsored, its coin is reserved for it until it is executed through the
+/// gas station or the reservation expires, and only then goes back to the pool. Reservations and
+/// executions in flight are kept in the same write-ahead log as the faucet's transfers, so that
+/// they survive restarts.
+pub struct GasStation {
+    wallet: WalletContext,
+    active_address: SuiAddress,
+    policy: GasStationPolicy,
+    producer: Mutex<Sender<ObjectID>>,
+    consumer: Mutex<Receiver<ObjectID>>,
+    metrics: FaucetMetrics,
+    wal: Mutex<WriteAheadLog>,
+    /// Coins reserved for sponsored transactions which are not being executed yet, the ID of the
+    /// request they were reserved by, and the IP address of its client if known.
+    reservations: std::sync::Mutex<HashMap<ObjectID, (Uuid, Option<IpAddr>)>>,
+    /// Number of coins reserved for the clients of each IP address, including reservations being
+    /// made.
+    reservations_per_ip: std::sync::Mutex<HashMap<IpAddr, usize>>,
+    /// Start of the current budget window of each sender, and the gas budget sponsored since.
+    budgets: std::sync::Mutex<HashMap<SuiAddress, (Instant, u64)>>,
+}
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+const EXECUTION_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl GasStation {
+    pub async fn new(
+        mut wallet: WalletContext,
+        prometheus_registry: &Registry,
+        wal_path: &Path,
+        policy: GasStationPolicy,
+    ) -> Result<Arc<Self>, FaucetError> {
+        let active_address = wallet
+            .active_address()
+            .map_err(|err| FaucetError::Wallet(err.to_string()))?;
+        info!("GasStation::new with active address: {active_address}");
+
+        let coins = wallet
+            .gas_objects(active_address)
+            .await
+            .map_err(|e| FaucetError::Wallet(e.to_string()))?
+            .iter()
+            // Ok to unwrap() since `get_gas_objects` guarantees gas
+            .map(|q| GasCoin::try_from(&q.1).unwrap())
+            .collect::<Vec<GasCoin>>();
+
+        let metrics = FaucetMetrics::new(prometheus_registry);
+
+        let wal = WriteAheadLog::open(wal_path);
+        let mut reserved = vec![];
+        let mut pending = vec![];
+
+        let (producer, consumer) = mpsc::channel(coins.len());
+        for coin in &coins {
+            let coin_id = *coin.id();
+            let entry = wal
+                .reclaim_sponsored(coin_id)
+                .map_err(FaucetError::internal)?;
+            match entry {
+                Some(SponsoredEntry {
+                    uuid,
+                    tx,
+                    sender_signature: Some(sender_signature),
+                }) => {
+                    let uuid = Uuid::from_bytes(uuid);
+                    info!(?uuid, ?coin_id, "Retrying sponsored txn from WAL.");
+                    pending.push((uuid, coin_id, tx, sender_signature));
+                }
+                Some(SponsoredEntry { uuid, .. }) => {
+                    let uuid = Uuid::from_bytes(uuid);
+                    info!(?uuid, ?coin_id, "Restoring gas coin reservation from WAL.");
+                    reserved.push((uuid, coin_id));
+                }
+                None => {
+                    producer
+                        .send(coin_id)
+                        .await
+                        .tap_ok(|_| {
+                            info!(?coin_id, "Adding coin to gas pool");
+                            metrics.total_available_coins.inc();
+                        })
+                        .tap_err(|e| error!(?coin_id, "Failed to add coin to gas pools: {e:?}"))
+                        .unwrap();
+                }
+            }
+        }
+
+        let station = Arc::new(Self {
+            wallet,
+            active_address,
+            policy,
+            producer: Mutex::new(producer),
+            consumer: Mutex::new(consumer),
+            metrics,
+            wal: Mutex::new(wal),
+            reservations: Default::default(),
+            reservations_per_ip: Default::default(),
+            budgets: Default::default(),
+        });
+
+        // Senders may still be about to execute the transactions sponsored before the restart, so
+        // their coins stay reserved for another full reservation timeout.
+        for (uuid, coin_id) in reserved {
+            station.reserve_gas_coin(uuid, coin_id, None);
+        }
+
+        // As with the faucet, ignore the results of the pending executions -- if they failed, the
+        // pending coins will simply remain in the WAL, and not be recycled.
+        futures::future::join_all(pending.into_iter().map(
+            |(uuid, coin_id, tx, sender_signature)| {
+                let station = station.clone();
+                async move {
+                    let tx = station.sign_sponsored(tx, sender_signature)?;
+                    station.execute_txn(uuid, coin_id, tx).await
+                }
+            },
+        ))
+        .await;
+
+        Ok(station)
+    }
+
+    /// Sponsors the transaction serialized in `tx_bytes`, replacing its gas payment with a coin
+    /// from the pool, and signs it on behalf of the gas station. The coin stays reserved for the
+    /// transaction until it is executed through [Self::execute], or the reservation times out.
+    ///
+    /// Only transactions passing the policy and succeeding in a dry run are sponsored, and their
+    /// gas budget is charged to the budget of their sender. Clients from `client_ip` can only hold
+    /// a limited number of reservations at once.
+    pub async fn sponsor(
+        self: &Arc<Self>,
+        uuid: Uuid,
+        tx_bytes: &Base64,
+        client_ip: IpAddr,
+    ) -> Result<SponsoredTransaction, FaucetError> {
+        self.acquire_reservation_slot(client_ip)?;
+        let result = self.sponsor_for(uuid, tx_bytes, client_ip).await;
+        if result.is_err() {
+            self.release_reservation_slot(Some(client_ip));
+        }
+        result
+    }
+
+    async fn sponsor_for(
+        self: &Arc<Self>,
+        uuid: Uuid,
+        tx_bytes: &Base64,
+        client_ip: IpAddr,
+    ) -> Result<SponsoredTransaction, FaucetError> {
+        let tx = decode_transaction(tx_bytes)?;
+        self.policy
+            .check(&tx, self.active_address)
+            .map_err(FaucetError::PolicyViolation)?;
+        let sender = tx.sender();
+        let gas_budget = tx.gas_budget();
+        info!(?uuid, ?sender, gas_budget, "Sponsoring transaction");
+
+        let gas_price = self.get_gas_price().await?;
+        let coin_ref = self.prepare_gas_coin(uuid).await?;
+        let coin_id = coin_ref.0;
+        let tx = TransactionData::new_with_gas_coins_allow_sponsor(
+            tx.into_kind(),
+            sender,
+            vec![coin_ref],
+            gas_budget,
+            gas_price,
+            self.active_address,
+        );
+
+        // The sponsor pays for the gas of failed transactions too.
+        if let Err(e) = self.dry_run(&tx).await {
+            self.recycle_gas_coin(coin_id, uuid).await;
+            return Err(e);
+        }
+        // Sign before reserving the coin, so that a reservation is only made for a transaction
+        // handed out to the client.
+        let sponsored = match self.sponsored_transaction(&tx) {
+            Ok(sponsored) => sponsored,
+            Err(e) => {
+                self.recycle_gas_coin(coin_id, uuid).await;
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.charge_sender(sender, gas_budget) {
+            self.recycle_gas_coin(coin_id, uuid).await;
+            return Err(e);
+        }
+
+        // Register the reservation before handing out the signature, so that the coin is not
+        // reused for another transaction before this one had a chance to be executed, even if the
+        // gas station restarts in the meantime.
+        let reserved = self
+            .wal
+            .lock()
+            .await
+            .reserve_sponsored(uuid, coin_id, tx.clone());
+        if let Err(e) = reserved {
+            self.refund_sender(sender, gas_budget);
+            self.recycle_gas_coin(coin_id, uuid).await;
+            return Err(FaucetError::internal(e));
+        }
+        self.reserve_gas_coin(uuid, coin_id, Some(client_ip));
+
+        info!(?uuid, ?sender, ?coin_id, "Sponsored transaction");
+        Ok(sponsored)
+    }
+
+    /// Serializes `tx` and signs it on behalf of the gas station, for the client to sign and
+    /// execute it.
+    fn sponsored_transaction(
+        &self,
+        tx: &TransactionData,
+    ) -> Result<SponsoredTransaction, FaucetError> {
+        let signature = self.sign(tx)?;
+        Ok(SponsoredTransaction {
+            tx_bytes: Base64::from_bytes(&bcs::to_bytes(tx).map_err(FaucetError::internal)?),
+            sponsor_signature: Base64::from_bytes(signature.as_ref()),
+            reserved_for_secs: self.policy.reservation_timeout.as_secs(),
+        })
+    }
+
+    /// Executes a transaction sponsored by [Self::sponsor], signed by its sender, and recycles its
+    /// gas coin once fullnode returns a definite response.
+    pub async fn execute(
+        self: &Arc<Self>,
+        tx_bytes: &Base64,
+        sender_signature: &Base64,
+    ) -> Result<SuiTransactionResponse, FaucetError> {
+        let tx = decode_transaction(tx_bytes)?;
+        let sender_signature = sender_signature
+            .to_vec()
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| GenericSignature::from_bytes(&bytes).map_err(|e| e.to_string()))
+            .map_err(|e| FaucetError::InvalidRequest(format!("Invalid sender signature: {e}")))?;
+        let coin_id = match tx.gas() {
+            [(coin_id, _, _)] => *coin_id,
+            _ => {
+                return Err(FaucetError::InvalidRequest(
+                    "Sponsored transactions pay for gas with a single coin".to_string(),
+                ))
+            }
+        };
+
+        // Taking the reservation makes sure the coin is neither released nor used by another
+        // execution while this one is in flight.
+        let (uuid, client_ip) = self
+            .reservations
+            .lock()
+            .unwrap()
+            .remove(&coin_id)
+            .ok_or_else(|| FaucetError::UnknownReservation(coin_id.to_hex_uncompressed()))?;
+        let tx = match self.start_execution(coin_id, tx, sender_signature).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                // The expiry of the reservation may have passed in the meantime, re-arm it.
+                self.reserve_gas_coin(uuid, coin_id, client_ip);
+                return Err(e);
+            }
+        };
+        self.release_reservation_slot(client_ip);
+
+        self.execute_txn(uuid, coin_id, tx).await
+    }
+
+    /// Checks that `tx` is the transaction sponsored with `coin_id` and signs it, then records the
+    /// signature of its sender in the WAL, for the execution to be retried after a restart.
+    async fn start_execution(
+        &self,
+        coin_id: ObjectID,
+        tx: TransactionData,
+        sender_signature: GenericSignature,
+    ) -> Result<VerifiedTransaction, FaucetError> {
+        let mut wal = self.wal.lock().await;
+        let entry = wal
+            .reclaim_sponsored(coin_id)
+            .map_err(FaucetError::internal)?;
+        match entry {
+            Some(entry) if entry.tx == tx => {}
+            _ => {
+                return Err(FaucetError::InvalidRequest(format!(
+                    "Transaction is not the one sponsored with gas coin {coin_id}"
+                )))
+            }
+        }
+
+        let verified = self.sign_sponsored(tx, sender_signature.clone())?;
+        wal.execute_sponsored(coin_id, sender_signature)
+            .map_err(FaucetError::internal)?;
+        Ok(verified)
+    }
+
+    /// Marks `coin_id` as reserved by the request `uuid` of a client from `client_ip`, and releases
+    /// it back to the pool once the reservation times out, unless the sponsored transaction is
+    /// being executed by then.
+    fn reserve_gas_coin(
+        self: &Arc<Self>,
+        uuid: Uuid,
+        coin_id: ObjectID,
+        client_ip: Option<IpAddr>,
+    ) {
+        self.reservations
+            .lock()
+            .unwrap()
+            .insert(coin_id, (uuid, client_ip));
+
+        let station = self.clone();
+        spawn_monitored_task!(async move {
+            tokio::time::sleep(station.policy.reservation_timeout).await;
+            let expired = {
+                let mut reservations = station.reservations.lock().unwrap();
+                match reservations.get(&coin_id) {
+                    Some((reserved_by, _)) if *reserved_by == uuid => reservations.remove(&coin_id),
+                    _ => None,
+                }
+            };
+
+            if let Some((_, client_ip)) = expired {
+                station.release_reservation_slot(client_ip);
+                info!(?uuid, ?coin_id, "Gas coin reservation expired");
+                let committed = station.wal.lock().await.commit_sponsored(coin_id);
+                if committed.is_err() {
+                    error!(?coin_id, "Failed to remove coin from WAL");
+                }
+                station.recycle_gas_coin(coin_id, uuid).await;
+            }
+        });
+    }
+
+    /// Counts a reservation against the limit of the clients from `client_ip`.
+    fn acquire_reservation_slot(&self, client_ip: IpAddr) -> Result<(), FaucetError> {
+        let mut reservations_per_ip = self.reservations_per_ip.lock().unwrap();
+        let reservations = reservations_per_ip.entry(client_ip).or_default();
+        if *reservations >= self.policy.max_reservations_per_ip {
+            return Err(FaucetError::TooManyRequests(format!(
+                "{client_ip} already holds {} gas coin reservations",
+                reservations
+            )));
+        }
+        *reservations += 1;
+        Ok(())
+    }
+
+    /// Stops counting a reservation against the limit of the clients from `client_ip`, if known.
+    fn release_reservation_slot(&self, client_ip: Option<IpAddr>) {
+        if let Some(client_ip) = client_ip {
+            let mut reservations_per_ip = self.reservations_per_ip.lock().unwrap();
+            if let Some(reservations) = reservations_per_ip.get_mut(&client_ip) {
+                *reservations -= 1;
+                if *reservations == 0 {
+                    reservations_per_ip.remove(&client_ip);
+                }
+            }
+        }
+    }
+
+    /// Adds `gas_budget` to the gas budget sponsored for `sender` in its current budget window, if
+    /// that stays within the policy's per address budget.
+    fn charge_sender(&self, sender: SuiAddress, gas_budget: u64) -> Result<(), FaucetError> {
+        let mut budgets = self.budgets.lock().unwrap();
+        let now = Instant::now();
+        let (window_start, spent) = budgets.entry(sender).or_insert((now, 0));
+        if now.duration_since(*window_start) >= self.policy.budget_window {
+            *window_start = now;
+            *spent = 0;
+        }
+
+        if *spent + gas_budget > self.policy.per_address_budget {
+            return Err(FaucetError::PolicyViolation(format!(
+                "sender {sender} has {} gas budget left out of {}",
+                self.policy.per_address_budget - *spent,
+                self.policy.per_address_budget
+            )));
+        }
+        *spent += gas_budget;
+        Ok(())
+    }
+
+    /// Gives back `gas_budget` charged to `sender` for a transaction which was not sponsored after
+    /// all.
+    fn refund_sender(&self, sender: SuiAddress, gas_budget: u64) {
+        if let Some((_, spent)) = self.budgets.lock().unwrap().get_mut(&sender) {
+            *spent = spent.saturating_sub(gas_budget);
+        }
+    }
+
+    /// Take the consumer lock and pull a Coin ID from the queue, without checking whether it is
+    /// valid or not.
+    async fn pop_gas_coin(&self, uuid: Uuid) -> Option<ObjectID> {
+        let mut consumer = match timeout(LOCK_TIMEOUT, self.consumer.lock()).await {
+            Ok(consumer) => consumer,
+            Err(_) => {
+                error!(?uuid, "Timeout when getting consumer lock");
+                return None;
+            }
+        };
+
+        info!(?uuid, "Got consumer lock, pulling coins.");
+        let coin = match timeout(RECV_TIMEOUT, consumer.recv()).await {
+            Ok(coin) => coin.expect("channel is closed"),
+            Err(_) => {
+                error!(?uuid, "Timeout when getting gas coin from the queue");
+                return None;
+            }
+        };
+
+        self.metrics.total_available_coins.dec();
+        Some(coin)
+    }
+
+    /// Pulls a coin from the queue and makes sure it still belongs to the gas station and can pay
+    /// for the largest gas budget allowed by the policy. Coins which do not are removed from the
+    /// pool.
+    async fn prepare_gas_coin(&self, uuid: Uuid) -> Result<ObjectRef, FaucetError> {
+        let coin_id = self
+            .pop_gas_coin(uuid)
+            .await
+            .ok_or(FaucetError::NoGasCoinAvailable)?;
+
+        match self.get_gas_coin(coin_id).await {
+            Ok(Some((coin_ref, coin))) if coin.value() >= self.policy.max_gas_budget => {
+                info!(?uuid, ?coin_id, "balance: {}", coin.value());
+                Ok(coin_ref)
+            }
+
+            Ok(Some(_)) => {
+                warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                Err(FaucetError::GasCoinWithInsufficientBalance(
+                    coin_id.to_hex_uncompressed(),
+                ))
+            }
+
+            Ok(None) => {
+                // The coin does not exist, or does not belong to the gas station.
+                warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                Err(FaucetError::InvalidGasCoin(coin_id.to_hex_uncompressed()))
+            }
+
+            Err(e) => {
+                error!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                self.recycle_gas_coin(coin_id, uuid).await;
+                Err(FaucetError::FullnodeReadingError(format!(
+                    "unknown gas coin {coin_id:?}"
+                )))
+            }
+        }
+    }
+
+    /// Returns the latest reference of gas coin `coin_id` and its balance, if it still exists and
+    /// belongs to the gas station.
+    async fn get_gas_coin(
+        &self,
+        coin_id: ObjectID,
+    ) -> anyhow::Result<Option<(ObjectRef, GasCoin)>> {
+        let client = self.wallet.get_client().await?;
+        let gas_obj = client
+            .read_api()
+            .get_object_with_options(
+                coin_id,
+                SuiObjectDataOptions::new()
+                    .with_type()
+                    .with_owner()
+                    .with_content(),
+            )
+            .await?;
+        Ok(gas_obj.data.and_then(|o| match o.owner {
+            Some(Owner::AddressOwner(owner)) if owner == self.active_address => {
+                GasCoin::try_from(&o)
+                    .ok()
+                    .map(|coin| (o.object_ref(), coin))
+            }
+            _ => None,
+        }))
+    }
+
+    async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
+        let producer = self.producer.lock().await;
+        info!(?uuid, ?coin_id, "Got producer lock and recycling coin");
+        producer
+            .try_send(coin_id)
+            .expect("unexpected - queue is large enough to hold all coins");
+        self.metrics.total_available_coins.inc();
+        info!(?uuid, ?coin_id, "Recycled coin");
+    }
+
+    async fn dry_run(&self, tx: &TransactionData) -> Result<(), FaucetError> {
+        let client = self
+            .wallet
+            .get_client()
+            .await
+            .map_err(|e| FaucetError::Wallet(format!("Unable to get client: {e:?}")))?;
+        let response = client
+            .read_api()
+            .dry_run_transaction(tx.clone())
+            .await
+            .map_err(|e| {
+                FaucetError::FullnodeReadingError(format!("Error dry running transaction {e:?}"))
+            })?;
+        match response.effects.status() {
+            SuiExecutionStatus::Success => Ok(()),
+            SuiExecutionStatus::Failure { error } => Err(FaucetError::PolicyViolation(format!(
+                "transaction fails in dry run: {error}"
+            ))),
+        }
+    }
+
+    fn sign(&self, tx: &TransactionData) -> Result<Signature, FaucetError> {
+        self.wallet
+            .config
+            .keystore
+            .sign_secure(&self.active_address, tx, Intent::default())
+            .map_err(FaucetError::internal)
+    }
+
+    /// Adds the signature of the gas station to the one of the sender, and verifies them both.
+    fn sign_sponsored(
+        &self,
+        tx: TransactionData,
+        sender_signature: GenericSignature,
+    ) -> Result<VerifiedTransaction, FaucetError> {
+        let signature = self.sign(&tx)?;
+        Transaction::from_signer_signatures(
+            tx,
+            Intent::default(),
+            vec![sender_signature, signature.into()],
+        )
+        .and_then(|tx| tx.verify())
+        .map_err(|e| FaucetError::InvalidRequest(format!("Invalid sender signature: {e}")))
+    }
+
+    /// Keep trying to execute `tx` until fullnode returns a definite response or a timeout is hit,
+    /// then release its gas coin. The coin goes back to the pool after a timeout too, as its
+    /// latest version is read again before it is used for another transaction.
+    async fn execute_txn(
+        &self,
+        uuid: Uuid,
+        coin_id: ObjectID,
+        tx: VerifiedTransaction,
+    ) -> Result<SuiTransactionResponse, FaucetError> {
+        let tx_digest = *tx.digest();
+        info!(
+            ?tx_digest,
+            ?coin_id,
+            ?uuid,
+            "Executing sponsored transaction."
+        );
+
+        let result = timeout(
+            EXECUTION_TIMEOUT,
+            self.execute_txn_with_retries(&tx, coin_id, uuid),
+        )
+        .await;
+
+        // Remove the coin from the WAL before recycling it, so that the transaction is not retried
+        // after a restart once the coin may be used by another one.
+        if self.wal.lock().await.commit_sponsored(coin_id).is_err() {
+            error!(?coin_id, "Failed to remove coin from WAL");
+        }
+        self.recycle_gas_coin(coin_id, uuid).await;
+
+        result.map_err(|elapsed| {
+            warn!(
+                ?tx_digest,
+                ?coin_id,
+                ?uuid,
+                "Failed to execute sponsored transaction after {elapsed}."
+            );
+            FaucetError::Internal("could not execute sponsored transaction within timeout".into())
+        })
+    }
+
+    async fn execute_txn_with_retries(
+        &self,
+        tx: &VerifiedTransaction,
+        coin_id: ObjectID,
+        uuid: Uuid,
+    ) -> SuiTransactionResponse {
+        let mut retry_delay = Duration::from_millis(500);
+
+        loop {
+            let res = self.execute_txn_once(tx, coin_id, uuid).await;
+
+            if let Ok(res) = res {
+                return res;
+            }
+
+            info!(
+                ?coin_id,
+                ?uuid,
+                ?retry_delay,
+                "Sponsored transaction failed, previous error: {:?}",
+                &res,
+            );
+
+            tokio::time::sleep(retry_delay).await;
+            retry_delay *= 2;
+        }
+    }
+
+    async fn execute_txn_once(
+        &self,
+        tx: &VerifiedTransaction,
+        coin_id: ObjectID,
+        uuid: Uuid,
+    ) -> Result<SuiTransactionResponse, anyhow::Error> {
+        self.metrics.current_executions_in_flight.inc();
+        let _metrics_guard = scopeguard::guard(self.metrics.clone(), |metrics| {
+            metrics.current_executions_in_flight.dec();
+        });
+
+        let tx_digest = tx.digest();
+        let client = self.wallet.get_client().await?;
+        Ok(client
+            .quorum_driver()
+            .execute_transaction(
+                tx.clone(),
+                SuiTransactionResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await
+            .tap_err(|e| {
+                error!(
+                    ?tx_digest,
+                    ?coin_id,
+                    ?uuid,
+                    "Sponsored transaction failed: {:?}",
+                    e
+                )
+            })?)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, FaucetError> {
+        let client = self
+            .wallet
+            .get_client()
+            .await
+            .map_err(|e| FaucetError::Wallet(format!("Unable to get client: {e:?}")))?;
+        client
+            .read_api()
+            .get_reference_gas_price()
+            .await
+            .map_err(|e| FaucetError::FullnodeReadingError(format!("Error fetch gas price {e:?}")))
+    }
+}
+
+fn decode_transaction(tx_bytes: &Base64) -> Result<TransactionData, FaucetError> {
+    tx_bytes
+        .to_vec()
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| bcs::from_bytes(&bytes).map_err(|e| e.to_string()))
+        .map_err(|e| FaucetError::InvalidRequest(format!("Invalid transaction bytes: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_keys::keystore::{InMemKeystore, Keystore};
+    use sui_types::messages::{Argument, Command};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use test_utils::network::TestClusterBuilder;
+
+    use super::*;
+
+    const GAS_BUDGET: u64 = 50_000;
+    const CLIENT_IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    async fn new_gas_station(
+        wallet: WalletContext,
+        wal_path: &Path,
+        reservation_timeout: Duration,
+    ) -> Arc<GasStation> {
+        let prom_registry = Registry::new();
+        let policy = GasStationPolicy {
+            allowed_move_calls: vec![],
+            max_gas_budget: GAS_BUDGET,
+            per_address_budget: 10 * GAS_BUDGET,
+            budget_window: Duration::from_secs(3600),
+            reservation_timeout,
+            max_reservations_per_ip: 2,
+        };
+        GasStation::new(wallet, &prom_registry, wal_path, policy)
+            .await
+            .unwrap()
+    }
+
+    /// Serialized transaction of `sender` transferring one of its coins to a random address.
+    async fn transfer_tx_bytes(
+        station: &GasStation,
+        sender: SuiAddress,
+        build: impl FnOnce(&mut ProgrammableTransactionBuilder),
+    ) -> Base64 {
+        let coin = station.wallet.gas_objects(sender).await.unwrap()[0]
+            .1
+            .object_ref();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .transfer_object(SuiAddress::random_for_testing_only(), coin)
+            .unwrap();
+        build(&mut builder);
+        let tx = TransactionData::new_programmable(sender, vec![], builder.finish(), GAS_BUDGET, 1);
+        Base64::from_bytes(&bcs::to_bytes(&tx).unwrap())
+    }
+
+    fn sign_as(station: &GasStation, sender: SuiAddress, tx_bytes: &Base64) -> Base64 {
+        let tx = decode_transaction(tx_bytes).unwrap();
+        let signature = station
+            .wallet
+            .config
+            .keystore
+            .sign_secure(&sender, &tx, Intent::default())
+            .unwrap();
+        Base64::from_bytes(signature.as_ref())
+    }
+
+    #[tokio::test]
+    async fn test_sponsor_and_execute() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+        let tmp = tempfile::tempdir().unwrap();
+        let station = new_gas_station(
+            test_cluster.wallet,
+            &tmp.path().join("gas_station.wal"),
+            Duration::from_secs(60),
+        )
+        .await;
+        let available = station.metrics.total_available_coins.get();
+
+        let tx_bytes = transfer_tx_bytes(&station, sender, |_| {}).await;
+        let sponsored = station
+            .sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP)
+            .await
+            .unwrap();
+        let tx = decode_transaction(&sponsored.tx_bytes).unwrap();
+        assert_eq!(tx.sender(), sender);
+        assert_eq!(tx.gas_owner(), station.active_address);
+        assert_eq!(available - 1, station.metrics.total_available_coins.get());
+
+        let sender_signature = sign_as(&station, sender, &sponsored.tx_bytes);
+        let response = station
+            .execute(&sponsored.tx_bytes, &sender_signature)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.effects.unwrap().status(),
+            &SuiExecutionStatus::Success
+        );
+        assert_eq!(available, station.metrics.total_available_coins.get());
+
+        // The reservation is gone once the transaction has been executed.
+        assert!(matches!(
+            station
+                .execute(&sponsored.tx_bytes, &sender_signature)
+                .await,
+            Err(FaucetError::UnknownReservation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signing_failure_releases_coin() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+        let mut wallet = test_cluster.wallet;
+        // The keystore no longer holds the key of the gas station.
+        let active_address = wallet.active_address().unwrap();
+        wallet.config.keystore = Keystore::from(InMemKeystore::new(1));
+        wallet.config.active_address = Some(active_address);
+        let tmp = tempfile::tempdir().unwrap();
+        let station = new_gas_station(
+            wallet,
+            &tmp.path().join("gas_station.wal"),
+            Duration::from_secs(60),
+        )
+        .await;
+        let available = station.metrics.total_available_coins.get();
+
+        let tx_bytes = transfer_tx_bytes(&station, sender, |_| {}).await;
+        assert!(matches!(
+            station.sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP).await,
+            Err(FaucetError::Internal(_))
+        ));
+
+        // Neither the coin, nor the client's reservations, nor the sender's budget are held.
+        assert_eq!(available, station.metrics.total_available_coins.get());
+        assert!(station.reservations.lock().unwrap().is_empty());
+        assert!(station.reservations_per_ip.lock().unwrap().is_empty());
+        assert!(station.budgets.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reject_gas_coin_use() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+        let tmp = tempfile::tempdir().unwrap();
+        let station = new_gas_station(
+            test_cluster.wallet,
+            &tmp.path().join("gas_station.wal"),
+            Duration::from_secs(60),
+        )
+        .await;
+        let available = station.metrics.total_available_coins.get();
+
+        let tx_bytes = transfer_tx_bytes(&station, sender, |builder| {
+            let recipient = builder.pure(sender).unwrap();
+            builder.command(Command::TransferObjects(vec![Argument::GasCoin], recipient));
+        })
+        .await;
+        assert!(matches!(
+            station.sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP).await,
+            Err(FaucetError::PolicyViolation(_))
+        ));
+        assert_eq!(available, station.metrics.total_available_coins.get());
+    }
+
+    #[tokio::test]
+    async fn test_reservations_per_ip_are_limited() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+        let tmp = tempfile::tempdir().unwrap();
+        let station = new_gas_station(
+            test_cluster.wallet,
+            &tmp.path().join("gas_station.wal"),
+            Duration::from_secs(60),
+        )
+        .await;
+
+        let tx_bytes = transfer_tx_bytes(&station, sender, |_| {}).await;
+        let sponsored = station
+            .sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP)
+            .await
+            .unwrap();
+        station
+            .sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP)
+            .await
+            .unwrap();
+        assert!(matches!(
+            station.sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP).await,
+            Err(FaucetError::TooManyRequests(_))
+        ));
+
+        // Clients from other addresses are not affected.
+        let other_ip = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+        station
+            .sponsor(Uuid::new_v4(), &tx_bytes, other_ip)
+            .await
+            .unwrap();
+
+        // Executing a sponsored transaction frees its reservation.
+        let sender_signature = sign_as(&station, sender, &sponsored.tx_bytes);
+        station
+            .execute(&sponsored.tx_bytes, &sender_signature)
+            .await
+            .unwrap();
+        let tx_bytes = transfer_tx_bytes(&station, sender, |_| {}).await;
+        station
+            .sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reservation_expires() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let sender = test_cluster.get_address_1();
+        let tmp = tempfile::tempdir().unwrap();
+        let station = new_gas_station(
+            test_cluster.wallet,
+            &tmp.path().join("gas_station.wal"),
+            Duration::from_secs(1),
+        )
+        .await;
+        let available = station.metrics.total_available_coins.get();
+
+        let tx_bytes = transfer_tx_bytes(&station, sender, |_| {}).await;
+        let sponsored = station
+            .sponsor(Uuid::new_v4(), &tx_bytes, CLIENT_IP)
+            .await
+            .unwrap();
+        assert_eq!(available - 1, station.metrics.total_available_coins.get());
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert_eq!(available, station.metrics.total_available_coins.get());
+
+        let sender_signature = sign_as(&station, sender, &sponsored.tx_bytes);
+        assert!(matches!(
+            station
+                .execute(&sponsored.tx_bytes, &sender_signature)
+                .await,
+            Err(FaucetError::UnknownReservation(_))
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{
+    Argument, Command, ProgrammableMoveCall, TransactionData, TransactionDataAPI, TransactionKind,
+};
+
+/// A package, module or function that sponsored transactions may call, written as `<package>`,
+/// `<package>::<module>` or `<package>::<module>::<function>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllowedMoveCall {
+    pub package: ObjectID,
+    pub module: Option<String>,
+    pub function: Option<String>,
+}
+
+impl AllowedMoveCall {
+    fn allows(&self, call: &ProgrammableMoveCall) -> bool {
+        self.package == call.package
+            && self
+                .module
+                .as_ref()
+                .map_or(true, |module| module.as_str() == call.module.as_str())
+            && self
+                .function
+                .as_ref()
+                .map_or(true, |function| function.as_str() == call.function.as_str())
+    }
+}
+
+impl FromStr for AllowedMoveCall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("::");
+        let package = ObjectID::from_hex_literal(parts.next().unwrap_or_default())?;
+        let module = parts.next().map(str::to_string);
+        let function = parts.next().map(str::to_string);
+        if parts.next().is_some() {
+            return Err(anyhow::anyhow!(
+                "Expected `<package>[::<module>[::<function>]]`, got `{s}`"
+            ));
+        }
+        Ok(Self {
+            package,
+            module,
+            function,
+        })
+    }
+}
+
+impl Display for AllowedMoveCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.package)?;
+        for part in [&self.module, &self.function].into_iter().flatten() {
+            write!(f, "::{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Which transactions the gas station agrees to sponsor, and for how much.
+#[derive(Clone, Debug)]
+pub struct GasStationPolicy {
+    /// Move calls sponsored transactions may make. Any call is allowed if empty.
+    pub allowed_move_calls: Vec<AllowedMoveCall>,
+    /// Largest gas budget of a single sponsored transaction.
+    pub max_gas_budget: u64,
+    /// Total gas budget sponsored for a single sender within `budget_window`.
+    pub per_address_budget: u64,
+    pub budget_window: Duration,
+    /// How long a coin stays reserved for a sponsored transaction which the gas station has not
+    /// been asked to execute. The coin then goes back to the pool, so senders executing sponsored
+    /// transactions on their own must do so before that.
+    pub reservation_timeout: Duration,
+    /// Largest number of coins reserved at once for the clients of a single IP address, so that
+    /// clients cannot take the whole pool by sponsoring transactions of fresh senders.
+    pub max_reservations_per_ip: usize,
+}
+
+impl GasStationPolicy {
+    /// Checks that `tx` may be sponsored by `sponsor`, returning the reason it may not otherwise.
+    ///
+    /// Sponsored transactions must be programmable transactions within the gas budget limit, which
+    /// only call allowed functions, do not publish or upgrade packages, and do not touch the gas
+    /// coin, as that belongs to the sponsor.
+    pub fn check(&self, tx: &TransactionData, sponsor: SuiAddress) -> Result<(), String> {
+        if tx.sender() == sponsor {
+            return Err("the gas station does not sponsor its own transactions".to_string());
+        }
+        if tx.gas_budget() > self.max_gas_budget {
+            return Err(format!(
+                "gas budget {} is above the limit of {}",
+                tx.gas_budget(),
+                self.max_gas_budget
+            ));
+        }
+        let pt = match tx.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => pt,
+            _ => return Err("only programmable transactions can be sponsored".to_string()),
+        };
+
+        for command in &pt.commands {
+            let arguments: Vec<&Argument> = match command {
+                Command::MoveCall(call) => {
+                    if !self.allowed_move_calls.is_empty()
+                        && !self.allowed_move_calls.iter().any(|a| a.allows(call))
+                    {
+                        return Err(format!(
+                            "calls to {}::{}::{} are not allowed",
+                            call.package, call.module, call.function
+                        ));
+                    }
+                    call.arguments.iter().collect()
+                }
+                Command::TransferObjects(objects, recipient) => {
+                    objects.iter().chain([recipient]).collect()
+                }
+                Command::SplitCoins(coin, amounts) => [coin].into_iter().chain(amounts).collect(),
+                Command::MergeCoins(coin, coins) => [coin].into_iter().chain(coins).collect(),
+                Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+                Command::Publish(..) | Command::Upgrade(..) => {
+                    return Err("packages cannot be published or upgraded".to_string())
+                }
+            };
+            if arguments.contains(&&Argument::GasCoin) {
+                return Err("the gas coin cannot be used by commands".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::identifier::Identifier;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::SUI_FRAMEWORK_OBJECT_ID;
+
+    use super::*;
+
+    fn policy(allowed_move_calls: &[&str]) -> GasStationPolicy {
+        GasStationPolicy {
+            allowed_move_calls: allowed_move_calls
+                .iter()
+                .map(|s| s.parse().unwrap())
+                .collect(),
+            max_gas_budget: 10_000,
+            per_address_budget: 100_000,
+            budget_window: Duration::from_secs(3600),
+            reservation_timeout: Duration::from_secs(60),
+            max_reservations_per_ip: 10,
+        }
+    }
+
+    fn transaction(build: impl FnOnce(&mut ProgrammableTransactionBuilder)) -> TransactionData {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        build(&mut builder);
+        TransactionData::new_programmable(
+            SuiAddress::random_for_testing_only(),
+            vec![random_object_ref()],
+            builder.finish(),
+            10_000,
+            1,
+        )
+    }
+
+    fn move_call(builder: &mut ProgrammableTransactionBuilder, module: &str, function: &str) {
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            Identifier::new(module).unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            vec![],
+        );
+    }
+
+    #[test]
+    fn test_parse_allowed_move_call() {
+        for s in ["0x2", "0x2::coin", "0x2::coin::join"] {
+            let call: AllowedMoveCall = s.parse().unwrap();
+            assert_eq!(call.package, SUI_FRAMEWORK_OBJECT_ID);
+            assert_eq!(call.to_string().parse::<AllowedMoveCall>().unwrap(), call);
+        }
+        assert!("coin::join".parse::<AllowedMoveCall>().is_err());
+        assert!("0x2::coin::join::extra".parse::<AllowedMoveCall>().is_err());
+    }
+
+    #[test]
+    fn test_check_move_calls() {
+        let sponsor = SuiAddress::random_for_testing_only();
+        let tx = transaction(|builder| move_call(builder, "coin", "join"));
+
+        assert!(policy(&[]).check(&tx, sponsor).is_ok());
+        assert!(policy(&["0x2"]).check(&tx, sponsor).is_ok());
+        assert!(policy(&["0x2::coin"]).check(&tx, sponsor).is_ok());
+        assert!(policy(&["0x3", "0x2::coin::join"])
+            .check(&tx, sponsor)
+            .is_ok());
+        assert!(policy(&["0x3"]).check(&tx, sponsor).is_err());
+        assert!(policy(&["0x2::pay"]).check(&tx, sponsor).is_err());
+        assert!(policy(&["0x2::coin::split"]).check(&tx, sponsor).is_err());
+    }
+
+    #[test]
+    fn test_check_rejects_gas_coin_use() {
+        let sponsor = SuiAddress::random_for_testing_only();
+        let tx = transaction(|builder| {
+            let recipient = builder.pure(sponsor).unwrap();
+            builder.command(Command::TransferObjects(vec![Argument::GasCoin], recipient));
+        });
+        assert!(policy(&[]).check(&tx, sponsor).is_err());
+
+        let tx = transaction(|builder| {
+            let amount = builder.pure(1u64).unwrap();
+            builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+        });
+        assert!(policy(&[]).check(&tx, sponsor).is_err());
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let tx = transaction(|builder| move_call(builder, "coin", "join"));
+
+        let mut policy = policy(&[]);
+        assert!(policy.check(&tx, tx.sender()).is_err());
+
+        policy.max_gas_budget = tx.gas_budget() - 1;
+        assert!(policy
+            .check(&tx, SuiAddress::random_for_testing_only())
+            .is_err());
+
+        let tx = transaction(|builder| {
+            builder.command(Command::Publish(vec![], vec![]));
+        });
+        assert!(policy
+            .check(&tx, SuiAddress::random_for_testing_only())
+            .is_err());
+    }
+}
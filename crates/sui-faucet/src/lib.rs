@@ -1,16 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod batch;
 mod errors;
 mod faucet;
+mod gas_station;
 mod metrics;
+mod quota;
 mod requests;
 mod responses;
 
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
+pub use batch::*;
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use gas_station::*;
+pub use quota::*;
 pub use requests::*;
 pub use responses::*;
@@ -3,7 +3,8 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    http::StatusCode,
+    extract::{ConnectInfo, Path},
+    http::{header::X_FORWARDED_FOR, HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -15,12 +16,18 @@ use std::{
     borrow::Cow,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use std::{env, path::PathBuf};
 use sui::client_commands::WalletContext;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_faucet::{Faucet, FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet};
+use sui_faucet::{
+    client_ip, AllowedMoveCall, BatchFaucetResponse, BatchQueue, BatchStatusFaucetResponse,
+    ExecuteSponsoredRequest, ExecuteSponsoredResponse, Faucet, FaucetError, FaucetRequest,
+    FaucetResponse, GasStation, GasStationPolicy, Quotas, RequestMetricsLayer, SimpleFaucet,
+    SponsorRequest, SponsorResponse,
+};
+use sui_types::base_types::SuiAddress;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -58,11 +65,84 @@ struct FaucetConfig {
 
     #[clap(long)]
     write_ahead_log: PathBuf,
+
+    /// Largest number of requests served to a single recipient within `--quota-window-secs`.
+    /// Unlimited if not set.
+    #[clap(long)]
+    max_requests_per_recipient: Option<usize>,
+
+    /// Largest number of requests served to a single IP address within `--quota-window-secs`.
+    /// Unlimited if not set.
+    #[clap(long)]
+    max_requests_per_ip: Option<usize>,
+
+    #[clap(long, default_value_t = 86_400)]
+    quota_window_secs: u64,
+
+    /// Addresses of the reverse proxies in front of the faucet. Requests relayed by them are
+    /// accounted against the per-IP quota, or reservation limit of the gas station, of the client
+    /// address in their `X-Forwarded-For` header.
+    #[clap(long)]
+    trusted_proxy: Vec<IpAddr>,
+
+    /// Where requests are accounted against quotas. Defaults to `quotas` next to the write-ahead
+    /// log.
+    #[clap(long)]
+    quota_store: Option<PathBuf>,
+
+    /// Largest number of recipients served by a single transaction of `/v1/batch_gas`.
+    #[clap(long, default_value_t = 50)]
+    max_batch_size: usize,
+
+    /// How long requests to `/v1/batch_gas` wait for more requests to be batched with.
+    #[clap(long, default_value_t = 500)]
+    batch_interval_ms: u64,
+
+    #[clap(long, default_value_t = 10_000)]
+    max_queued_batch_requests: usize,
+
+    /// Run as a gas station sponsoring the transactions of clients, instead of sending them coins.
+    #[clap(long)]
+    gas_station: bool,
+
+    /// Packages, modules (`<package>::<module>`) or functions (`<package>::<module>::<function>`)
+    /// which transactions sponsored by the gas station may call. Any call is allowed if none is
+    /// given.
+    #[clap(long)]
+    allowed_move_call: Vec<AllowedMoveCall>,
+
+    /// Largest gas budget of a transaction sponsored by the gas station.
+    #[clap(long, default_value_t = 50_000_000)]
+    max_gas_budget: u64,
+
+    /// Total gas budget the gas station sponsors for a single sender within
+    /// `--gas-budget-window-secs`.
+    #[clap(long, default_value_t = 1_000_000_000)]
+    per_address_gas_budget: u64,
+
+    #[clap(long, default_value_t = 86_400)]
+    gas_budget_window_secs: u64,
+
+    /// How long the gas station keeps a coin reserved for a sponsored transaction which it has not
+    /// been asked to execute.
+    #[clap(long, default_value_t = 60)]
+    reservation_timeout_secs: u64,
+
+    /// Largest number of coins the gas station keeps reserved at once for the clients of a single
+    /// IP address.
+    #[clap(long, default_value_t = 10)]
+    max_reservations_per_ip: usize,
 }
 
+/// Addresses of the reverse proxies trusted to report the IP address of their clients.
+#[derive(Clone)]
+struct TrustedProxies(Arc<Vec<IpAddr>>);
+
 struct AppState<F = SimpleFaucet> {
-    faucet: F,
+    faucet: Arc<F>,
     config: FaucetConfig,
+    quotas: Quotas,
+    batch_queue: Arc<BatchQueue>,
     // TODO: add counter
 }
 
@@ -99,42 +179,99 @@ async fn main() -> Result<(), anyhow::Error> {
     let registry_service = sui_node::metrics::start_prometheus_server(prom_binding);
     let prometheus_registry = registry_service.default_registry();
 
-    let app_state = Arc::new(AppState {
-        faucet: SimpleFaucet::new(context, &prometheus_registry, write_ahead_log)
-            .await
-            .unwrap(),
-        config,
-    });
-
     // TODO: restrict access if needed
     let cors = CorsLayer::new()
         .allow_methods(vec![Method::GET, Method::POST])
         .allow_headers(Any)
         .allow_origin(Any);
 
-    let app = Router::new()
-        .route("/", get(health))
-        .route("/gas", post(request_gas))
-        .layer(
-            ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(handle_error))
-                .layer(RequestMetricsLayer::new(&prometheus_registry))
-                .layer(cors)
-                .load_shed()
-                .buffer(request_buffer_size)
-                .layer(RateLimitLayer::new(
-                    max_request_per_second,
-                    Duration::from_secs(1),
-                ))
-                .concurrency_limit(max_concurrency)
-                .layer(Extension(app_state))
-                .into_inner(),
+    let app = if config.gas_station {
+        let policy = GasStationPolicy {
+            allowed_move_calls: config.allowed_move_call.clone(),
+            max_gas_budget: config.max_gas_budget,
+            per_address_budget: config.per_address_gas_budget,
+            budget_window: Duration::from_secs(config.gas_budget_window_secs),
+            reservation_timeout: Duration::from_secs(config.reservation_timeout_secs),
+            max_reservations_per_ip: config.max_reservations_per_ip,
+        };
+        let gas_station = GasStation::new(context, &prometheus_registry, write_ahead_log, policy)
+            .await
+            .unwrap();
+        Router::new()
+            .route("/", get(health))
+            .route("/sponsor", post(sponsor_transaction))
+            .route("/execute", post(execute_sponsored_transaction))
+            .layer(Extension(gas_station))
+            .layer(Extension(TrustedProxies(Arc::new(
+                config.trusted_proxy.clone(),
+            ))))
+    } else {
+        let faucet = Arc::new(
+            SimpleFaucet::new(context, &prometheus_registry, write_ahead_log)
+                .await
+                .unwrap(),
+        );
+        let quota_store = config
+            .quota_store
+            .clone()
+            .unwrap_or_else(|| write_ahead_log.with_file_name("quotas"));
+        let quotas = Quotas::open(
+            &quota_store,
+            Duration::from_secs(config.quota_window_secs),
+            config.max_requests_per_recipient,
+            config.max_requests_per_ip,
         );
+        let batch_queue = BatchQueue::start(
+            faucet.clone(),
+            vec![config.amount; config.num_coins],
+            config.max_batch_size,
+            Duration::from_millis(config.batch_interval_ms),
+            config.max_queued_batch_requests,
+        );
+        let app_state = Arc::new(AppState {
+            faucet,
+            config,
+            quotas,
+            batch_queue,
+        });
+
+        let state = app_state.clone();
+        spawn_monitored_task!(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                if let Err(e) = state.quotas.prune(now_ms()) {
+                    warn!("Failed to prune quotas: {:?}", e);
+                }
+            }
+        });
+
+        Router::new()
+            .route("/", get(health))
+            .route("/gas", post(request_gas))
+            .route("/v1/batch_gas", post(batch_request_gas))
+            .route("/v1/status/:task_id", get(request_status))
+            .layer(Extension(app_state))
+    }
+    .layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(handle_error))
+            .layer(RequestMetricsLayer::new(&prometheus_registry))
+            .layer(cors)
+            .load_shed()
+            .buffer(request_buffer_size)
+            .layer(RateLimitLayer::new(
+                max_request_per_second,
+                Duration::from_secs(1),
+            ))
+            .concurrency_limit(max_concurrency)
+            .into_inner(),
+    );
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -147,6 +284,8 @@ async fn health() -> &'static str {
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
@@ -154,6 +293,10 @@ async fn request_gas(
     info!(uuid = ?id, "Got new gas request.");
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            if let Err(v) = check_quotas(&state, requests.recipient, addr, &headers) {
+                warn!(uuid =?id, "Rejected gas request: {:?}", v);
+                return (error_status(&v), Json(FaucetResponse::from(v)));
+            }
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the reclycing of coins
             spawn_monitored_task!(async move {
@@ -177,14 +320,161 @@ async fn request_gas(
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            (error_status(&v), Json(FaucetResponse::from(v)))
+        }
+    }
+}
+
+/// handler for requests to be served gas in a batch transaction
+async fn batch_request_gas(
+    Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    // ID for traceability, and for polling the status of the request
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new batch gas request.");
+    let result = match payload {
+        FaucetRequest::FixedAmountRequest(requests) => {
+            check_quotas(&state, requests.recipient, addr, &headers)
+                .and_then(|()| state.batch_queue.enqueue(id, requests.recipient))
+        }
+    };
+    match result {
+        Ok(()) => {
+            info!(uuid =?id, "Request is queued");
+            (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id)))
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to queue gas request: {:?}", v);
+            (error_status(&v), Json(BatchFaucetResponse::from(v)))
+        }
+    }
+}
+
+/// handler for polling the status of requests to `/v1/batch_gas`
+async fn request_status(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> impl IntoResponse {
+    let id = match Uuid::parse_str(&task_id) {
+        Ok(id) => id,
+        Err(e) => {
+            let error = FaucetError::InvalidRequest(format!("Invalid task ID {task_id}: {e}"));
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(BatchStatusFaucetResponse::from(error)),
+            );
+        }
+    };
+    match state.batch_queue.status(&id) {
+        Some(status) => (
+            StatusCode::OK,
+            Json(BatchStatusFaucetResponse::from(status)),
+        ),
+        None => {
+            let error = FaucetError::InvalidRequest(format!("Unknown task ID {id}"));
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(FaucetResponse::from(v)),
+                StatusCode::NOT_FOUND,
+                Json(BatchStatusFaucetResponse::from(error)),
             )
         }
     }
 }
 
+/// Accounts a request for `recipient` received from `addr` against the quotas.
+fn check_quotas(
+    state: &AppState,
+    recipient: SuiAddress,
+    addr: SocketAddr,
+    headers: &HeaderMap,
+) -> Result<(), FaucetError> {
+    let ip = request_ip(addr, headers, &state.config.trusted_proxy);
+    state.quotas.acquire(recipient, Some(ip), now_ms())
+}
+
+/// The IP address of the client behind a request received from `addr`.
+fn request_ip(addr: SocketAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let forwarded_for = headers
+        .get(X_FORWARDED_FOR)
+        .and_then(|value| value.to_str().ok());
+    client_ip(addr.ip(), forwarded_for, trusted_proxies)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is after the Unix epoch")
+        .as_millis() as u64
+}
+
+/// handler for requests to sponsor a transaction with the gas station
+async fn sponsor_transaction(
+    Extension(gas_station): Extension<Arc<GasStation>>,
+    Extension(TrustedProxies(trusted_proxies)): Extension<TrustedProxies>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<SponsorRequest>,
+) -> impl IntoResponse {
+    // ID for traceability
+    let id = Uuid::new_v4();
+    let ip = request_ip(addr, &headers, &trusted_proxies);
+    info!(uuid = ?id, ?ip, "Got new sponsor request.");
+    // As for gas requests, a connection drop must not interrupt the reservation of the coin
+    let result =
+        spawn_monitored_task!(async move { gas_station.sponsor(id, &payload.tx_bytes, ip).await })
+            .await
+            .unwrap();
+    match result {
+        Ok(v) => {
+            info!(uuid =?id, "Request is successfully served");
+            (StatusCode::CREATED, Json(SponsorResponse::from(v)))
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to sponsor transaction: {:?}", v);
+            (error_status(&v), Json(SponsorResponse::from(v)))
+        }
+    }
+}
+
+/// handler for requests to execute a transaction sponsored by the gas station
+async fn execute_sponsored_transaction(
+    Extension(gas_station): Extension<Arc<GasStation>>,
+    Json(payload): Json<ExecuteSponsoredRequest>,
+) -> impl IntoResponse {
+    info!("Got new execute request.");
+    // A connection drop must not interrupt the recycling of the coin either
+    let result = spawn_monitored_task!(async move {
+        gas_station
+            .execute(&payload.tx_bytes, &payload.sender_signature)
+            .await
+    })
+    .await
+    .unwrap();
+    match result {
+        Ok(v) => {
+            info!(digest = ?v.digest, "Sponsored transaction executed");
+            (StatusCode::OK, Json(ExecuteSponsoredResponse::from(v)))
+        }
+        Err(v) => {
+            warn!("Failed to execute sponsored transaction: {:?}", v);
+            (error_status(&v), Json(ExecuteSponsoredResponse::from(v)))
+        }
+    }
+}
+
+fn error_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::InvalidRequest(_) | FaucetError::UnknownReservation(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        FaucetError::PolicyViolation(_) => StatusCode::FORBIDDEN,
+        FaucetError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -0,0 +1,267 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use typed_store::rocks::{DBMap, TypedStoreError};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store_derive::DBMapUtils;
+
+use crate::FaucetError;
+
+/// Who a request is accounted to.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum QuotaKey {
+    Recipient(SuiAddress),
+    Ip(IpAddr),
+}
+
+/// Persistent log of the times, in milliseconds since the Unix epoch, at which requests were
+/// accepted within the current quota window, keyed by who they are accounted to.
+#[derive(DBMapUtils)]
+pub struct QuotaStore {
+    requests: DBMap<QuotaKey, Vec<u64>>,
+}
+
+impl QuotaStore {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::default(),
+            None,
+            None,
+        )
+    }
+}
+
+/// Limits on the number of requests served to a single recipient, and from a single IP address,
+/// within a sliding window. Requests count against the quotas once they are accepted, whether or
+/// not they are fulfilled.
+pub struct Quotas {
+    store: Mutex<QuotaStore>,
+    window: Duration,
+    max_requests_per_recipient: Option<usize>,
+    max_requests_per_ip: Option<usize>,
+}
+
+impl Quotas {
+    /// Opens the quota accounting stored at `path`. Quotas which are not set are unlimited.
+    pub fn open(
+        path: &Path,
+        window: Duration,
+        max_requests_per_recipient: Option<usize>,
+        max_requests_per_ip: Option<usize>,
+    ) -> Self {
+        Self {
+            store: Mutex::new(QuotaStore::open(path)),
+            window,
+            max_requests_per_recipient,
+            max_requests_per_ip,
+        }
+    }
+
+    /// Accounts a request for `recipient` from `ip`, made at `now_ms`, unless either of them has
+    /// already used up their quota within the window ending at `now_ms`.
+    pub fn acquire(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        now_ms: u64,
+    ) -> Result<(), FaucetError> {
+        let limits = [
+            (
+                Some(QuotaKey::Recipient(recipient)),
+                self.max_requests_per_recipient,
+            ),
+            (ip.map(QuotaKey::Ip), self.max_requests_per_ip),
+        ];
+        let window_start = now_ms.saturating_sub(self.window.as_millis() as u64);
+
+        let store = self.store.lock().unwrap();
+        let mut updates = vec![];
+        for (key, limit) in limits {
+            let (key, limit) = match (key, limit) {
+                (Some(key), Some(limit)) => (key, limit),
+                _ => continue,
+            };
+            let mut requests = store
+                .requests
+                .get(&key)
+                .map_err(FaucetError::internal)?
+                .unwrap_or_default();
+            requests.retain(|t| *t > window_start);
+            if requests.len() >= limit {
+                let retry_in_secs = (requests[0] - window_start) / 1000 + 1;
+                return Err(FaucetError::TooManyRequests(format!(
+                    "{} reached its quota of {limit} requests, retry in {retry_in_secs}s",
+                    match key {
+                        QuotaKey::Recipient(recipient) => format!("Recipient {recipient}"),
+                        QuotaKey::Ip(ip) => format!("IP address {ip}"),
+                    }
+                )));
+            }
+            requests.push(now_ms);
+            updates.push((key, requests));
+        }
+
+        store
+            .requests
+            .multi_insert(updates)
+            .map_err(FaucetError::internal)
+    }
+
+    /// Removes the accounting of everyone whose requests all fell out of the window ending at
+    /// `now_ms`, so that the store does not keep growing.
+    pub fn prune(&self, now_ms: u64) -> Result<(), TypedStoreError> {
+        let window_start = now_ms.saturating_sub(self.window.as_millis() as u64);
+        let store = self.store.lock().unwrap();
+        let expired: Vec<_> = store
+            .requests
+            .iter()
+            .filter(|(_, requests)| requests.iter().all(|t| *t <= window_start))
+            .map(|(key, _)| key)
+            .collect();
+        store.requests.multi_remove(expired)
+    }
+}
+
+/// The IP address of the client making a request received from `peer`. Requests relayed by one of
+/// the `trusted_proxies` are accounted to the address they were forwarded for, i.e. the last
+/// address of the `X-Forwarded-For` header which is not a trusted proxy itself. Addresses
+/// forwarded by anyone else can't be trusted, they are ignored.
+pub fn client_ip(peer: IpAddr, forwarded_for: Option<&str>, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+    let mut client = peer;
+    for hop in forwarded_for.into_iter().flat_map(|f| f.rsplit(',')) {
+        match hop.trim().parse() {
+            Ok(ip) => client = ip,
+            Err(_) => break,
+        }
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const WINDOW_MS: u64 = 60_000;
+    const T0: u64 = 1_000_000;
+
+    fn quotas(path: &Path) -> Quotas {
+        Quotas::open(path, Duration::from_millis(WINDOW_MS), Some(2), Some(3))
+    }
+
+    #[test]
+    fn test_recipient_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quotas"));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.acquire(recipient, None, T0).unwrap();
+        quotas.acquire(recipient, None, T0 + 1_000).unwrap();
+        assert!(matches!(
+            quotas.acquire(recipient, None, T0 + 2_000),
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        quotas
+            .acquire(SuiAddress::random_for_testing_only(), None, T0 + 2_000)
+            .unwrap();
+
+        // The window slides past the first request.
+        quotas
+            .acquire(recipient, None, T0 + WINDOW_MS - 1)
+            .unwrap_err();
+        quotas.acquire(recipient, None, T0 + WINDOW_MS).unwrap();
+    }
+
+    #[test]
+    fn test_ip_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quotas"));
+        let ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        for t in 0..3 {
+            quotas
+                .acquire(SuiAddress::random_for_testing_only(), ip, T0 + t)
+                .unwrap();
+        }
+        assert!(matches!(
+            quotas.acquire(SuiAddress::random_for_testing_only(), ip, T0 + 3),
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        quotas
+            .acquire(SuiAddress::random_for_testing_only(), None, T0 + 3)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_rejected_requests_are_not_accounted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quotas"));
+        let recipient = SuiAddress::random_for_testing_only();
+        let ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        quotas.acquire(recipient, ip, T0).unwrap();
+        quotas.acquire(recipient, ip, T0 + 1).unwrap();
+        // Rejected by the recipient quota, so it does not use up the IP quota either.
+        quotas.acquire(recipient, ip, T0 + 2).unwrap_err();
+        quotas
+            .acquire(SuiAddress::random_for_testing_only(), ip, T0 + 3)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_quotas_survive_restarts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("quotas");
+        let recipient = SuiAddress::random_for_testing_only();
+        {
+            let quotas = quotas(&path);
+            quotas.acquire(recipient, None, T0).unwrap();
+            quotas.acquire(recipient, None, T0 + 1).unwrap();
+        }
+
+        let quotas = quotas(&path);
+        quotas.acquire(recipient, None, T0 + 2).unwrap_err();
+
+        // Pruning only forgets requests which are out of the window.
+        quotas.prune(T0 + WINDOW_MS).unwrap();
+        quotas.acquire(recipient, None, T0 + 3).unwrap_err();
+        quotas.prune(T0 + WINDOW_MS + 1).unwrap();
+        assert!(quotas.store.lock().unwrap().requests.is_empty());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let inner_proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let client: IpAddr = "1.2.3.4".parse().unwrap();
+        let trusted = [proxy, inner_proxy];
+
+        // Only trusted proxies may forward requests on behalf of someone else.
+        assert_eq!(client_ip(client, Some("5.6.7.8"), &trusted), client);
+        assert_eq!(client_ip(proxy, None, &trusted), proxy);
+        assert_eq!(client_ip(proxy, Some("1.2.3.4"), &trusted), client);
+        // Addresses prepended by the client are not trusted.
+        assert_eq!(
+            client_ip(proxy, Some("5.6.7.8, 1.2.3.4, 10.0.0.2"), &trusted),
+            client
+        );
+        assert_eq!(client_ip(proxy, Some("garbage, 1.2.3.4"), &trusted), client);
+        assert_eq!(client_ip(proxy, Some("1.2.3.4, garbage"), &trusted), proxy);
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::Base64;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::SuiAddress;
 
@@ -21,3 +22,20 @@ impl FaucetRequest {
         })
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorRequest {
+    /// BCS serialized `TransactionData` to sponsor. Its gas payment, owner and price are replaced
+    /// by the gas station, its gas budget is kept.
+    pub tx_bytes: Base64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteSponsoredRequest {
+    /// BCS serialized `TransactionData`, as returned by the gas station
+    pub tx_bytes: Base64,
+    /// Signature of the sender over `tx_bytes`
+    pub sender_signature: Base64,
+}
@@ -3,6 +3,8 @@
 
 use crate::*;
 use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::SuiTransactionResponse;
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -28,3 +30,104 @@ impl From<FaucetReceipt> for FaucetResponse {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFaucetResponse {
+    /// ID of the queued request, to poll its status with
+    pub task: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for BatchFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            task: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<Uuid> for BatchFaucetResponse {
+    fn from(v: Uuid) -> Self {
+        Self {
+            task: Some(v.to_string()),
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusFaucetResponse {
+    pub status: Option<BatchSendStatus>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for BatchStatusFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            status: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<BatchSendStatus> for BatchStatusFaucetResponse {
+    fn from(v: BatchSendStatus) -> Self {
+        Self {
+            status: Some(v),
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorResponse {
+    pub sponsored: Option<SponsoredTransaction>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for SponsorResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            sponsored: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<SponsoredTransaction> for SponsorResponse {
+    fn from(v: SponsoredTransaction) -> Self {
+        Self {
+            sponsored: Some(v),
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteSponsoredResponse {
+    pub transaction: Option<SuiTransactionResponse>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for ExecuteSponsoredResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            transaction: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<SuiTransactionResponse> for ExecuteSponsoredResponse {
+    fn from(v: SuiTransactionResponse) -> Self {
+        Self {
+            transaction: Some(v),
+            error: None,
+        }
+    }
+}
@@ -8,7 +8,9 @@ use jsonrpsee::RpcModule;
 use sui_json_rpc::api::CoinReadApiClient;
 use sui_json_rpc::api::CoinReadApiServer;
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_types::{Balance, CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{
+    Balance, CheckpointBalance, CoinPage, SuiCheckpointSequenceNumber, SuiCoinMetadata,
+};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -56,6 +58,17 @@ impl CoinReadApiServer for CoinReadApi {
         self.fullnode.get_balance(owner, coin_type).await
     }
 
+    async fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: SuiCheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointBalance> {
+        self.fullnode
+            .get_balance_at_checkpoint(owner, coin_type, checkpoint)
+            .await
+    }
+
     async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
         self.fullnode.get_all_balances(owner).await
     }
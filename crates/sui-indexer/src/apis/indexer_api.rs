@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -12,11 +13,14 @@ use jsonrpsee::{RpcModule, SubscriptionSink};
 use sui_core::event_handler::EventHandler;
 use sui_json_rpc::api::IndexerApiServer;
 use sui_json_rpc::api::{validate_limit, IndexerApiClient, QUERY_MAX_RESULT_LIMIT};
-use sui_json_rpc::indexer_api::spawn_subscription;
+use sui_json_rpc::indexer_api::{
+    spawn_resumed_event_subscription, spawn_subscription, EVENT_REPLAY_PAGE_SIZE,
+};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    CheckpointId, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionResponse, SuiTransactionResponseQuery, TransactionsPage,
+    CheckpointId, DynamicFieldPage, EventFilter, EventPage, Filter, ObjectsPage, Page,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionResponse, SuiTransactionResponseQuery,
+    TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -28,6 +32,20 @@ use sui_types::query::TransactionFilter;
 use crate::errors::IndexerError;
 use crate::store::IndexerStore;
 
+/// Return the query used to replay historical events for `filter`. Filters which cannot be
+/// served by the events table are replayed from all events, and the results are matched against
+/// `filter` afterwards.
+fn event_replay_query(filter: &EventFilter) -> EventFilter {
+    match filter {
+        EventFilter::Transaction(_)
+        | EventFilter::MoveModule { .. }
+        | EventFilter::MoveEventType(_)
+        | EventFilter::Sender(_)
+        | EventFilter::TimeRange { .. } => filter.clone(),
+        _ => EventFilter::All(vec![]),
+    }
+}
+
 pub(crate) struct IndexerApi<S> {
     state: S,
     fullnode: HttpClient,
@@ -180,6 +198,22 @@ impl<S: IndexerStore> IndexerApi<S> {
                     is_descending,
                 )
             }
+            Some(
+                filter @ (TransactionFilter::Checkpoint { .. }
+                | TransactionFilter::TimeRange { .. }
+                | TransactionFilter::And(..)
+                | TransactionFilter::Or(..)),
+            ) => {
+                let indexer_seq_number = self
+                    .state
+                    .get_transaction_sequence_by_digest(cursor_str, is_descending)?;
+                self.state.get_transaction_digest_page_by_filter(
+                    &filter,
+                    indexer_seq_number,
+                    limit + 1,
+                    is_descending,
+                )
+            }
         }?;
 
         // digests here are of size (limit + 1), where the last one is the cursor for the next page
@@ -214,7 +248,7 @@ impl<S: IndexerStore> IndexerApi<S> {
 #[async_trait]
 impl<S> IndexerApiServer for IndexerApi<S>
 where
-    S: IndexerStore + Sync + Send + 'static,
+    S: IndexerStore + Clone + Sync + Send + 'static,
 {
     async fn get_owned_objects(
         &self,
@@ -286,15 +320,66 @@ where
             .await
     }
 
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.event_handler.subscribe(filter));
+    fn subscribe_event(
+        &self,
+        sink: SubscriptionSink,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+    ) -> SubscriptionResult {
+        let live = self.event_handler.subscribe(filter.clone());
+        let Some(cursor) = cursor else {
+            spawn_subscription(sink, live);
+            return Ok(());
+        };
+        let state = self.state.clone();
+        let query = event_replay_query(&filter);
+        let fetch_page = move |cursor| {
+            let page = if filter.requires_transaction_context() {
+                Err(IndexerError::NotImplementedError(format!(
+                    "Resuming subscriptions with filter [{filter:?}] is not supported by the Indexer."
+                )))
+            } else {
+                state
+                    .get_events(
+                        query.clone(),
+                        Some(cursor),
+                        Some(EVENT_REPLAY_PAGE_SIZE),
+                        false,
+                    )
+                    .map(|page| Page {
+                        data: page
+                            .data
+                            .into_iter()
+                            .filter(|e| filter.matches(e))
+                            .collect(),
+                        ..page
+                    })
+            };
+            future::ready(page.map_err(anyhow::Error::from))
+        };
+        // Events are stored, and queried, in the order they were indexed in.
+        let state = self.state.clone();
+        let position = move |id| {
+            let position = state.get_event(id).ok().and_then(|e| e.id);
+            future::ready(Ok::<_, anyhow::Error>(position))
+        };
+        spawn_resumed_event_subscription(sink, cursor, live, fetch_page, position);
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        spawn_subscription(sink, self.event_handler.subscribe_transactions(filter));
         Ok(())
     }
 }
 
 impl<S> SuiRpcModule for IndexerApi<S>
 where
-    S: IndexerStore + Sync + Send + 'static,
+    S: IndexerStore + Clone + Sync + Send + 'static,
 {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
@@ -186,6 +186,19 @@ impl TransactionBuilderServer for TransactionBuilderApi {
             .await
     }
 
+    async fn sponsor_transaction(
+        &self,
+        sender: SuiAddress,
+        tx_bytes: Base64,
+        sponsor: SuiAddress,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        self.fullnode
+            .sponsor_transaction(sender, tx_bytes, sponsor, gas, gas_budget)
+            .await
+    }
+
     async fn request_add_stake(
         &self,
         signer: SuiAddress,
@@ -48,14 +48,21 @@ impl WriteApiServer for WriteApi {
         tx_bytes: Base64,
         gas_price: Option<BigInt>,
         epoch: Option<EpochId>,
+        gas_profile: Option<bool>,
     ) -> RpcResult<DevInspectResults> {
         self.fullnode
-            .dev_inspect_transaction(sender_address, tx_bytes, gas_price, epoch)
+            .dev_inspect_transaction(sender_address, tx_bytes, gas_price, epoch, gas_profile)
             .await
     }
 
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse> {
-        self.fullnode.dry_run_transaction(tx_bytes).await
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        gas_profile: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse> {
+        self.fullnode
+            .dry_run_transaction(tx_bytes, gas_profile)
+            .await
     }
 }
 
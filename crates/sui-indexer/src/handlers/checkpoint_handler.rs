@@ -143,7 +143,13 @@ where
             let ws_guard = self.metrics.subscription_process_latency.start_timer();
             for tx in &checkpoint.transactions {
                 self.event_handler
-                    .process_events(&tx.effects, &tx.events)
+                    .process_tx(
+                        Some(&tx.transaction),
+                        &tx.effects,
+                        &tx.events,
+                        Some(tx.timestamp_ms),
+                        Some(next_cursor_sequence_number as u64),
+                    )
                     .await?;
             }
             ws_guard.stop_and_record();
@@ -13,6 +13,7 @@ use sui_types::error::SuiError;
 use sui_types::event::EventID;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
 use sui_types::storage::ObjectStore;
 
 use crate::errors::IndexerError;
@@ -135,6 +136,14 @@ pub trait IndexerStore {
         is_descending: bool,
     ) -> Result<Vec<String>, IndexerError>;
 
+    fn get_transaction_digest_page_by_filter(
+        &self,
+        filter: &TransactionFilter,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<String>, IndexerError>;
+
     fn get_transaction_sequence_by_digest(
         &self,
         tx_digest: Option<String>,
@@ -39,6 +39,7 @@ use sui_types::messages_checkpoint::{
     CheckpointCommitment, CheckpointSequenceNumber, ECMHLiveObjectSetDigest, EndOfEpochData,
 };
 use sui_types::object::ObjectRead;
+use sui_types::query::TransactionFilter;
 
 use crate::errors::{Context, IndexerError};
 use crate::models::checkpoints::Checkpoint;
@@ -58,7 +59,7 @@ use crate::schema::{
 use crate::store::diesel_marco::{read_only, transactional};
 use crate::store::indexer_store::TemporaryCheckpointStore;
 use crate::store::module_resolver::IndexerModuleResolver;
-use crate::store::query::DBFilter;
+use crate::store::query::{transaction_digest_page_sql, DBFilter};
 use crate::store::{IndexerStore, TemporaryEpochStore};
 use crate::types::SuiTransactionFullResponse;
 use crate::utils::{get_balance_changes_from_effect, get_object_changes};
@@ -363,7 +364,7 @@ impl IndexerStore for PgIndexerStore {
             } else {
                 boxed_query = boxed_query.order(events::id.asc());
             }
-            boxed_query.load(conn)
+            boxed_query.limit(page_limit as i64).load(conn)
         })
         .context("Failed reading events from PostgresDB")?;
 
@@ -945,6 +946,23 @@ impl IndexerStore for PgIndexerStore {
         )
     }
 
+    fn get_transaction_digest_page_by_filter(
+        &self,
+        filter: &TransactionFilter,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<String>, IndexerError> {
+        let sql_query = transaction_digest_page_sql(filter, start_sequence, limit, is_descending);
+        Ok(
+            read_only!(&self.cp, |conn| diesel::sql_query(sql_query).load(conn))
+                .context(&format!("Failed reading transaction digests by filter {filter:?} with start_sequence {start_sequence:?} and limit {limit}"))?
+                .into_iter()
+                .map(|table: TempDigestTable| table.digest_name)
+                .collect(),
+        )
+    }
+
     fn read_transactions(
         &self,
         last_processed_id: i64,
@@ -3,6 +3,7 @@
 
 use sui_json_rpc_types::SuiObjectDataFilter;
 use sui_types::base_types::ObjectID;
+use sui_types::query::TransactionFilter;
 
 pub trait DBFilter<C> {
     fn to_sql(&self, cursor: Option<C>, limit: usize, columns: Vec<&str>) -> String;
@@ -134,6 +135,94 @@ fn to_outer_clauses(filter: &SuiObjectDataFilter) -> Option<String> {
     }
 }
 
+/// Query for a page of transaction digests matching `filter`, paginated by `transactions.id`.
+/// Filters served by the transaction index tables are expressed as sub-queries, so that
+/// `And` / `Or` can combine any of them.
+pub fn transaction_digest_page_sql(
+    filter: &TransactionFilter,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    let clauses = to_transaction_clauses(filter);
+    let cursor = if let Some(start_sequence) = start_sequence {
+        if is_descending {
+            format!("\n  AND t.id < {start_sequence}")
+        } else {
+            format!("\n  AND t.id > {start_sequence}")
+        }
+    } else {
+        "".to_string()
+    };
+    let order = if is_descending { "DESC" } else { "ASC" };
+    format!(
+        "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE {clauses}{cursor}
+ORDER BY t.id {order}
+LIMIT {limit};"
+    )
+}
+
+fn to_transaction_clauses(filter: &TransactionFilter) -> String {
+    match filter {
+        TransactionFilter::MoveFunction {
+            package,
+            module,
+            function,
+        } => {
+            let module = if let Some(module) = module {
+                format!(" AND move_module = '{}'", escape(module))
+            } else {
+                "".to_string()
+            };
+            let function = if let Some(function) = function {
+                format!(" AND move_function = '{}'", escape(function))
+            } else {
+                "".to_string()
+            };
+            format!("t.transaction_digest IN (SELECT transaction_digest FROM move_calls WHERE move_package = '{package}'{module}{function})")
+        }
+        TransactionFilter::InputObject(id) => format!(
+            "t.transaction_digest IN (SELECT transaction_digest FROM input_objects WHERE object_id = '{id}')"
+        ),
+        TransactionFilter::ChangedObject(id) => format!(
+            "('{id}' = ANY(t.created) OR '{id}' = ANY(t.mutated) OR '{id}' = ANY(t.unwrapped))"
+        ),
+        TransactionFilter::FromAddress(a) => format!("t.sender = '{a}'"),
+        TransactionFilter::ToAddress(a) => format!(
+            "t.transaction_digest IN (SELECT transaction_digest FROM recipients WHERE recipient = '{a}')"
+        ),
+        TransactionFilter::FromAndToAddress { from, to } => format!(
+            "t.transaction_digest IN (SELECT transaction_digest FROM recipients WHERE recipient = '{to}' AND sender = '{from}')"
+        ),
+        TransactionFilter::TransactionKind(kind) => {
+            format!("t.transaction_kind = '{}'", escape(kind))
+        }
+        TransactionFilter::Checkpoint { start, end } => format!(
+            "(t.checkpoint_sequence_number >= {start} AND t.checkpoint_sequence_number < {end})"
+        ),
+        // transactions are stamped with the timestamp of their checkpoint
+        TransactionFilter::TimeRange { start_ms, end_ms } => {
+            format!("(t.timestamp_ms >= {start_ms} AND t.timestamp_ms < {end_ms})")
+        }
+        TransactionFilter::And(f1, f2) => format!(
+            "({} AND {})",
+            to_transaction_clauses(f1),
+            to_transaction_clauses(f2)
+        ),
+        TransactionFilter::Or(f1, f2) => format!(
+            "({} OR {})",
+            to_transaction_clauses(f1),
+            to_transaction_clauses(f2)
+        ),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
@@ -143,8 +232,9 @@ mod test {
 
     use sui_json_rpc_types::SuiObjectDataFilter;
     use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::query::TransactionFilter;
 
-    use crate::store::query::DBFilter;
+    use crate::store::query::{transaction_digest_page_sql, DBFilter};
 
     #[test]
     fn test_address_filter() {
@@ -235,4 +325,51 @@ WHERE t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted')
 LIMIT 100;";
         assert_eq!(expected_sql, filter.to_sql(None, 100, vec!["*"]));
     }
+
+    #[test]
+    fn test_transaction_and_or_filter() {
+        let sender = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let object_id = ObjectID::from_str(
+            "0xef9fb75a7b3d4cb5551ef0b08c83528b94d5f5cd8be28b1d08a87dbbf3731738",
+        )
+        .unwrap();
+        let filter = TransactionFilter::FromAddress(sender).and(
+            TransactionFilter::InputObject(object_id)
+                .or(TransactionFilter::ChangedObject(object_id)),
+        );
+        let expected_sql = "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE (t.sender = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381' AND (t.transaction_digest IN (SELECT transaction_digest FROM input_objects WHERE object_id = '0xef9fb75a7b3d4cb5551ef0b08c83528b94d5f5cd8be28b1d08a87dbbf3731738') OR ('0xef9fb75a7b3d4cb5551ef0b08c83528b94d5f5cd8be28b1d08a87dbbf3731738' = ANY(t.created) OR '0xef9fb75a7b3d4cb5551ef0b08c83528b94d5f5cd8be28b1d08a87dbbf3731738' = ANY(t.mutated) OR '0xef9fb75a7b3d4cb5551ef0b08c83528b94d5f5cd8be28b1d08a87dbbf3731738' = ANY(t.unwrapped))))
+  AND t.id < 42
+ORDER BY t.id DESC
+LIMIT 100;";
+        assert_eq!(
+            expected_sql,
+            transaction_digest_page_sql(&filter, Some(42), 100, true)
+        );
+    }
+
+    #[test]
+    fn test_transaction_time_range_filter() {
+        let sender = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let filter = TransactionFilter::FromAddress(sender).and(TransactionFilter::TimeRange {
+            start_ms: 1000,
+            end_ms: 2000,
+        });
+        let expected_sql = "SELECT t.transaction_digest AS digest_name
+FROM transactions t
+WHERE (t.sender = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381' AND (t.timestamp_ms >= 1000 AND t.timestamp_ms < 2000))
+ORDER BY t.id ASC
+LIMIT 50;";
+        assert_eq!(
+            expected_sql,
+            transaction_digest_page_sql(&filter, None, 50, false)
+        );
+    }
 }
@@ -0,0 +1,329 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use move_binary_format::file_format_common::VERSION_MAX;
+use move_binary_format::normalized::{
+    Module as NormalizedModule, Struct as NormalizedStruct, Type as NormalizedType,
+};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout, MoveValue};
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::event::Event;
+use sui_types::move_package::{normalize_modules, MovePackage};
+use sui_types::object::MoveObject;
+
+use crate::{SuiData, SuiEvent, SuiMoveValue, SuiParsedData};
+
+/// A source of the packages a [`PackageResolver`] decodes values with.
+#[async_trait]
+pub trait PackageStore: Send + Sync {
+    /// Returns the package stored at `id`, or `None` if there is no package at `id`.
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<Option<MovePackage>>;
+}
+
+#[async_trait]
+impl<S: PackageStore + ?Sized> PackageStore for Arc<S> {
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<Option<MovePackage>> {
+        self.as_ref().fetch(id).await
+    }
+}
+
+/// A [`PackageStore`] over a fixed set of packages, e.g. the packages read from a checkpoint
+/// archive.
+#[derive(Default)]
+pub struct InMemoryPackageStore {
+    packages: BTreeMap<ObjectID, MovePackage>,
+}
+
+impl InMemoryPackageStore {
+    pub fn new(packages: impl IntoIterator<Item = MovePackage>) -> Self {
+        Self {
+            packages: packages
+                .into_iter()
+                .map(|package| (package.id(), package))
+                .collect(),
+        }
+    }
+
+    pub fn insert(&mut self, package: MovePackage) {
+        self.packages.insert(package.id(), package);
+    }
+}
+
+#[async_trait]
+impl PackageStore for InMemoryPackageStore {
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<Option<MovePackage>> {
+        Ok(self.packages.get(&id).cloned())
+    }
+}
+
+/// Decodes the BCS bytes of Move values into their JSON-RPC representation, using only the
+/// packages that define their types, so that it does not need a fullnode's module resolver.
+///
+/// Packages are fetched from the store the first time they are needed, and their normalized
+/// modules are cached by the ID the package is stored at. A struct is looked up in the package
+/// its type tag points to, i.e. the version of the package that introduced it, and the types its
+/// fields refer to are looked up through that package's linkage table. Values of types introduced
+/// by a package upgrade are therefore decoded like any other.
+pub struct PackageResolver<S> {
+    store: S,
+    packages: RwLock<HashMap<ObjectID, Arc<Package>>>,
+}
+
+/// The parts of a package needed to build type layouts.
+struct Package {
+    /// ID the modules of the package refer to themselves by, shared by every version of it.
+    runtime_id: AccountAddress,
+    /// ID the package is stored at.
+    storage_id: ObjectID,
+    modules: BTreeMap<String, NormalizedModule>,
+    /// Maps each struct to the ID of the version of the package that introduced it.
+    type_origins: BTreeMap<(String, String), ObjectID>,
+    /// Maps the runtime ID of each dependency to the ID of the version the package links against.
+    linkage: BTreeMap<ObjectID, ObjectID>,
+}
+
+enum LayoutError {
+    /// The package stored at this ID has not been fetched yet.
+    MissingPackage(ObjectID),
+    Invalid(anyhow::Error),
+}
+
+impl<S: PackageStore> PackageResolver<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            packages: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the layout of `type_`, fetching the packages it depends on that are not cached yet.
+    pub async fn type_layout(&self, type_: &TypeTag) -> anyhow::Result<MoveTypeLayout> {
+        loop {
+            match self.cached_type_layout(type_) {
+                Ok(layout) => return Ok(layout),
+                Err(LayoutError::MissingPackage(id)) => self.fetch(id).await?,
+                Err(LayoutError::Invalid(error)) => return Err(error),
+            }
+        }
+    }
+
+    pub async fn struct_layout(&self, struct_tag: &StructTag) -> anyhow::Result<MoveStructLayout> {
+        let type_ = TypeTag::Struct(Box::new(struct_tag.clone()));
+        match self.type_layout(&type_).await? {
+            MoveTypeLayout::Struct(layout) => Ok(layout),
+            _ => unreachable!("The layout of a struct type is a struct layout"),
+        }
+    }
+
+    /// Decodes `bytes` as a value of type `type_`.
+    pub async fn decode_value(
+        &self,
+        type_: &TypeTag,
+        bytes: &[u8],
+    ) -> anyhow::Result<SuiMoveValue> {
+        let layout = self.type_layout(type_).await?;
+        Ok(MoveValue::simple_deserialize(bytes, &layout)?.into())
+    }
+
+    pub async fn decode_object(&self, object: MoveObject) -> anyhow::Result<SuiParsedData> {
+        let layout = self.struct_layout(&object.type_().clone().into()).await?;
+        SuiParsedData::try_from_object(object, layout)
+    }
+
+    pub async fn decode_event(
+        &self,
+        event: Event,
+        tx_digest: TransactionDigest,
+        event_seq: u64,
+        timestamp_ms: Option<u64>,
+    ) -> anyhow::Result<SuiEvent> {
+        let layout = self.struct_layout(&event.type_).await?;
+        Ok(SuiEvent::try_from_layout(
+            event,
+            tx_digest,
+            event_seq,
+            timestamp_ms,
+            &layout,
+        )?)
+    }
+
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<()> {
+        let package = self
+            .store
+            .fetch(id)
+            .await?
+            .ok_or_else(|| anyhow!("Package {id} not found"))?;
+        let package = Package::new(&package)?;
+        self.packages.write().unwrap().insert(id, Arc::new(package));
+        Ok(())
+    }
+
+    fn cached_package(&self, id: ObjectID) -> Result<Arc<Package>, LayoutError> {
+        self.packages
+            .read()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(LayoutError::MissingPackage(id))
+    }
+
+    fn cached_type_layout(&self, type_: &TypeTag) -> Result<MoveTypeLayout, LayoutError> {
+        Ok(match type_ {
+            TypeTag::Bool => MoveTypeLayout::Bool,
+            TypeTag::U8 => MoveTypeLayout::U8,
+            TypeTag::U16 => MoveTypeLayout::U16,
+            TypeTag::U32 => MoveTypeLayout::U32,
+            TypeTag::U64 => MoveTypeLayout::U64,
+            TypeTag::U128 => MoveTypeLayout::U128,
+            TypeTag::U256 => MoveTypeLayout::U256,
+            TypeTag::Address => MoveTypeLayout::Address,
+            TypeTag::Signer => MoveTypeLayout::Signer,
+            TypeTag::Vector(type_) => {
+                MoveTypeLayout::Vector(Box::new(self.cached_type_layout(type_)?))
+            }
+            TypeTag::Struct(struct_tag) => {
+                MoveTypeLayout::Struct(self.cached_struct_layout(struct_tag)?)
+            }
+        })
+    }
+
+    fn cached_struct_layout(
+        &self,
+        struct_tag: &StructTag,
+    ) -> Result<MoveStructLayout, LayoutError> {
+        let package = self.cached_package(struct_tag.address.into())?;
+        let struct_ = package.struct_(&struct_tag.module, &struct_tag.name)?;
+        if struct_.type_parameters.len() != struct_tag.type_params.len() {
+            return Err(anyhow!(
+                "Expected {} type arguments for {struct_tag}, got {}",
+                struct_.type_parameters.len(),
+                struct_tag.type_params.len()
+            )
+            .into());
+        }
+
+        let mut fields = Vec::with_capacity(struct_.fields.len());
+        for field in &struct_.fields {
+            let type_ = self.field_type_tag(&package, &field.type_, &struct_tag.type_params)?;
+            fields.push(MoveFieldLayout::new(
+                field.name.clone(),
+                self.cached_type_layout(&type_)?,
+            ));
+        }
+        Ok(MoveStructLayout::WithTypes {
+            type_: struct_tag.clone(),
+            fields,
+        })
+    }
+
+    /// Converts the type of a field of a struct defined in `package` to a type tag, given the type
+    /// arguments the struct is instantiated with.
+    fn field_type_tag(
+        &self,
+        package: &Package,
+        type_: &NormalizedType,
+        type_args: &[TypeTag],
+    ) -> Result<TypeTag, LayoutError> {
+        Ok(match type_ {
+            NormalizedType::Bool => TypeTag::Bool,
+            NormalizedType::U8 => TypeTag::U8,
+            NormalizedType::U16 => TypeTag::U16,
+            NormalizedType::U32 => TypeTag::U32,
+            NormalizedType::U64 => TypeTag::U64,
+            NormalizedType::U128 => TypeTag::U128,
+            NormalizedType::U256 => TypeTag::U256,
+            NormalizedType::Address => TypeTag::Address,
+            NormalizedType::Signer => TypeTag::Signer,
+            NormalizedType::Vector(type_) => {
+                TypeTag::Vector(Box::new(self.field_type_tag(package, type_, type_args)?))
+            }
+            NormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                let defining_id = self
+                    .cached_package(package.relocate(*address))?
+                    .defining_id(module, name)?;
+                let type_params = type_arguments
+                    .iter()
+                    .map(|type_| self.field_type_tag(package, type_, type_args))
+                    .collect::<Result<_, _>>()?;
+                TypeTag::Struct(Box::new(StructTag {
+                    address: defining_id.into(),
+                    module: module.clone(),
+                    name: name.clone(),
+                    type_params,
+                }))
+            }
+            NormalizedType::TypeParameter(idx) => type_args
+                .get(*idx as usize)
+                .cloned()
+                .ok_or_else(|| anyhow!("Type parameter {idx} out of bounds"))?,
+            NormalizedType::Reference(_) | NormalizedType::MutableReference(_) => {
+                return Err(anyhow!("Unexpected reference type in a struct field").into());
+            }
+        })
+    }
+}
+
+impl Package {
+    fn new(package: &MovePackage) -> anyhow::Result<Self> {
+        // We are on the read path - it's OK to use VERSION_MAX of the supported Move binary format.
+        let modules = normalize_modules(package.serialized_module_map().values(), VERSION_MAX)?;
+        let runtime_id = modules
+            .values()
+            .next()
+            .map(|module| module.address)
+            .ok_or_else(|| anyhow!("Package {} has no modules", package.id()))?;
+        Ok(Self {
+            runtime_id,
+            storage_id: package.id(),
+            modules,
+            type_origins: package.type_origin_map(),
+            linkage: package
+                .linkage_table()
+                .iter()
+                .map(|(runtime_id, upgrade_info)| (*runtime_id, upgrade_info.upgraded_id))
+                .collect(),
+        })
+    }
+
+    /// Returns the ID of the package this package loads the modules at `runtime_id` from.
+    fn relocate(&self, runtime_id: AccountAddress) -> ObjectID {
+        if runtime_id == self.runtime_id {
+            return self.storage_id;
+        }
+        let runtime_id = ObjectID::from(runtime_id);
+        self.linkage.get(&runtime_id).copied().unwrap_or(runtime_id)
+    }
+
+    fn struct_(&self, module: &IdentStr, name: &IdentStr) -> anyhow::Result<&NormalizedStruct> {
+        self.modules
+            .get(module.as_str())
+            .and_then(|module| module.structs.get(name))
+            .ok_or_else(|| anyhow!("Struct {}::{module}::{name} not found", self.storage_id))
+    }
+
+    fn defining_id(&self, module: &IdentStr, name: &IdentStr) -> anyhow::Result<ObjectID> {
+        self.type_origins
+            .get(&(module.to_string(), name.to_string()))
+            .copied()
+            .ok_or_else(|| anyhow!("No type origin for {}::{module}::{name}", self.storage_id))
+    }
+}
+
+impl From<anyhow::Error> for LayoutError {
+    fn from(error: anyhow::Error) -> Self {
+        LayoutError::Invalid(error)
+    }
+}
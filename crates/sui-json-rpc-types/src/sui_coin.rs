@@ -14,7 +14,7 @@ use sui_types::coin::CoinMetadata;
 use sui_types::error::SuiError;
 use sui_types::object::Object;
 
-use crate::Page;
+use crate::{Page, SuiCheckpointSequenceNumber};
 
 pub type CoinPage = Page<Coin, ObjectID>;
 
@@ -27,6 +27,15 @@ pub struct Balance {
     pub locked_balance: HashMap<EpochId, u128>,
 }
 
+/// The balance of one coin type owned by an address, after the execution of a checkpoint.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointBalance {
+    pub coin_type: String,
+    pub checkpoint: SuiCheckpointSequenceNumber,
+    pub total_balance: u128,
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Coin {
@@ -1,10 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+
 use fastcrypto::encoding::Base58;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
+use move_core_types::value::{MoveStruct, MoveStructLayout};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -12,10 +15,13 @@ use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
-use sui_types::error::SuiResult;
+use sui_types::error::{SuiError, SuiResult};
 use sui_types::event::{Event, EventEnvelope, EventID};
+use sui_types::object::{MoveObject, ObjectFormatOptions};
 
-use crate::{type_and_fields_from_move_struct, Page};
+use crate::{
+    type_and_fields_from_move_struct, Page, SuiTransactionEffects, SuiTransactionEffectsAPI,
+};
 
 pub type EventPage = Page<SuiEvent, EventID>;
 
@@ -77,6 +83,21 @@ impl SuiEvent {
         event_seq: u64,
         timestamp_ms: Option<u64>,
         resolver: &impl GetModule,
+    ) -> SuiResult<Self> {
+        let layout = MoveObject::get_layout_from_struct_tag(
+            event.type_.clone(),
+            ObjectFormatOptions::default(),
+            resolver,
+        )?;
+        Self::try_from_layout(event, tx_digest, event_seq, timestamp_ms, &layout)
+    }
+
+    pub fn try_from_layout(
+        event: Event,
+        tx_digest: TransactionDigest,
+        event_seq: u64,
+        timestamp_ms: Option<u64>,
+        layout: &MoveStructLayout,
     ) -> SuiResult<Self> {
         let Event {
             package_id,
@@ -88,7 +109,11 @@ impl SuiEvent {
 
         let bcs = contents.to_vec();
 
-        let move_struct = Event::move_event_to_move_struct(&type_, &contents, resolver)?;
+        let move_struct = MoveStruct::simple_deserialize(&contents, layout).map_err(|e| {
+            SuiError::ObjectSerializationError {
+                error: e.to_string(),
+            }
+        })?;
         let (type_, field) = type_and_fields_from_move_struct(&type_, move_struct);
 
         Ok(SuiEvent {
@@ -107,6 +132,45 @@ impl SuiEvent {
     }
 }
 
+/// Objects and addresses affected by the transaction which emitted an event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionEventContext {
+    /// Objects created, mutated or unwrapped by the transaction.
+    pub affected_objects: Vec<ObjectID>,
+    /// Owners of the objects created, mutated or unwrapped by the transaction.
+    pub recipients: Vec<SuiAddress>,
+}
+
+impl From<&SuiTransactionEffects> for TransactionEventContext {
+    fn from(effects: &SuiTransactionEffects) -> Self {
+        let changed_objects = effects.all_changed_objects();
+        Self {
+            affected_objects: changed_objects
+                .iter()
+                .map(|(o, _)| o.reference.object_id)
+                .collect(),
+            recipients: changed_objects
+                .iter()
+                .filter_map(|(o, _)| o.owner.get_owner_address().ok())
+                .collect(),
+        }
+    }
+}
+
+/// A [SuiEvent] together with the context of the transaction which emitted it, which is needed to
+/// match [EventFilter::Recipient] and [EventFilter::AffectedObject].
+#[derive(Clone, Debug)]
+pub struct SuiEventWithContext {
+    pub event: SuiEvent,
+    pub context: Arc<TransactionEventContext>,
+}
+
+impl From<SuiEventWithContext> for SuiEvent {
+    fn from(value: SuiEventWithContext) -> Self {
+        value.event
+    }
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum EventFilter {
@@ -119,6 +183,11 @@ pub enum EventFilter {
     ),
     /// Return events emitted in a specified Package.
     Package(ObjectID),
+    /// Return events emitted by transactions which created, mutated or unwrapped an object
+    /// owned by the given address.
+    Recipient(SuiAddress),
+    /// Return events emitted by transactions which created, mutated or unwrapped the given object.
+    AffectedObject(ObjectID),
     /// Return events emitted in a specified Move module.
     MoveModule {
         /// the Move package ID
@@ -151,10 +220,18 @@ pub enum EventFilter {
     Any(Vec<EventFilter>),
     And(Box<EventFilter>, Box<EventFilter>),
     Or(Box<EventFilter>, Box<EventFilter>),
+    /// Return events which do not match the given filter.
+    Not(Box<EventFilter>),
 }
 
 impl EventFilter {
-    fn try_matches(&self, item: &SuiEvent) -> SuiResult<bool> {
+    /// Match `item` against this filter. Filters on the emitting transaction, such as
+    /// [EventFilter::Recipient], never match if `context` is `None`.
+    fn try_matches(
+        &self,
+        item: &SuiEvent,
+        context: Option<&TransactionEventContext>,
+    ) -> SuiResult<bool> {
         Ok(match self {
             EventFilter::MoveEventType(event_type) => &item.type_ == event_type,
             EventFilter::MoveEventField { path, value } => {
@@ -165,14 +242,25 @@ impl EventFilter {
             EventFilter::MoveModule { package, module } => {
                 &item.transaction_module == module && &item.package_id == package
             }
-            EventFilter::All(filters) => filters.iter().all(|f| f.matches(item)),
-            EventFilter::Any(filters) => filters.iter().any(|f| f.matches(item)),
+            EventFilter::Recipient(recipient) => {
+                matches!(context, Some(c) if c.recipients.contains(recipient))
+            }
+            EventFilter::AffectedObject(object_id) => {
+                matches!(context, Some(c) if c.affected_objects.contains(object_id))
+            }
+            EventFilter::All(filters) => filters
+                .iter()
+                .all(|f| f.try_matches(item, context).unwrap_or_default()),
+            EventFilter::Any(filters) => filters
+                .iter()
+                .any(|f| f.try_matches(item, context).unwrap_or_default()),
             EventFilter::And(f1, f2) => {
-                EventFilter::All(vec![*(*f1).clone(), *(*f2).clone()]).matches(item)
+                f1.try_matches(item, context)? && f2.try_matches(item, context)?
             }
             EventFilter::Or(f1, f2) => {
-                EventFilter::Any(vec![*(*f1).clone(), *(*f2).clone()]).matches(item)
+                f1.try_matches(item, context)? || f2.try_matches(item, context)?
             }
+            EventFilter::Not(f) => !f.try_matches(item, context)?,
             EventFilter::Transaction(digest) => digest == &item.id.tx_digest,
 
             EventFilter::TimeRange {
@@ -194,11 +282,39 @@ impl EventFilter {
     pub fn or(self, other_filter: EventFilter) -> Self {
         Self::Any(vec![self, other_filter])
     }
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Whether matching this filter depends on the transaction which emitted the event.
+    pub fn requires_transaction_context(&self) -> bool {
+        match self {
+            EventFilter::Recipient(_) | EventFilter::AffectedObject(_) => true,
+            EventFilter::All(filters) | EventFilter::Any(filters) => {
+                filters.iter().any(|f| f.requires_transaction_context())
+            }
+            EventFilter::And(f1, f2) | EventFilter::Or(f1, f2) => {
+                f1.requires_transaction_context() || f2.requires_transaction_context()
+            }
+            EventFilter::Not(f) => f.requires_transaction_context(),
+            _ => false,
+        }
+    }
+
+    pub fn matches_with_context(&self, item: &SuiEvent, context: &TransactionEventContext) -> bool {
+        self.try_matches(item, Some(context)).unwrap_or_default()
+    }
 }
 
 impl Filter<SuiEvent> for EventFilter {
     fn matches(&self, item: &SuiEvent) -> bool {
-        self.try_matches(item).unwrap_or_default()
+        self.try_matches(item, None).unwrap_or_default()
+    }
+}
+
+impl Filter<SuiEventWithContext> for EventFilter {
+    fn matches(&self, item: &SuiEventWithContext) -> bool {
+        self.matches_with_context(&item.event, &item.context)
     }
 }
 
@@ -20,7 +20,7 @@ use sui_json::{primitive_type, SuiJsonValue};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::digests::{ObjectDigest, TransactionEventsDigest};
 use sui_types::error::{ExecutionError, SuiError};
-use sui_types::gas::GasCostSummary;
+use sui_types::gas::{GasCostSummary, GasProfile};
 use sui_types::messages::{
     Argument, CallArg, Command, ExecuteTransactionRequestType, ExecutionStatus, GenesisObject,
     InputObjectKind, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction, SenderSignedData,
@@ -37,7 +37,7 @@ use sui_types::storage::{DeleteKind, WriteKind};
 
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
-use crate::{Page, SuiEvent, SuiMovePackage, SuiObjectRef};
+use crate::{Filter, Page, SuiEvent, SuiMovePackage, SuiObjectRef};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Eq, Copy)]
@@ -241,6 +241,59 @@ impl SuiTransactionResponse {
     }
 }
 
+/// Whether the transaction matches the filter, or `None` if the response lacks the data needed to
+/// tell.
+fn try_matches(filter: &TransactionFilter, item: &SuiTransactionResponse) -> Option<bool> {
+    let data = &item.transaction.as_ref()?.data;
+    Some(match filter {
+        TransactionFilter::MoveFunction {
+            package,
+            module,
+            function,
+        } => data.move_calls().into_iter().any(|call| {
+            &call.package == package
+                && module.as_ref().map(|m| m == &call.module).unwrap_or(true)
+                && function
+                    .as_ref()
+                    .map(|f| f == &call.function)
+                    .unwrap_or(true)
+        }),
+        TransactionFilter::InputObject(object_id) => {
+            data.input_objects().any(|id| &id == object_id)
+        }
+        TransactionFilter::ChangedObject(object_id) => item
+            .effects
+            .as_ref()?
+            .all_changed_objects()
+            .iter()
+            .any(|(o, _)| &o.reference.object_id == object_id),
+        TransactionFilter::FromAddress(address) => data.sender() == address,
+        TransactionFilter::ToAddress(address) => item
+            .effects
+            .as_ref()?
+            .all_changed_objects()
+            .iter()
+            .any(|(o, _)| matches!(o.owner.get_owner_address(), Ok(a) if &a == address)),
+        TransactionFilter::FromAndToAddress { from, to } => {
+            TransactionFilter::FromAddress(*from).matches(item)
+                && TransactionFilter::ToAddress(*to).matches(item)
+        }
+        TransactionFilter::TransactionKind(kind) => data.transaction().name() == kind,
+        TransactionFilter::Checkpoint { start, end } => (*start..*end).contains(&item.checkpoint?),
+        TransactionFilter::TimeRange { start_ms, end_ms } => {
+            (*start_ms..*end_ms).contains(&item.timestamp_ms?)
+        }
+        TransactionFilter::And(f1, f2) => try_matches(f1, item)? && try_matches(f2, item)?,
+        TransactionFilter::Or(f1, f2) => try_matches(f1, item)? || try_matches(f2, item)?,
+    })
+}
+
+impl Filter<SuiTransactionResponse> for TransactionFilter {
+    fn matches(&self, item: &SuiTransactionResponse) -> bool {
+        try_matches(self, item).unwrap_or_default()
+    }
+}
+
 /// We are specifically ignoring events for now until events become more stable.
 impl PartialEq for SuiTransactionResponse {
     fn eq(&self, other: &Self) -> bool {
@@ -651,6 +704,9 @@ pub struct DryRunTransactionResponse {
     pub events: SuiTransactionEvents,
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
+    /// Breakdown of the gas charged, only present if a gas profile was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_profile: Option<GasProfile>,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -695,6 +751,9 @@ pub struct DevInspectResults {
     /// Execution error from executing the transaction commands
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Breakdown of the gas charged, only present if a gas profile was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_profile: Option<GasProfile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -719,6 +778,7 @@ impl DevInspectResults {
         effects: TransactionEffects,
         events: TransactionEvents,
         return_values: Result<Vec<ExecutionResult>, ExecutionError>,
+        gas_profile: Option<GasProfile>,
         resolver: &impl GetModule,
     ) -> Result<Self, anyhow::Error> {
         let tx_digest = *effects.transaction_digest();
@@ -753,6 +813,7 @@ impl DevInspectResults {
             events: SuiTransactionEvents::try_from(events, tx_digest, None, resolver)?,
             results,
             error,
+            gas_profile,
         })
     }
 }
@@ -912,6 +973,28 @@ impl SuiTransactionData {
             },
         }
     }
+
+    /// Return the IDs of the objects and packages read by this transaction, including gas
+    /// payment objects.
+    pub fn input_objects(&self) -> impl Iterator<Item = ObjectID> + '_ {
+        let gas = self.gas_data().payment.iter().map(|o| o.object_id);
+        let inputs = match self.transaction() {
+            SuiTransactionKind::ProgrammableTransaction(pt) => pt
+                .inputs
+                .iter()
+                .filter_map(|input| match input {
+                    SuiCallArg::Object(SuiObjectArg::ImmOrOwnedObject { object_id, .. })
+                    | SuiCallArg::Object(SuiObjectArg::SharedObject { object_id, .. }) => {
+                        Some(*object_id)
+                    }
+                    SuiCallArg::Pure(_) => None,
+                })
+                .chain(self.move_calls().into_iter().map(|call| call.package))
+                .collect(),
+            _ => vec![],
+        };
+        gas.chain(inputs)
+    }
 }
 
 impl Display for SuiTransactionData {
@@ -0,0 +1,10 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::a {
+    use d::d::A;
+
+    struct S has store, drop {
+        a: A
+    }
+}
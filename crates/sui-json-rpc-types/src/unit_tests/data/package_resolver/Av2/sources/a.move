@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::a {
+    use d::d::{A, B};
+
+    struct S has store, drop {
+        a: A
+    }
+
+    struct T has store, drop {
+        s: S,
+        a: A,
+        b: B,
+    }
+}
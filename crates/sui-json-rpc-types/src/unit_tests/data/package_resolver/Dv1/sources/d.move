@@ -0,0 +1,8 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module d::d {
+    struct A has store, drop {
+        x: u64
+    }
+}
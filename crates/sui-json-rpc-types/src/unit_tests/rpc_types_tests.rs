@@ -1,21 +1,33 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::anyhow;
+use move_binary_format::file_format::CompiledModule;
 use move_core_types::ident_str;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
-use move_core_types::value::{MoveStruct, MoveValue};
+use move_core_types::value::{
+    MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue,
+};
 
+use sui_framework::make_system_packages;
+use sui_framework_build::compiled_package::BuildConfig;
+use sui_types::balance::Balance;
 use sui_types::base_types::SequenceNumber;
-use sui_types::base_types::{ObjectID, SuiAddress};
-use sui_types::gas_coin::GasCoin;
-use sui_types::object::MoveObject;
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::event::{Event, EventID};
+use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::move_package::MovePackage;
+use sui_types::object::{MoveObject, OBJECT_START_VERSION};
 use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{SuiMoveStruct, SuiMoveValue};
+use crate::{
+    EventFilter, Filter, InMemoryPackageStore, PackageResolver, SuiData, SuiEvent, SuiMoveStruct,
+    SuiMoveValue, SuiParsedData, TransactionEventContext,
+};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -143,3 +155,212 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_event_filter_not_and_transaction_context() {
+    let package_id = ObjectID::random();
+    let recipient = SuiAddress::random_for_testing_only();
+    let affected_object = ObjectID::random();
+    let event = SuiEvent {
+        id: EventID {
+            tx_digest: TransactionDigest::random(),
+            event_seq: 0,
+        },
+        package_id,
+        transaction_module: ident_str!("test").to_owned(),
+        sender: SuiAddress::random_for_testing_only(),
+        type_: StructTag {
+            address: package_id.into(),
+            module: ident_str!("test").to_owned(),
+            name: ident_str!("TestEvent").to_owned(),
+            type_params: vec![],
+        },
+        parsed_json: serde_json::Value::Null,
+        bcs: vec![],
+        timestamp_ms: None,
+    };
+    let context = TransactionEventContext {
+        affected_objects: vec![affected_object],
+        recipients: vec![recipient],
+    };
+
+    let from_package = EventFilter::Package(package_id);
+    assert!(from_package.matches(&event));
+    assert!(!from_package.clone().not().matches(&event));
+    assert!(EventFilter::Package(ObjectID::random())
+        .not()
+        .matches(&event));
+
+    // Filters on the emitting transaction only match with its context.
+    let to_recipient = EventFilter::Recipient(recipient);
+    assert!(!to_recipient.matches(&event));
+    assert!(to_recipient.matches_with_context(&event, &context));
+    assert!(
+        !EventFilter::Recipient(SuiAddress::random_for_testing_only())
+            .matches_with_context(&event, &context)
+    );
+    assert!(EventFilter::AffectedObject(affected_object).matches_with_context(&event, &context));
+    assert!(!EventFilter::AffectedObject(ObjectID::random()).matches_with_context(&event, &context));
+
+    // All events from the package, except those of the given type.
+    let filter = from_package.and(EventFilter::MoveEventType(event.type_.clone()).not());
+    assert!(!filter.matches(&event));
+    assert!(!filter.requires_transaction_context());
+    assert!(to_recipient.not().requires_transaction_context());
+}
+
+#[tokio::test]
+async fn test_package_resolver_layouts() {
+    let resolver = PackageResolver::new(InMemoryPackageStore::new(make_system_packages()));
+
+    // The layout is built from the packages alone, and agrees with the one hard-coded for coins.
+    let layout = resolver.struct_layout(&GasCoin::type_()).await.unwrap();
+    assert_eq!(layout, GasCoin::layout());
+
+    let id = ObjectID::random();
+    let object = MoveObject::new_gas_coin(SequenceNumber::new(), id, 10000);
+    let expected = SuiParsedData::try_from_object(object.clone(), GasCoin::layout()).unwrap();
+    assert_eq!(resolver.decode_object(object).await.unwrap(), expected);
+
+    let values = vec![1u64, 2, 3];
+    let value = resolver
+        .decode_value(
+            &TypeTag::Vector(Box::new(TypeTag::U64)),
+            &bcs::to_bytes(&values).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        value,
+        SuiMoveValue::from(MoveValue::Vector(
+            values.into_iter().map(MoveValue::U64).collect()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_package_resolver_events() {
+    let resolver = PackageResolver::new(InMemoryPackageStore::new(make_system_packages()));
+    let balance_type = Balance::type_(GAS::type_tag());
+    let event = Event::new(
+        &SUI_FRAMEWORK_ADDRESS,
+        ident_str!("balance"),
+        SuiAddress::random_for_testing_only(),
+        balance_type.clone(),
+        bcs::to_bytes(&Balance::new(100)).unwrap(),
+    );
+
+    let tx_digest = TransactionDigest::random();
+    let event = resolver
+        .decode_event(event, tx_digest, 0, None)
+        .await
+        .unwrap();
+    assert_eq!(event.id.tx_digest, tx_digest);
+    assert_eq!(event.type_, balance_type);
+    assert_eq!(event.parsed_json["value"], "100");
+}
+
+#[tokio::test]
+async fn test_package_resolver_missing_package() {
+    let resolver = PackageResolver::new(InMemoryPackageStore::default());
+    let err = resolver.struct_layout(&GasCoin::type_()).await.unwrap_err();
+    assert!(err.to_string().contains("not found"));
+}
+
+#[tokio::test]
+async fn test_package_resolver_upgraded_package() {
+    let d_v1 = MovePackage::new_initial(
+        OBJECT_START_VERSION,
+        build_test_modules("Dv1"),
+        u64::MAX,
+        [],
+    )
+    .unwrap();
+    let d_v2 = d_v1
+        .new_upgraded(
+            ObjectID::from_single_byte(0xd2),
+            build_test_modules("Dv2"),
+            u64::MAX,
+            [],
+        )
+        .unwrap();
+    let a_v1 = MovePackage::new_initial(
+        OBJECT_START_VERSION,
+        build_test_modules("Av1"),
+        u64::MAX,
+        [&d_v1],
+    )
+    .unwrap();
+    // The upgrade of `a` is relinked against the upgrade of `d`.
+    let a_v2 = a_v1
+        .new_upgraded(
+            ObjectID::from_single_byte(0xa2),
+            build_test_modules("Av2"),
+            u64::MAX,
+            [&d_v2],
+        )
+        .unwrap();
+    let resolver = PackageResolver::new(InMemoryPackageStore::new([d_v1, d_v2, a_v1, a_v2]));
+
+    // `a::T` is introduced by the upgrade. Its fields refer to `a::S`, introduced by the original
+    // package, to `d::A`, introduced by the original dependency but loaded from its upgrade, and
+    // to `d::B`, introduced by the upgrade of the dependency.
+    let layout = resolver
+        .struct_layout(&test_struct_tag(0xa2, "a", "T"))
+        .await
+        .unwrap();
+    let a_layout = MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+        type_: test_struct_tag(0xd1, "d", "A"),
+        fields: vec![test_field("x", MoveTypeLayout::U64)],
+    });
+    let expected = MoveStructLayout::WithTypes {
+        type_: test_struct_tag(0xa2, "a", "T"),
+        fields: vec![
+            test_field(
+                "s",
+                MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+                    type_: test_struct_tag(0xa1, "a", "S"),
+                    fields: vec![test_field("a", a_layout.clone())],
+                }),
+            ),
+            test_field("a", a_layout),
+            test_field(
+                "b",
+                MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+                    type_: test_struct_tag(0xd2, "d", "B"),
+                    fields: vec![test_field("y", MoveTypeLayout::U64)],
+                }),
+            ),
+        ],
+    };
+    assert_eq!(layout, expected);
+
+    // Types introduced by the upgrade are not part of the original package.
+    assert!(resolver
+        .struct_layout(&test_struct_tag(0xa1, "a", "T"))
+        .await
+        .is_err());
+}
+
+fn build_test_modules(test_dir: &str) -> Vec<CompiledModule> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend(["src", "unit_tests", "data", "package_resolver", test_dir]);
+    sui_framework::build_move_package(&path, BuildConfig::new_for_testing())
+        .unwrap()
+        .get_modules()
+        .cloned()
+        .collect()
+}
+
+fn test_struct_tag(address: u8, module: &str, name: &str) -> StructTag {
+    StructTag {
+        address: ObjectID::from_single_byte(address).into(),
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+fn test_field(name: &str, layout: MoveTypeLayout) -> MoveFieldLayout {
+    MoveFieldLayout::new(Identifier::new(name).unwrap(), layout)
+}
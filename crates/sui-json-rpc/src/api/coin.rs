@@ -3,7 +3,9 @@
 
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
-use sui_json_rpc_types::{Balance, CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{
+    Balance, CheckpointBalance, CoinPage, SuiCheckpointSequenceNumber, SuiCoinMetadata,
+};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -47,6 +49,20 @@ pub trait CoinReadApi {
         coin_type: Option<String>,
     ) -> RpcResult<Balance>;
 
+    /// Return the total coin balance for one coin type, owned by the address owner after the
+    /// execution of a checkpoint. Only nodes which indexed every checkpoint since genesis serve
+    /// historical balances, up to the last checkpoint they indexed.
+    #[method(name = "getBalanceAtCheckpoint")]
+    async fn get_balance_at_checkpoint(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional type names for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to 0x2::sui::SUI if not specified.
+        coin_type: Option<String>,
+        /// the sequence number of the checkpoint
+        checkpoint: SuiCheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointBalance>;
+
     /// Return the total coin balance for all coin type, owned by the address owner.
     #[method(name = "getAllBalances")]
     async fn get_all_balances(
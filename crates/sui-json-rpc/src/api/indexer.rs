@@ -6,13 +6,15 @@ use jsonrpsee_proc_macros::rpc;
 
 use sui_json_rpc_types::{
     CheckpointId, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, SuiEvent,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionResponseQuery, TransactionsPage,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionResponse, SuiTransactionResponseQuery,
+    TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -48,6 +50,8 @@ pub trait IndexerApi {
     ) -> RpcResult<TransactionsPage>;
 
     /// Return list of events for a specified query criteria.
+    /// Filters without a dedicated index examine a bounded number of events per call, so a page
+    /// may hold fewer than `limit` events while `hasNextPage` is still true.
     #[method(name = "queryEvents")]
     async fn query_events(
         &self,
@@ -67,6 +71,18 @@ pub trait IndexerApi {
         &self,
         /// the filter criteria of the event stream, see the [Sui docs](https://docs.sui.io/build/pubsub#event-filters) for detailed examples.
         filter: EventFilter,
+        /// optional exclusive cursor. If provided, matching events emitted after the cursor are replayed before the live stream starts, so a dropped subscription can be resumed without missing events.
+        cursor: Option<EventID>,
+    );
+
+    /// Subscribe to a stream of Sui transactions.
+    /// Fullnodes stream transactions as they are executed, before they are checkpointed, and
+    /// reject filters by checkpoint.
+    #[subscription(name = "subscribeTransaction", item = SuiTransactionResponse)]
+    fn subscribe_transaction(
+        &self,
+        /// the filter criteria of the transaction stream.
+        filter: TransactionFilter,
     );
 
     /// Return the list of dynamic field objects owned by an object.
@@ -218,6 +218,23 @@ pub trait TransactionBuilder {
         txn_builder_mode: Option<SuiTransactionBuilderMode>,
     ) -> RpcResult<TransactionBytes>;
 
+    /// Create an unsigned sponsored transaction, whose gas is paid by a sponsor instead of its
+    /// sender. The transaction must be signed by both the sender and the sponsor.
+    #[method(name = "sponsorTransaction")]
+    async fn sponsor_transaction(
+        &self,
+        /// the transaction sender's Sui address
+        sender: SuiAddress,
+        /// BCS encoded TransactionKind(as opposed to TransactionData, which include gasBudget and gasPrice)
+        tx_bytes: Base64,
+        /// the Sui address of the sponsor paying for gas
+        sponsor: SuiAddress,
+        /// gas object to be used in this transaction, node will pick one from the sponsor's possession if not provided
+        gas: Option<ObjectID>,
+        /// the gas budget, the transaction will fail if the gas cost exceed the budget
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes>;
+
     /// Add stake to a validator's staking pool using multiple coins and amount.
     #[method(name = "requestAddStake")]
     async fn request_add_stake(
@@ -52,10 +52,19 @@ pub trait WriteApi {
         gas_price: Option<BigInt>,
         /// The epoch to perform the call. Will be set from the system state object if not provided
         epoch: Option<EpochId>,
+        /// Whether to include a breakdown of the gas charged per command and per Move function
+        /// in the results. Default to be False
+        gas_profile: Option<bool>,
     ) -> RpcResult<DevInspectResults>;
 
     /// Return transaction execution effects including the gas cost summary,
     /// while the effects are not committed to the chain.
     #[method(name = "dryRunTransaction")]
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse>;
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        /// Whether to include a breakdown of the gas charged per command and per Move function
+        /// in the response. Default to be False
+        gas_profile: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse>;
 }
@@ -12,8 +12,8 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 use tracing::debug;
 
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_types::{Balance, Coin as SuiCoin};
-use sui_json_rpc_types::{CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{Balance, CheckpointBalance, Coin as SuiCoin};
+use sui_json_rpc_types::{CoinPage, SuiCheckpointSequenceNumber, SuiCoinMetadata};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
 use sui_types::base_types::{MoveObjectType, ObjectID, ObjectRef, ObjectType, SuiAddress};
@@ -230,6 +230,59 @@ impl CoinReadApiServer for CoinReadApi {
         })
     }
 
+    async fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: SuiCheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointBalance> {
+        let coin_type = match coin_type {
+            Some(c) => parse_sui_struct_tag(&c)?,
+            None => GAS::type_(),
+        };
+        let sequence_number = u64::from(checkpoint);
+        match self
+            .state
+            .get_balance_history_watermark()
+            .map_err(Error::from)?
+        {
+            Some(watermark) if sequence_number > watermark => {
+                return Err(anyhow!(
+                    "Balances at checkpoint {sequence_number} have not been indexed, the latest \
+                     indexed checkpoint is {watermark}"
+                )
+                .into());
+            }
+            Some(_) => {}
+            None => {
+                return Err(anyhow!(
+                    "Historical balances are not available on this node, which did not index \
+                     checkpoints from genesis"
+                )
+                .into());
+            }
+        }
+
+        let balance = self
+            .state
+            .get_balance_at_checkpoint(
+                owner,
+                &TypeTag::Struct(Box::new(coin_type.clone())),
+                sequence_number,
+            )
+            .map_err(Error::from)?;
+        let total_balance = u128::try_from(balance).map_err(|_| {
+            Error::UnexpectedError(format!(
+                "Negative balance {balance} of {owner} at checkpoint {sequence_number}"
+            ))
+        })?;
+        Ok(CheckpointBalance {
+            coin_type: coin_type.to_string(),
+            checkpoint,
+            total_balance,
+        })
+    }
+
     async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
         // TODO: Add index to improve performance?
         let coins = self.get_owner_coin_iterator(owner, &None)?;
@@ -1,22 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{stream, Stream, StreamExt};
 use jsonrpsee::core::error::SubscriptionClosed;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::{Error as RpcError, RpcResult};
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 use serde::Serialize;
+use tokio::sync::OnceCell;
 use tracing::{debug, warn};
 
 use mysten_metrics::spawn_monitored_task;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    CheckpointId, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page,
+    CheckpointId, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent,
     SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionResponse,
     SuiTransactionResponseQuery, TransactionsPage,
 };
@@ -26,12 +28,17 @@ use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::error::UserInputError;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
 
 use crate::api::IndexerApiServer;
 use crate::api::ReadApiServer;
 use crate::api::{cap_page_limit, validate_limit, QUERY_MAX_RESULT_LIMIT_OBJECTS};
+use crate::error::Error;
 use crate::SuiRpcModule;
 
+/// Number of historical events fetched per query when resuming an event subscription.
+pub const EVENT_REPLAY_PAGE_SIZE: usize = 256;
+
 pub fn spawn_subscription<S, T>(mut sink: SubscriptionSink, rx: S)
 where
     S: Stream<Item = T> + Unpin + Send + 'static,
@@ -51,6 +58,108 @@ where
     });
 }
 
+/// Resume an event subscription from the exclusive `cursor`.
+/// Historical events are fetched page by page with `fetch_page` until the history is exhausted,
+/// after which the `live` stream takes over. `live` must be subscribed before calling this, so that
+/// events emitted during the replay are not lost; events seen in both are only sent once.
+/// `position` returns the position of an event in the order the history is fetched in, or `None`
+/// for an event missing from the history: the live events positioned up to the last event
+/// replayed were already sent.
+/// The subscription is rejected if the first page cannot be fetched, e.g. for an unknown cursor.
+pub fn spawn_resumed_event_subscription<S, F, Fut, P, PFut, K>(
+    mut sink: SubscriptionSink,
+    cursor: EventID,
+    live: S,
+    fetch_page: F,
+    position: P,
+) where
+    S: Stream<Item = SuiEvent> + Unpin + Send + 'static,
+    F: Fn(EventID) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<EventPage, anyhow::Error>> + Send + 'static,
+    P: Fn(EventID) -> PFut + Send + Sync + 'static,
+    PFut: Future<Output = Result<Option<K>, anyhow::Error>> + Send + 'static,
+    K: Ord + Send + Sync + 'static,
+{
+    spawn_monitored_task!(async move {
+        let first_page = match fetch_page(cursor).await {
+            Ok(page) => page,
+            Err(e) => {
+                if let Err(e) = sink.reject(RpcError::from(Error::from(e))) {
+                    warn!(error = ?e, "Failed to reject event subscription.");
+                }
+                return;
+            }
+        };
+        let next_cursor = first_page
+            .has_next_page
+            .then_some(first_page.next_cursor)
+            .flatten();
+        let fetch_page = Arc::new(fetch_page);
+        let remaining = stream::unfold(next_cursor, move |cursor| {
+            let fetch_page = fetch_page.clone();
+            async move {
+                match (*fetch_page)(cursor?).await {
+                    Ok(page) => {
+                        let next_cursor = page.has_next_page.then_some(page.next_cursor).flatten();
+                        Some((page.data, next_cursor))
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "Failed to replay events for subscription.");
+                        None
+                    }
+                }
+            }
+        })
+        .flat_map(stream::iter);
+
+        // Events indexed while we are replaying show up in both the history and the live stream,
+        // up to the last event replayed, or the cursor if there were none.
+        let last_replayed = Arc::new(Mutex::new(cursor));
+        let history = {
+            let last_replayed = last_replayed.clone();
+            stream::iter(first_page.data)
+                .chain(remaining)
+                .inspect(move |e| {
+                    *last_replayed.lock().unwrap() = e.id.clone();
+                })
+        };
+        // The live stream is only polled once the history is exhausted, when the watermark is
+        // final.
+        let position = Arc::new(position);
+        let watermark = Arc::new(OnceCell::new());
+        let live = live.filter(move |e| {
+            let position = position.clone();
+            let last_replayed = last_replayed.clone();
+            let watermark = watermark.clone();
+            let id = e.id.clone();
+            async move {
+                let watermark = watermark
+                    .get_or_init(|| async {
+                        let last_replayed = last_replayed.lock().unwrap().clone();
+                        match (*position)(last_replayed).await {
+                            Ok(watermark) => watermark,
+                            Err(e) => {
+                                warn!(error = ?e, "Failed to position the last replayed event.");
+                                None
+                            }
+                        }
+                    })
+                    .await;
+                match (watermark, (*position)(id).await) {
+                    (Some(watermark), Ok(Some(position))) => position > *watermark,
+                    (_, Err(e)) => {
+                        warn!(error = ?e, "Failed to position a live event.");
+                        true
+                    }
+                    _ => true,
+                }
+            }
+        });
+
+        spawn_subscription(sink, history.chain(live).boxed());
+    });
+}
+
 pub struct IndexerApi<R> {
     state: Arc<AuthorityState>,
     read_api: R,
@@ -172,23 +281,64 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         );
         let descending = descending_order.unwrap_or_default();
         let limit = cap_page_limit(limit);
-        // Retrieve 1 extra item for next cursor
-        let mut data = self
+        Ok(self
             .state
-            .query_events(query, cursor.clone(), limit + 1, descending)
-            .await?;
-        let has_next_page = data.len() > limit;
-        data.truncate(limit);
-        let next_cursor = data.last().map_or(cursor, |e| Some(e.id.clone()));
-        Ok(EventPage {
-            data,
-            next_cursor,
-            has_next_page,
-        })
+            .query_events(query, cursor, limit, descending)
+            .await?)
     }
 
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.state.event_handler.subscribe(filter));
+    fn subscribe_event(
+        &self,
+        sink: SubscriptionSink,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+    ) -> SubscriptionResult {
+        let live = self.state.event_handler.subscribe(filter.clone());
+        let Some(cursor) = cursor else {
+            spawn_subscription(sink, live);
+            return Ok(());
+        };
+        let state = self.state.clone();
+        let fetch_page = move |cursor| {
+            let state = state.clone();
+            let filter = filter.clone();
+            async move {
+                state
+                    .query_events(filter, Some(cursor), EVENT_REPLAY_PAGE_SIZE, false)
+                    .await
+            }
+        };
+        // Events are queried in the order of their transaction, then in the transaction.
+        let state = self.state.clone();
+        let position = move |id: EventID| {
+            let position = state
+                .get_transaction_seq(&id.tx_digest)
+                .map(|seq| seq.map(|seq| (seq, id.event_seq)));
+            async move { position }
+        };
+        spawn_resumed_event_subscription(sink, cursor, live, fetch_page, position);
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        mut sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        if filter.filters_by_checkpoint() {
+            let error = anyhow!(
+                "Transactions are streamed before they are checkpointed, \
+                 subscriptions cannot filter them by checkpoint"
+            );
+            if let Err(e) = sink.reject(RpcError::from(Error::from(error))) {
+                warn!(error = ?e, "Failed to reject transaction subscription.");
+            }
+            return Ok(());
+        }
+        spawn_subscription(
+            sink,
+            self.state.event_handler.subscribe_transactions(filter),
+        );
         Ok(())
     }
 
@@ -735,7 +735,7 @@ async fn get_display_object_by_type(
 
     // If there's any recent version of Display, give it to the client.
     // TODO: add support for version query.
-    if let Some(event) = events.pop() {
+    if let Some(event) = events.data.pop() {
         let display: DisplayVersionUpdatedEvent = bcs::from_bytes(&event.bcs[..])
             .map_err(|e| anyhow!("Failed to deserialize 'VersionUpdatedEvent': {e}"))?;
 
@@ -15,7 +15,7 @@ use sui_open_rpc::Module;
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
-    messages::TransactionData,
+    messages::{TransactionData, TransactionKind},
 };
 
 use fastcrypto::encoding::Base64;
@@ -333,6 +333,23 @@ impl TransactionBuilderServer for TransactionBuilderApi {
         Ok(TransactionBytes::from_data(data)?)
     }
 
+    async fn sponsor_transaction(
+        &self,
+        sender: SuiAddress,
+        tx_bytes: Base64,
+        sponsor: SuiAddress,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let tx_kind: TransactionKind =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
+        let data = self
+            .builder
+            .sponsored_transaction(sender, tx_kind, sponsor, gas, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
     async fn request_add_stake(
         &self,
         signer: SuiAddress,
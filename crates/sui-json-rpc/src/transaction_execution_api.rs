@@ -156,11 +156,12 @@ impl TransactionExecutionApi {
     async fn dry_run_transaction(
         &self,
         tx_bytes: Base64,
+        gas_profile: bool,
     ) -> Result<DryRunTransactionResponse, Error> {
         let (txn_data, txn_digest) = get_transaction_data_and_digest(tx_bytes)?;
         let (resp, written_objects, transaction_effects) = self
             .state
-            .dry_exec_transaction(txn_data.clone(), txn_digest)
+            .dry_exec_transaction(txn_data.clone(), txn_digest, gas_profile)
             .await?;
         let object_cache = ObjectProviderCache::new_with_cache(self.state.clone(), written_objects);
         let balance_changes =
@@ -179,6 +180,7 @@ impl TransactionExecutionApi {
             events: resp.events,
             object_changes,
             balance_changes,
+            gas_profile: resp.gas_profile,
         })
     }
 }
@@ -203,17 +205,29 @@ impl WriteApiServer for TransactionExecutionApi {
         tx_bytes: Base64,
         gas_price: Option<BigInt>,
         _epoch: Option<EpochId>,
+        gas_profile: Option<bool>,
     ) -> RpcResult<DevInspectResults> {
         let tx_kind: TransactionKind =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
         Ok(self
             .state
-            .dev_inspect_transaction(sender_address, tx_kind, gas_price.map(<u64>::from))
+            .dev_inspect_transaction(
+                sender_address,
+                tx_kind,
+                gas_price.map(<u64>::from),
+                gas_profile.unwrap_or_default(),
+            )
             .await?)
     }
 
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse> {
-        Ok(self.dry_run_transaction(tx_bytes).await?)
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        gas_profile: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse> {
+        Ok(self
+            .dry_run_transaction(tx_bytes, gas_profile.unwrap_or_default())
+            .await?)
     }
 }
 
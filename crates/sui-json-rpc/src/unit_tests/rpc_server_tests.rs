@@ -17,9 +17,10 @@ use sui_json::{call_args, type_args};
 use sui_json_rpc_types::ObjectChange;
 use sui_json_rpc_types::ObjectsPage;
 use sui_json_rpc_types::{
-    Balance, CoinPage, DelegatedStake, StakeStatus, SuiCoinMetadata, SuiExecutionStatus,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionEffectsAPI,
-    SuiTransactionResponse, SuiTransactionResponseOptions, TransactionBytes,
+    Balance, CheckpointBalance, CoinPage, DelegatedStake, StakeStatus, SuiCoinMetadata,
+    SuiExecutionStatus, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
+    TransactionBytes,
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_macros::sim_test;
@@ -115,7 +116,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
     let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
     let tx_bytes1 = tx_bytes.clone();
-    let dryrun_response = http_client.dry_run_transaction(tx_bytes).await?;
+    let dryrun_response = http_client.dry_run_transaction(tx_bytes, None).await?;
 
     let tx_response: SuiTransactionResponse = http_client
         .execute_transaction(
@@ -391,6 +392,39 @@ async fn test_get_balance() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_balance_at_checkpoint() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    // Wait for the genesis checkpoint to be executed
+    while http_client
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .is_err()
+    {
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    let result: CheckpointBalance = http_client
+        .get_balance_at_checkpoint(*address, None, 0.into())
+        .await?;
+    assert_eq!("0x2::sui::SUI", result.coin_type);
+    assert_eq!(
+        (DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT as u64 * DEFAULT_GAS_AMOUNT) as u128,
+        result.total_balance
+    );
+
+    // Checkpoints which have not been executed are rejected
+    assert!(http_client
+        .get_balance_at_checkpoint(*address, None, u64::MAX.into())
+        .await
+        .is_err());
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_metadata() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
@@ -862,7 +896,9 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
 
     let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
 
-    let dryrun_response = http_client.dry_run_transaction(tx_bytes.clone()).await?;
+    let dryrun_response = http_client
+        .dry_run_transaction(tx_bytes.clone(), None)
+        .await?;
 
     let executed_response = http_client
         .execute_transaction(
@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A reference external signer serving the keys of a plaintext keystore, or of a deterministic
+//! in-memory keystore, over its stdin and stdout or a Unix socket. Meant for tests, as it keeps
+//! the keys in memory unprotected.
+
+use clap::Parser;
+use std::io::{stdin, stdout, BufReader};
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_keys::external_signer::serve;
+use sui_keys::keystore::{FileBasedKeystore, InMemKeystore, Keystore};
+
+#[derive(Parser)]
+#[clap(name = "mock-signer", rename_all = "kebab-case")]
+struct Args {
+    /// Keystore file holding the keys to sign with. Defaults to an in-memory keystore with
+    /// `--num-keys` deterministic keys.
+    #[clap(long)]
+    keystore: Option<PathBuf>,
+    #[clap(long, default_value = "1")]
+    num_keys: usize,
+    /// Listen on this Unix socket instead of serving requests over stdin and stdout.
+    #[clap(long)]
+    socket: Option<PathBuf>,
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+    let keystore = Arc::new(match &args.keystore {
+        Some(path) => Keystore::from(FileBasedKeystore::new(path)?),
+        None => Keystore::from(InMemKeystore::new(args.num_keys)),
+    });
+
+    match args.socket {
+        #[cfg(unix)]
+        Some(path) => {
+            let listener = std::os::unix::net::UnixListener::bind(&path)?;
+            for stream in listener.incoming() {
+                let stream = stream?;
+                let keystore = keystore.clone();
+                std::thread::spawn(move || {
+                    if let Ok(reader) = stream.try_clone() {
+                        let _ = serve(keystore.as_ref(), BufReader::new(reader), stream);
+                    }
+                });
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        Some(_) => Err(anyhow::anyhow!(
+            "Unix sockets are not supported on this platform"
+        )),
+        None => serve(keystore.as_ref(), stdin().lock(), stdout().lock()),
+    }
+}
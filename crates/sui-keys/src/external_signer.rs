@@ -0,0 +1,458 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore whose private keys are held by a separate signer process.
+//!
+//! The keystore talks to the signer over a Unix socket, or over the stdin and stdout of a
+//! signer process it spawns. Messages are newline delimited JSON objects, one request followed
+//! by one response at a time:
+//! ```text
+//! {"method":"list_keys"}
+//! {"keys":["<Base64 encoded flag || pubkey>", ...]}
+//!
+//! {"method":"sign_hashed","params":{"address":"0x...","message":"<Base64 encoded message>"}}
+//! {"signature":"<Base64 encoded flag || sig || pk>"}
+//!
+//! {"method":"sign_secure","params":{"address":"0x...","intent_message":"<Base64 encoded BCS>"}}
+//! {"signature":"<Base64 encoded flag || sig || pk>"}
+//! ```
+//! For `sign_secure`, the signer signs the hash of the BCS serialized intent message, exactly
+//! as [Signature::new_secure] does. Failed requests are answered with `{"error":"<message>"}`.
+
+use anyhow::anyhow;
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::VerifyingKey;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tracing::warn;
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature,
+    SuiSignatureInner,
+};
+use sui_types::error::{SuiError, SuiResult};
+
+use crate::keystore::AccountKeystore;
+
+/// How to reach an external signer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExternalSignerConfig {
+    /// Path of the Unix socket the signer listens on.
+    Socket(PathBuf),
+    /// Program and arguments of a signer serving requests over its stdin and stdout.
+    Command(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    ListKeys,
+    SignHashed {
+        address: SuiAddress,
+        /// Base64 encoded message
+        message: String,
+    },
+    SignSecure {
+        address: SuiAddress,
+        /// Base64 encoded BCS serialized intent message
+        intent_message: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    /// Base64 encoded `flag || pubkey` of every key held by the signer
+    Keys(Vec<String>),
+    Signature(Signature),
+    Error(String),
+}
+
+/// How long to wait for a response of the external signer. Generous, as hardware signers may
+/// wait for the user to confirm a signature.
+const SIGNER_RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A keystore forwarding signing requests to an external signer, see the module documentation
+/// for the protocol. The signer is connected to on first use, and its keys are listed once. Keys
+/// can neither be added nor read.
+pub struct ExternalKeystore {
+    config: ExternalSignerConfig,
+    state: Mutex<SignerState>,
+}
+
+#[derive(Default)]
+struct SignerState {
+    /// Dropped when a request fails, to reconnect on the next one
+    connection: Option<SignerConnection>,
+    keys: Option<Vec<PublicKey>>,
+}
+
+struct SignerConnection {
+    reader: SignerReader,
+    writer: Box<dyn Write + Send>,
+    /// The signer process, if it was spawned by the keystore
+    child: Option<Child>,
+}
+
+enum SignerReader {
+    /// A socket whose reads time out after [SIGNER_RESPONSE_TIMEOUT]
+    #[cfg(unix)]
+    Socket(BufReader<UnixStream>),
+    /// Lines read from the stdout of the signer process by a separate thread, as pipes have no
+    /// read timeout
+    Process(Receiver<std::io::Result<String>>),
+}
+
+impl Drop for SignerConnection {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl SignerConnection {
+    fn connect(config: &ExternalSignerConfig) -> Result<Self, anyhow::Error> {
+        match config {
+            #[cfg(unix)]
+            ExternalSignerConfig::Socket(path) => {
+                let stream = UnixStream::connect(path)
+                    .map_err(|e| anyhow!("Can't connect to external signer at {:?}: {e}", path))?;
+                stream.set_read_timeout(Some(SIGNER_RESPONSE_TIMEOUT))?;
+                Ok(Self {
+                    reader: SignerReader::Socket(BufReader::new(stream.try_clone()?)),
+                    writer: Box::new(stream),
+                    child: None,
+                })
+            }
+            #[cfg(not(unix))]
+            ExternalSignerConfig::Socket(_) => Err(anyhow!(
+                "Unix socket signers are not supported on this platform"
+            )),
+            ExternalSignerConfig::Command(command) => {
+                let (program, args) = command
+                    .split_first()
+                    .ok_or_else(|| anyhow!("External signer command is empty"))?;
+                let mut child = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| anyhow!("Can't start external signer {:?}: {e}", program))?;
+                let stdin = child.stdin.take().expect("stdin is piped");
+                let stdout = child.stdout.take().expect("stdout is piped");
+                // The thread stops once the signer process is killed, or the connection dropped.
+                let (sender, lines) = channel();
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stdout).lines() {
+                        if sender.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok(Self {
+                    reader: SignerReader::Process(lines),
+                    writer: Box::new(stdin),
+                    child: Some(child),
+                })
+            }
+        }
+    }
+
+    fn request(&mut self, request: &SignerRequest) -> Result<SignerResponse, anyhow::Error> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+        Ok(serde_json::from_str(&self.reader.read_line()?)?)
+    }
+}
+
+impl SignerReader {
+    fn read_line(&mut self) -> Result<String, anyhow::Error> {
+        match self {
+            #[cfg(unix)]
+            SignerReader::Socket(reader) => {
+                let mut line = String::new();
+                match reader.read_line(&mut line) {
+                    Ok(0) => Err(connection_closed()),
+                    Ok(_) => Ok(line),
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                        ) =>
+                    {
+                        Err(timed_out())
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+            SignerReader::Process(lines) => match lines.recv_timeout(SIGNER_RESPONSE_TIMEOUT) {
+                Ok(line) => Ok(line?),
+                Err(RecvTimeoutError::Timeout) => Err(timed_out()),
+                Err(RecvTimeoutError::Disconnected) => Err(connection_closed()),
+            },
+        }
+    }
+}
+
+fn connection_closed() -> anyhow::Error {
+    anyhow!("External signer closed the connection")
+}
+
+fn timed_out() -> anyhow::Error {
+    anyhow!(
+        "External signer did not respond within {}s",
+        SIGNER_RESPONSE_TIMEOUT.as_secs()
+    )
+}
+
+impl SignerState {
+    /// Sends `request` to the signer, connecting to it first if needed.
+    fn request(
+        &mut self,
+        config: &ExternalSignerConfig,
+        request: &SignerRequest,
+    ) -> Result<SignerResponse, anyhow::Error> {
+        if self.connection.is_none() {
+            self.connection = Some(SignerConnection::connect(config)?);
+        }
+        let connection = self.connection.as_mut().expect("connected above");
+        match connection.request(request) {
+            Ok(SignerResponse::Error(e)) => Err(anyhow!("External signer error: {e}")),
+            Ok(response) => Ok(response),
+            Err(e) => {
+                // The response may still arrive, and must not be read as the response of the
+                // next request.
+                self.connection = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.config.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(ExternalKeystore::lazy(ExternalSignerConfig::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!("Keys cannot be added to an external signer"))
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        // An unreachable signer holds no keys, signing with it fails with the actual error.
+        self.list_keys().unwrap_or_else(|e| {
+            warn!(
+                "Can't list the keys of external signer {:?}: {e}",
+                self.config
+            );
+            vec![]
+        })
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "The private key of [{address}] is held by an external signer"
+        ))
+    }
+
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let signature = self.sign(SignerRequest::SignHashed {
+            address: *address,
+            message: Base64::encode(msg),
+        })?;
+        verify_hashed(&signature, *address, msg)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        Ok(signature)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?;
+        let signature = self.sign(SignerRequest::SignSecure {
+            address: *address,
+            intent_message: Base64::encode(bytes),
+        })?;
+        signature
+            .verify_secure(&intent_msg, *address)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        Ok(signature)
+    }
+}
+
+impl ExternalKeystore {
+    /// Connects to the signer described by `config` and lists its keys, failing if the signer
+    /// can't be reached.
+    pub fn new(config: ExternalSignerConfig) -> Result<Self, anyhow::Error> {
+        let keystore = Self::lazy(config);
+        keystore.list_keys()?;
+        Ok(keystore)
+    }
+
+    /// Creates a keystore connecting to the signer described by `config` on first use. This is
+    /// how keystores are loaded from the client config.
+    pub fn lazy(config: ExternalSignerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(SignerState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &ExternalSignerConfig {
+        &self.config
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, SignerState>, anyhow::Error> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("External signer connection poisoned by a panicked request"))
+    }
+
+    fn list_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        let mut state = self.state()?;
+        if let Some(keys) = &state.keys {
+            return Ok(keys.clone());
+        }
+        let keys: Vec<_> = match state.request(&self.config, &SignerRequest::ListKeys)? {
+            SignerResponse::Keys(keys) => keys
+                .iter()
+                .map(|key| {
+                    PublicKey::decode_base64(key)
+                        .map_err(|e| anyhow!("External signer returned an invalid key: {e}"))
+                })
+                .collect::<Result<_, _>>()?,
+            response => return Err(unexpected_response(&response)),
+        };
+        state.keys = Some(keys.clone());
+        Ok(keys)
+    }
+
+    fn sign(&self, request: SignerRequest) -> Result<Signature, signature::Error> {
+        let response = self
+            .state()
+            .and_then(|mut state| state.request(&self.config, &request))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        match response {
+            SignerResponse::Signature(signature) => Ok(signature),
+            response => Err(signature::Error::from_source(
+                unexpected_response(&response).to_string(),
+            )),
+        }
+    }
+}
+
+fn unexpected_response(response: &SignerResponse) -> anyhow::Error {
+    anyhow!("Unexpected response from external signer: {:?}", response)
+}
+
+/// Verifies a signature over a hashed message, as created by [Signature::new_hashed], against the
+/// key of `address`.
+fn verify_hashed(signature: &Signature, address: SuiAddress, msg: &[u8]) -> SuiResult<()> {
+    fn verify<S: SuiSignatureInner>(
+        signature: &S,
+        address: SuiAddress,
+        msg: &[u8],
+    ) -> SuiResult<()> {
+        let (sig, pk) = signature.get_verification_inputs(address)?;
+        pk.verify(msg, &sig)
+            .map_err(|e| SuiError::InvalidSignature {
+                error: e.to_string(),
+            })
+    }
+    match signature {
+        Signature::Ed25519SuiSignature(signature) => verify(signature, address, msg),
+        Signature::Secp256k1SuiSignature(signature) => verify(signature, address, msg),
+        Signature::Secp256r1SuiSignature(signature) => verify(signature, address, msg),
+    }
+}
+
+/// Serves the external signer protocol for the keys of `keystore`, one request per line of
+/// `reader`, until `reader` is closed. This is all a signer needs to do on top of managing its
+/// keys, and is used by the `mock-signer` binary.
+pub fn serve<K, R, W>(keystore: &K, reader: R, mut writer: W) -> Result<(), anyhow::Error>
+where
+    K: AccountKeystore,
+    R: BufRead,
+    W: Write,
+{
+    for line in reader.lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(request) => handle_request(keystore, request),
+            Err(e) => SignerResponse::Error(format!("Invalid request: {e}")),
+        };
+        let mut line = serde_json::to_string(&response)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes())?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn handle_request<K: AccountKeystore>(keystore: &K, request: SignerRequest) -> SignerResponse {
+    let result = match request {
+        SignerRequest::ListKeys => {
+            return SignerResponse::Keys(
+                keystore
+                    .keys()
+                    .iter()
+                    .map(EncodeDecodeBase64::encode_base64)
+                    .collect(),
+            )
+        }
+        SignerRequest::SignHashed { address, message } => Base64::decode(&message)
+            .map_err(|e| anyhow!("Invalid message: {e}"))
+            .and_then(|msg| Ok(keystore.sign_hashed(&address, &msg)?)),
+        SignerRequest::SignSecure {
+            address,
+            intent_message,
+        } => Base64::decode(&intent_message)
+            .map_err(|e| anyhow!("Invalid intent message: {e}"))
+            .and_then(|bytes| {
+                let mut hasher = DefaultHash::default();
+                hasher.update(&bytes);
+                Ok(keystore.sign_hashed(&address, &hasher.finalize().digest)?)
+            }),
+    };
+    match result {
+        Ok(signature) => SignerResponse::Signature(signature),
+        Err(e) => SignerResponse::Error(e.to_string()),
+    }
+}
@@ -1,11 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
 use anyhow::anyhow;
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
-use rand::{rngs::StdRng, SeedableRng};
+use fastcrypto::encoding::{Base64, Encoding};
+use hmac::Hmac;
+use rand::rngs::OsRng;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha256;
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::collections::BTreeMap;
 use std::fmt::Write;
@@ -21,6 +27,7 @@ use sui_types::crypto::{
     SignatureScheme, SuiKeyPair,
 };
 
+use crate::external_signer::ExternalKeystore;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 
 #[derive(Serialize, Deserialize)]
@@ -28,6 +35,8 @@ use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileBasedKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -91,6 +100,61 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                writeln!(writer, "Keystore Path : {:?}", file.path)?;
+                write!(
+                    writer,
+                    "Keystore State : {}",
+                    if file.is_locked() {
+                        "Locked"
+                    } else {
+                        "Unlocked"
+                    }
+                )?;
+                write!(f, "{}", writer)
+            }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External")?;
+                write!(writer, "Keystore Signer : {:?}", external.config())?;
+                write!(f, "{}", writer)
+            }
+        }
+    }
+}
+
+impl Keystore {
+    /// Opens the keystore file at `path`, which may be either a plaintext or an encrypted
+    /// keystore. Encrypted keystores are returned locked.
+    pub fn open(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        if EncryptedFileBasedKeystore::is_encrypted(path) {
+            Ok(Keystore::Encrypted(EncryptedFileBasedKeystore::new(path)?))
+        } else {
+            Ok(Keystore::File(FileBasedKeystore::new(path)?))
+        }
+    }
+
+    /// Whether the private keys of this keystore are currently unavailable. Only encrypted
+    /// keystores can be locked.
+    pub fn is_locked(&self) -> bool {
+        match self {
+            Keystore::Encrypted(keystore) => keystore.is_locked(),
+            Keystore::File(_) | Keystore::InMem(_) | Keystore::External(_) => false,
+        }
+    }
+
+    /// Unlocks an encrypted keystore with `passphrase`, this is a no-op for other keystores.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        match self {
+            Keystore::Encrypted(keystore) => keystore.unlock(passphrase),
+            Keystore::File(_) | Keystore::InMem(_) | Keystore::External(_) => Ok(()),
+        }
+    }
+
+    /// Drops the decrypted private keys of an encrypted keystore from memory.
+    pub fn lock(&mut self) {
+        if let Keystore::Encrypted(keystore) = self {
+            keystore.lock()
         }
     }
 }
@@ -179,8 +243,16 @@ impl FileBasedKeystore {
                 File::open(path)
                     .map_err(|e| anyhow!("Can't open FileBasedKeystore from {:?}: {e}", path))?,
             );
-            let kp_strings: Vec<String> = serde_json::from_reader(reader)
-                .map_err(|e| anyhow!("Can't deserialize FileBasedKeystore from {:?}: {e}", path))?;
+            let kp_strings: Vec<String> = serde_json::from_reader(reader).map_err(|e| {
+                if EncryptedFileBasedKeystore::is_encrypted(path) {
+                    anyhow!(
+                        "Keystore {:?} is encrypted, open it as an Encrypted keystore",
+                        path
+                    )
+                } else {
+                    anyhow!("Can't deserialize FileBasedKeystore from {:?}: {e}", path)
+                }
+            })?;
             kp_strings
                 .iter()
                 .map(|kpstr| {
@@ -203,6 +275,10 @@ impl FileBasedKeystore {
         self.path = Some(path.to_path_buf());
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn save(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
             let store = serde_json::to_string_pretty(
@@ -223,6 +299,386 @@ impl FileBasedKeystore {
     }
 }
 
+pub const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+/// Number of PBKDF2-HMAC-SHA256 iterations used to derive the encryption key of new keystores.
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+const KDF_SALT_LENGTH: usize = 16;
+const AEAD_NONCE_LENGTH: usize = 12;
+const AEAD_KEY_LENGTH: usize = 32;
+/// Encrypted with the derived key so that a passphrase can be checked even when the keystore
+/// holds no keys.
+const PASSPHRASE_CHECK: &[u8] = b"sui encrypted keystore";
+
+/// On disk representation of an [EncryptedFileBasedKeystore].
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    kdf: KdfParams,
+    passphrase_check: EncryptedData,
+    keys: Vec<EncryptedKey>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct KdfParams {
+    iterations: u32,
+    /// Base64 encoded salt
+    salt: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct EncryptedData {
+    /// Base64 encoded AES-256-GCM nonce
+    nonce: String,
+    /// Base64 encoded ciphertext and authentication tag
+    ciphertext: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct EncryptedKey {
+    /// Base64 encoded `flag || pubkey`, kept in the clear so that addresses can be listed
+    /// without the passphrase. It is authenticated as associated data of the ciphertext.
+    public_key: String,
+    #[serde(flatten)]
+    data: EncryptedData,
+}
+
+/// A file based keystore whose private keys are encrypted at rest with a key derived from a
+/// passphrase. Every key pair is individually encrypted with AES-256-GCM using a key derived
+/// with PBKDF2-HMAC-SHA256.
+///
+/// The keystore is locked after being loaded: public keys and addresses are available, but the
+/// keystore must be unlocked with its passphrase before keys can be used to sign or new keys can
+/// be added.
+pub struct EncryptedFileBasedKeystore {
+    path: PathBuf,
+    kdf: KdfParams,
+    passphrase_check: EncryptedData,
+    keys: BTreeMap<SuiAddress, (PublicKey, EncryptedData)>,
+    unlocked: Option<UnlockedKeys>,
+}
+
+struct UnlockedKeys {
+    cipher: Aes256Gcm,
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(
+            msg,
+            self.unlocked_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.unlocked_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+
+    fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        let unlocked = self.unlocked.as_mut().ok_or_else(locked_error)?;
+        let public_key = keypair.public();
+        let address: SuiAddress = (&public_key).into();
+        let data = encrypt(
+            &unlocked.cipher,
+            keypair.encode_base64().as_bytes(),
+            &public_key.encode_base64(),
+        )?;
+        unlocked.keys.insert(address, keypair);
+        self.keys.insert(address, (public_key, data));
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.keys.values().map(|(key, _)| key.clone()).collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        self.unlocked_key(address)
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Loads the encrypted keystore at `path`, which is returned locked.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let reader =
+            BufReader::new(File::open(path).map_err(|e| {
+                anyhow!("Can't open EncryptedFileBasedKeystore from {:?}: {e}", path)
+            })?);
+        let file: EncryptedKeystoreFile = serde_json::from_reader(reader).map_err(|e| {
+            anyhow!(
+                "Can't deserialize EncryptedFileBasedKeystore from {:?}: {e}",
+                path
+            )
+        })?;
+        if file.version != ENCRYPTED_KEYSTORE_VERSION {
+            return Err(anyhow!(
+                "Unsupported encrypted keystore version {} in {:?}",
+                file.version,
+                path
+            ));
+        }
+        let keys = file
+            .keys
+            .into_iter()
+            .map(|key| {
+                let public_key = PublicKey::decode_base64(&key.public_key)
+                    .map_err(|e| anyhow!("Invalid public key in {:?}: {e}", path))?;
+                Ok(((&public_key).into(), (public_key, key.data)))
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            kdf: file.kdf,
+            passphrase_check: file.passphrase_check,
+            keys,
+            unlocked: None,
+        })
+    }
+
+    /// Creates a new, empty and unlocked keystore at `path` protected by `passphrase`.
+    pub fn create(path: &Path, passphrase: &str) -> Result<Self, anyhow::Error> {
+        Self::create_with_kdf_iterations(path, passphrase, DEFAULT_KDF_ITERATIONS)
+    }
+
+    pub fn create_with_kdf_iterations(
+        path: &Path,
+        passphrase: &str,
+        kdf_iterations: u32,
+    ) -> Result<Self, anyhow::Error> {
+        if path.exists() {
+            return Err(anyhow!("Keystore {:?} already exists", path));
+        }
+        let keystore = Self::new_unlocked(path, passphrase, kdf_iterations)?;
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Converts the plaintext [FileBasedKeystore] at `path` into an encrypted keystore protected
+    /// by `passphrase`, replacing the original file. Returns the unlocked keystore.
+    pub fn encrypt_in_place(path: &Path, passphrase: &str) -> Result<Self, anyhow::Error> {
+        Self::encrypt_in_place_with_kdf_iterations(path, passphrase, DEFAULT_KDF_ITERATIONS)
+    }
+
+    pub fn encrypt_in_place_with_kdf_iterations(
+        path: &Path,
+        passphrase: &str,
+        kdf_iterations: u32,
+    ) -> Result<Self, anyhow::Error> {
+        if Self::is_encrypted(path) {
+            return Err(anyhow!("Keystore {:?} is already encrypted", path));
+        }
+        let plaintext = FileBasedKeystore::new(&path.to_path_buf())?;
+        let mut keystore = Self::new_unlocked(path, passphrase, kdf_iterations)?;
+        let unlocked = keystore.unlocked.as_mut().ok_or_else(locked_error)?;
+        for keypair in plaintext.keys.into_values() {
+            let public_key = keypair.public();
+            let address: SuiAddress = (&public_key).into();
+            let data = encrypt(
+                &unlocked.cipher,
+                keypair.encode_base64().as_bytes(),
+                &public_key.encode_base64(),
+            )?;
+            unlocked.keys.insert(address, keypair);
+            keystore.keys.insert(address, (public_key, data));
+        }
+        // Write to a temporary file first so that the keys are never lost if the write fails.
+        let tmp_path = path.with_extension("tmp");
+        keystore.write(&tmp_path)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(keystore)
+    }
+
+    /// Whether the file at `path` holds an encrypted keystore.
+    pub fn is_encrypted(path: &Path) -> bool {
+        File::open(path)
+            .ok()
+            .and_then(|file| {
+                serde_json::from_reader::<_, EncryptedKeystoreFile>(BufReader::new(file)).ok()
+            })
+            .is_some()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.is_none()
+    }
+
+    /// Decrypts all private keys with the key derived from `passphrase`. Fails without changing
+    /// the state of the keystore if the passphrase is wrong.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let salt = Base64::decode(&self.kdf.salt).map_err(|e| anyhow!("Invalid KDF salt: {e}"))?;
+        let cipher = derive_cipher(passphrase, &salt, self.kdf.iterations)?;
+        if decrypt(&cipher, &self.passphrase_check, "").ok().as_deref() != Some(PASSPHRASE_CHECK) {
+            return Err(anyhow!("Incorrect passphrase for keystore {:?}", self.path));
+        }
+        let keys = self
+            .keys
+            .iter()
+            .map(|(address, (public_key, data))| {
+                let plaintext = decrypt(&cipher, data, &public_key.encode_base64())?;
+                let keypair = SuiKeyPair::decode_base64(std::str::from_utf8(&plaintext)?)
+                    .map_err(|e| anyhow!("Invalid key pair for address [{address}]: {e}"))?;
+                if keypair.public() != *public_key {
+                    return Err(anyhow!("Key pair does not match address [{address}]"));
+                }
+                Ok((*address, keypair))
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+        self.unlocked = Some(UnlockedKeys { cipher, keys });
+        Ok(())
+    }
+
+    pub fn lock(&mut self) {
+        self.unlocked = None;
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        self.write(&self.path)
+    }
+
+    fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let file = EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: self.kdf.clone(),
+            passphrase_check: self.passphrase_check.clone(),
+            keys: self
+                .keys
+                .values()
+                .map(|(public_key, data)| EncryptedKey {
+                    public_key: public_key.encode_base64(),
+                    data: data.clone(),
+                })
+                .collect(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    fn new_unlocked(
+        path: &Path,
+        passphrase: &str,
+        kdf_iterations: u32,
+    ) -> Result<Self, anyhow::Error> {
+        if kdf_iterations == 0 {
+            return Err(anyhow!("KDF iterations must be non zero"));
+        }
+        let mut salt = [0u8; KDF_SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = derive_cipher(passphrase, &salt, kdf_iterations)?;
+        let passphrase_check = encrypt(&cipher, PASSPHRASE_CHECK, "")?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            kdf: KdfParams {
+                iterations: kdf_iterations,
+                salt: Base64::encode(salt),
+            },
+            passphrase_check,
+            keys: BTreeMap::new(),
+            unlocked: Some(UnlockedKeys {
+                cipher,
+                keys: BTreeMap::new(),
+            }),
+        })
+    }
+
+    fn unlocked_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        let unlocked = self.unlocked.as_ref().ok_or_else(locked_error)?;
+        unlocked
+            .keys
+            .get(address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))
+    }
+}
+
+fn locked_error() -> anyhow::Error {
+    anyhow!("Keystore is locked, unlock it with its passphrase first")
+}
+
+fn derive_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: u32,
+) -> Result<Aes256Gcm, anyhow::Error> {
+    let mut key = [0u8; AEAD_KEY_LENGTH];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), salt, iterations, &mut key);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!("Invalid key: {e}"));
+    key.fill(0);
+    cipher
+}
+
+fn encrypt(cipher: &Aes256Gcm, msg: &[u8], aad: &str) -> Result<EncryptedData, anyhow::Error> {
+    let mut nonce = [0u8; AEAD_NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt keystore entry"))?;
+    Ok(EncryptedData {
+        nonce: Base64::encode(nonce),
+        ciphertext: Base64::encode(ciphertext),
+    })
+}
+
+fn decrypt(cipher: &Aes256Gcm, data: &EncryptedData, aad: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let nonce = Base64::decode(&data.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+    if nonce.len() != AEAD_NONCE_LENGTH {
+        return Err(anyhow!("Invalid nonce length {}", nonce.len()));
+    }
+    let ciphertext =
+        Base64::decode(&data.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+    cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to decrypt keystore entry"))
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
@@ -5,8 +5,12 @@ use std::str::FromStr;
 use fastcrypto::hash::HashFunction;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use shared_crypto::intent::Intent;
+use sui_keys::external_signer::{serve, ExternalKeystore, ExternalSignerConfig};
+use sui_keys::keystore::{
+    AccountKeystore, EncryptedFileBasedKeystore, FileBasedKeystore, InMemKeystore, Keystore,
+};
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, SignatureScheme, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +73,186 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+// Keep the tests fast, the default iteration count is only needed to slow down brute forcing.
+const TEST_KDF_ITERATIONS: u32 = 10;
+
+#[test]
+fn encrypted_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(EncryptedFileBasedKeystore::create_with_kdf_iterations(
+        &keystore_path,
+        "passphrase",
+        TEST_KDF_ITERATIONS,
+    )?);
+    assert!(!keystore.is_locked());
+    let (address, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+
+    // Keys are listed but cannot be used while the keystore is locked
+    let mut keystore = Keystore::open(&keystore_path)?;
+    assert!(keystore.is_locked());
+    assert_eq!(keystore.addresses(), vec![address]);
+    assert!(keystore
+        .sign_secure(&address, &"msg", Intent::default())
+        .is_err());
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None)
+        .is_err());
+
+    assert!(keystore.unlock("wrong passphrase").is_err());
+    assert!(keystore.is_locked());
+    keystore.unlock("passphrase")?;
+    keystore.sign_secure(&address, &"msg", Intent::default())?;
+    assert!(keystore.to_string().contains("Encrypted"));
+
+    keystore.lock();
+    assert!(keystore.get_key(&address).is_err());
+
+    // The private key is not stored in the clear
+    let contents = std::fs::read_to_string(&keystore_path)?;
+    let mut unlocked = Keystore::open(&keystore_path)?;
+    unlocked.unlock("passphrase")?;
+    assert!(!contents.contains(&unlocked.get_key(&address)?.encode_base64()));
+    Ok(())
+}
+
+#[test]
+fn encrypt_keystore_in_place_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let (address, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+    let expected = keystore.get_key(&address)?.encode_base64();
+
+    EncryptedFileBasedKeystore::encrypt_in_place_with_kdf_iterations(
+        &keystore_path,
+        "passphrase",
+        TEST_KDF_ITERATIONS,
+    )?;
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    assert!(
+        EncryptedFileBasedKeystore::encrypt_in_place_with_kdf_iterations(
+            &keystore_path,
+            "passphrase",
+            TEST_KDF_ITERATIONS,
+        )
+        .is_err()
+    );
+
+    let mut keystore = Keystore::open(&keystore_path)?;
+    assert!(matches!(keystore, Keystore::Encrypted(_)));
+    keystore.unlock("passphrase")?;
+    assert_eq!(keystore.get_key(&address)?.encode_base64(), expected);
+    Ok(())
+}
+
+fn check_external_keystore(keystore: &Keystore, expected: &InMemKeystore) {
+    assert_eq!(keystore.addresses(), expected.addresses());
+    let address = expected.addresses()[0];
+    let intent = Intent::default();
+    let signature = keystore
+        .sign_secure(&address, &"msg", intent.clone())
+        .unwrap();
+    assert_eq!(
+        signature,
+        expected.sign_secure(&address, &"msg", intent).unwrap()
+    );
+    assert_eq!(
+        keystore.sign_hashed(&address, b"msg").unwrap(),
+        expected.sign_hashed(&address, b"msg").unwrap()
+    );
+    // Errors of the signer are forwarded
+    assert!(keystore
+        .sign_hashed(&SuiAddress::random_for_testing_only(), b"msg")
+        .is_err());
+    assert!(keystore.get_key(&address).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_socket_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path)?;
+    std::thread::spawn(move || {
+        let signer_keys = InMemKeystore::new(2);
+        let (stream, _) = listener.accept().unwrap();
+        let reader = std::io::BufReader::new(stream.try_clone().unwrap());
+        serve(&signer_keys, reader, stream).unwrap();
+    });
+
+    let keystore = Keystore::from(ExternalKeystore::new(ExternalSignerConfig::Socket(
+        socket_path,
+    ))?);
+    check_external_keystore(&keystore, &InMemKeystore::new(2));
+    Ok(())
+}
+
+#[test]
+fn external_keystore_mock_signer_test() -> Result<(), anyhow::Error> {
+    let config = ExternalSignerConfig::Command(vec![
+        env!("CARGO_BIN_EXE_mock-signer").to_string(),
+        "--num-keys".to_string(),
+        "3".to_string(),
+    ]);
+    // The keystore is stored in the client config as the description of its signer
+    let serialized = serde_json::to_string(&Keystore::from(ExternalKeystore::new(config)?))?;
+    let keystore: Keystore = serde_json::from_str(&serialized)?;
+    assert!(keystore.to_string().contains("External"));
+    check_external_keystore(&keystore, &InMemKeystore::new(3));
+    Ok(())
+}
+
+#[test]
+fn external_keystore_connects_lazily() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ExternalSignerConfig::Socket(temp_dir.path().join("missing.sock"));
+    assert!(ExternalKeystore::new(config.clone()).is_err());
+
+    // Loading the keystore doesn't reach the signer, using it fails instead
+    let keystore: Keystore = serde_json::from_value(serde_json::json!({ "External": config }))?;
+    assert!(keystore.keys().is_empty());
+    assert!(keystore
+        .sign_hashed(&SuiAddress::random_for_testing_only(), b"msg")
+        .is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_rejects_signature_of_other_message() -> Result<(), anyhow::Error> {
+    use std::io::{BufRead, Write};
+    use sui_keys::external_signer::{SignerRequest, SignerResponse};
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path)?;
+    std::thread::spawn(move || {
+        let signer_keys = InMemKeystore::new(1);
+        let (mut stream, _) = listener.accept().unwrap();
+        let reader = std::io::BufReader::new(stream.try_clone().unwrap());
+        for line in reader.lines() {
+            let response = match serde_json::from_str(&line.unwrap()).unwrap() {
+                SignerRequest::SignHashed { address, .. } => SignerResponse::Signature(
+                    signer_keys.sign_hashed(&address, b"other msg").unwrap(),
+                ),
+                _ => SignerResponse::Keys(
+                    signer_keys
+                        .keys()
+                        .iter()
+                        .map(EncodeDecodeBase64::encode_base64)
+                        .collect(),
+                ),
+            };
+            let mut line = serde_json::to_string(&response).unwrap();
+            line.push('\n');
+            stream.write_all(line.as_bytes()).unwrap();
+        }
+    });
+
+    let keystore = ExternalKeystore::new(ExternalSignerConfig::Socket(socket_path))?;
+    let address = keystore.addresses()[0];
+    assert!(keystore.sign_hashed(&address, b"msg").is_err());
+    Ok(())
+}
@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -79,6 +80,7 @@ use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
+use sui_storage::archive::{ArchiveReader, ArchiveWriter};
 use sui_storage::IndexStore;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::committee::Committee;
@@ -97,6 +99,9 @@ pub mod admin;
 mod handle;
 pub mod metrics;
 
+/// Number of checkpoint bundles downloaded in parallel when syncing from an archive.
+const ARCHIVE_READ_CONCURRENCY: usize = 5;
+
 pub struct ValidatorComponents {
     validator_server_handle: JoinHandle<Result<()>>,
     narwhal_manager: NarwhalManager,
@@ -131,6 +136,7 @@ pub struct SuiNode {
     trusted_peer_change_tx: watch::Sender<TrustedPeerChangeEvent>,
 
     _db_checkpoint_handle: Option<Sender<()>>,
+    _checkpoint_archive_handle: Option<Sender<()>>,
 
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
@@ -242,13 +248,29 @@ impl SuiNode {
             Some(Arc::new(IndexStore::new(config.db_path().join("indexes"))))
         };
 
+        if let Some(archive_store_config) = &config.checkpoint_archive_read_config {
+            // Checkpoints synced from the archive are executed by the checkpoint executor like
+            // the ones synced from peers, which state sync then only asks for the rest.
+            let reader = ArchiveReader::new(
+                archive_store_config,
+                NonZeroUsize::new(ARCHIVE_READ_CONCURRENCY).unwrap(),
+            )?;
+            match reader.sync_to_store(state_sync_store.clone(), None).await {
+                Ok(checkpoint) => info!(
+                    "Synced checkpoints from archive up to {}",
+                    checkpoint.sequence_number()
+                ),
+                Err(e) => warn!("Failed to sync checkpoints from archive: {e:?}"),
+            }
+        }
+
         // Create network
         // TODO only configure validators as seed/preferred peers for validators and not for
         // fullnodes once we've had a chance to re-work fullnode configuration generation.
         let (trusted_peer_change_tx, trusted_peer_change_rx) = watch::channel(Default::default());
         let (p2p_network, discovery_handle, state_sync_handle) = Self::create_p2p_network(
             &config,
-            state_sync_store,
+            state_sync_store.clone(),
             trusted_peer_change_rx,
             &prometheus_registry,
         )?;
@@ -282,6 +304,19 @@ impl SuiNode {
             None => None,
         };
 
+        let checkpoint_archive_handle = match &config.checkpoint_archive_config {
+            Some(archive_config) => {
+                let writer = ArchiveWriter::new(
+                    state_sync_store.clone(),
+                    &archive_config.object_store_config,
+                    archive_config.checkpoints_per_bundle,
+                    archive_config.upload_interval_s,
+                )?;
+                Some(writer.start())
+            }
+            None => None,
+        };
+
         let state = AuthorityState::new(
             config.protocol_public_key(),
             secret,
@@ -407,6 +442,7 @@ impl SuiNode {
             trusted_peer_change_tx,
 
             _db_checkpoint_handle: db_checkpoint_handle,
+            _checkpoint_archive_handle: checkpoint_archive_handle,
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
         };
@@ -852,6 +888,7 @@ impl SuiNode {
             self.state.database.clone(),
             self.state.transaction_manager().clone(),
             self.accumulator.clone(),
+            self.state.indexes(),
             self.config.checkpoint_executor_config.clone(),
             &self.registry_service.default_registry(),
         );
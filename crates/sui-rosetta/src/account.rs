@@ -8,7 +8,7 @@ use axum_extra::extract::WithRejection;
 use futures::StreamExt;
 
 use sui_sdk::rpc_types::StakeStatus;
-use sui_sdk::{SuiClient, SUI_COIN_TYPE};
+use sui_sdk::SuiClient;
 use sui_types::base_types::SuiAddress;
 
 use crate::errors::Error;
@@ -16,7 +16,7 @@ use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
     Amount, Coin, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
 /// at which the balance lookup was performed.
@@ -45,13 +45,36 @@ pub async fn balance(
             ctx.blocks().current_block_identifier().await?
         };
 
-        ctx.blocks()
-            .get_balance_at_block(address, block_identifier.index)
-            .await
-            .map(|balance| AccountBalanceResponse {
-                block_identifier,
-                balances: vec![Amount::new(balance)],
-            })
+        let currencies = if request.currencies.is_empty() {
+            vec![SUI.clone()]
+        } else {
+            request.currencies
+        };
+        let mut balances = vec![];
+        for currency in currencies {
+            // SUI balances are indexed by the Rosetta server, other coins by the full node.
+            let balance = if currency.is_sui() {
+                ctx.blocks()
+                    .get_balance_at_block(address, block_identifier.index)
+                    .await?
+            } else {
+                let coin_type = currency.coin_type()?;
+                ctx.client
+                    .coin_read_api()
+                    .get_balance_at_checkpoint(
+                        address,
+                        Some(coin_type.to_string()),
+                        block_identifier.index,
+                    )
+                    .await?
+                    .total_balance as i128
+            };
+            balances.push(Amount::new_with_currency(balance, currency));
+        }
+        Ok(AccountBalanceResponse {
+            block_identifier,
+            balances,
+        })
     }
 }
 
@@ -125,16 +148,27 @@ pub async fn coins(
     WithRejection(Json(request), _): WithRejection<Json<AccountCoinsRequest>, Error>,
 ) -> Result<AccountCoinsResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let coins = context
-        .client
-        .coin_read_api()
-        .get_coins_stream(
-            request.account_identifier.address,
-            Some(SUI_COIN_TYPE.to_string()),
-        )
-        .map(Coin::from)
-        .collect()
-        .await;
+    let currencies = if request.currencies.is_empty() {
+        vec![SUI.clone()]
+    } else {
+        request.currencies
+    };
+    let mut coins = vec![];
+    for currency in currencies {
+        let coin_type = currency.coin_type()?;
+        coins.extend(
+            context
+                .client
+                .coin_read_api()
+                .get_coins_stream(
+                    request.account_identifier.address,
+                    Some(coin_type.to_string()),
+                )
+                .map(|coin| Coin::new(coin, currency.clone()))
+                .collect::<Vec<_>>()
+                .await,
+        );
+    }
 
     Ok(AccountCoinsResponse {
         block_identifier: context.blocks().current_block_identifier().await?,
@@ -20,6 +20,7 @@ use sui_types::messages::{Transaction, TransactionData, TransactionDataAPI};
 use sui_types::signature::GenericSignature;
 
 use crate::errors::Error;
+use crate::operations::Operations;
 use crate::types::{
     Amount, ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
     ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
@@ -29,7 +30,7 @@ use crate::types::{
     InternalOperation, MetadataOptions, SignatureType, SigningPayload, TransactionIdentifier,
     TransactionIdentifierResponse,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{CoinMetadataCache, OnlineServerContext, SuiEnv};
 
 /// This module implements the [Rosetta Construction API](https://www.rosetta-api.org/docs/ConstructionApi.html)
 
@@ -221,6 +222,31 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![], 2000)
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let coin_type = currency.coin_type()?;
+            let expected = context.coin_metadata_cache.get_currency(&coin_type).await?;
+            if !currency.same_as(&expected) {
+                return Err(Error::InvalidInput(format!(
+                    "Currency {currency:?} does not match the coin metadata {expected:?}"
+                )));
+            }
+            let amount = amounts.iter().map(|amount| *amount as u128).sum::<u128>();
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(*sender, Some(coin_type.to_string()), amount, None, vec![])
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            // Gas is paid in SUI, only the budget is needed.
+            (Some(0), coins, 2000)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![], 2000),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
@@ -333,9 +359,14 @@ pub async fn metadata(
 ///  This is run as a sanity check before signing (after /construction/payloads)
 /// and before broadcast (after /construction/combine).
 ///
+/// The currencies other than SUI are resolved from the coin metadata when the offline server
+/// is given a full node to look it up, otherwise they are only identified by their coin type,
+/// with an empty symbol and 0 decimals.
+///
 /// [Rosetta API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionparse)
 pub async fn parse(
     Extension(env): Extension<SuiEnv>,
+    Extension(coin_metadata_cache): Extension<Option<CoinMetadataCache>>,
     WithRejection(Json(request), _): WithRejection<Json<ConstructionParseRequest>, Error>,
 ) -> Result<ConstructionParseResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
@@ -353,7 +384,11 @@ pub async fn parse(
     } else {
         vec![]
     };
-    let operations = data.try_into()?;
+    let operations: Operations = data.try_into()?;
+    let operations = match coin_metadata_cache {
+        Some(coin_metadata_cache) => operations.resolve_currencies(&coin_metadata_cache).await?,
+        None => operations,
+    };
     Ok(ConstructionParseResponse {
         operations,
         account_identifier_signers,
@@ -23,14 +23,19 @@ mod account;
 mod block;
 mod construction;
 mod errors;
+mod mempool;
 mod network;
 pub mod operations;
+mod search;
 mod state;
 pub mod types;
 
+pub use crate::state::{CoinMetadataCache, MempoolProvider};
+
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: None,
 });
 
 pub struct RosettaOnlineServer {
@@ -40,13 +45,24 @@ pub struct RosettaOnlineServer {
 
 impl RosettaOnlineServer {
     pub fn new(env: SuiEnv, client: SuiClient, data_path: &Path) -> Self {
-        let blocks = Arc::new(CheckpointBlockProvider::spawn(client.clone(), data_path));
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        let blocks = Arc::new(CheckpointBlockProvider::spawn(
+            client.clone(),
+            coin_metadata_cache.clone(),
+            data_path,
+        ));
         Self {
             env,
-            context: OnlineServerContext::new(client, blocks),
+            context: OnlineServerContext::new(client, blocks, coin_metadata_cache),
         }
     }
 
+    /// Serves the mempool endpoints from the transactions pending in `mempool`.
+    pub fn with_mempool(mut self, mempool: Arc<dyn MempoolProvider + Send + Sync>) -> Self {
+        self.context = self.context.with_mempool(mempool);
+        self
+    }
+
     pub fn serve(self, addr: SocketAddr) -> JoinHandle<hyper::Result<()>> {
         // Online endpoints
         let app = Router::new()
@@ -59,6 +75,9 @@ impl RosettaOnlineServer {
             .route("/network/status", post(network::status))
             .route("/network/list", post(network::list))
             .route("/network/options", post(network::options))
+            .route("/mempool", post(mempool::mempool))
+            .route("/mempool/transaction", post(mempool::transaction))
+            .route("/search/transactions", post(search::transactions))
             .layer(Extension(self.env))
             .with_state(self.context);
         let server = axum::Server::bind(&addr).serve(app.into_make_service());
@@ -72,11 +91,22 @@ impl RosettaOnlineServer {
 
 pub struct RosettaOfflineServer {
     env: SuiEnv,
+    coin_metadata_cache: Option<CoinMetadataCache>,
 }
 
 impl RosettaOfflineServer {
     pub fn new(env: SuiEnv) -> Self {
-        Self { env }
+        Self {
+            env,
+            coin_metadata_cache: None,
+        }
+    }
+
+    /// Resolves the currencies of the parsed operations from `coin_metadata_cache`, instead of
+    /// identifying them by their coin type only.
+    pub fn with_coin_metadata_cache(mut self, coin_metadata_cache: CoinMetadataCache) -> Self {
+        self.coin_metadata_cache = Some(coin_metadata_cache);
+        self
     }
 
     pub fn serve(self, addr: SocketAddr) -> JoinHandle<hyper::Result<()>> {
@@ -90,7 +120,8 @@ impl RosettaOfflineServer {
             .route("/construction/parse", post(construction::parse))
             .route("/network/list", post(network::list))
             .route("/network/options", post(network::options))
-            .layer(Extension(self.env));
+            .layer(Extension(self.env))
+            .layer(Extension(self.coin_metadata_cache));
         let server = axum::Server::bind(&addr).serve(app.into_make_service());
         info!(
             "Sui Rosetta offline server listening on {}",
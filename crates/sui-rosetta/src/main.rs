@@ -19,7 +19,7 @@ use tracing::log::warn;
 use sui_config::{sui_config_dir, Config, NodeConfig, SUI_FULLNODE_CONFIG, SUI_KEYSTORE_FILENAME};
 use sui_node::{metrics, SuiNode};
 use sui_rosetta::types::{CurveType, PrefundedAccount, SuiEnv};
-use sui_rosetta::{RosettaOfflineServer, RosettaOnlineServer, SUI};
+use sui_rosetta::{CoinMetadataCache, RosettaOfflineServer, RosettaOnlineServer, SUI};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{EncodeDecodeBase64, KeypairTraits, SuiKeyPair, ToFromBytes};
@@ -62,6 +62,9 @@ pub enum RosettaServerCommand {
         env: SuiEnv,
         #[clap(long, default_value = "0.0.0.0:9003")]
         addr: SocketAddr,
+        /// Full node used to look up the coin metadata of the parsed transactions.
+        #[clap(long)]
+        full_node_url: Option<String>,
     },
 }
 
@@ -126,9 +129,17 @@ impl RosettaServerCommand {
                 )?;
                 info!("Rosetta DSL file is stored in {:?}", dsl_path);
             }
-            RosettaServerCommand::StartOfflineServer { env, addr } => {
+            RosettaServerCommand::StartOfflineServer {
+                env,
+                addr,
+                full_node_url,
+            } => {
                 info!("Starting Rosetta Offline Server.");
-                let server = RosettaOfflineServer::new(env);
+                let mut server = RosettaOfflineServer::new(env);
+                if let Some(full_node_url) = full_node_url {
+                    let sui_client = wait_for_sui_client(full_node_url).await;
+                    server = server.with_coin_metadata_cache(CoinMetadataCache::new(sui_client));
+                }
                 server.serve(addr).await??;
             }
             RosettaServerCommand::StartOnlineRemoteServer {
@@ -169,13 +180,16 @@ impl RosettaServerCommand {
                 let registry_service = metrics::start_prometheus_server(config.metrics_address);
                 // Staring a full node for the rosetta server.
                 let rpc_address = format!("http://127.0.0.1:{}", config.json_rpc_address.port());
-                let _node = SuiNode::start(&config, registry_service).await?;
+                let node = SuiNode::start(&config, registry_service).await?;
 
                 let sui_client = wait_for_sui_client(rpc_address).await;
 
                 let rosetta_path = data_path.join("rosetta_db");
                 info!("Rosetta db path : {rosetta_path:?}");
-                let rosetta = RosettaOnlineServer::new(env, sui_client, &rosetta_path);
+                let mut rosetta = RosettaOnlineServer::new(env, sui_client, &rosetta_path);
+                if let Some(orchestrator) = node.transaction_orchestrator() {
+                    rosetta = rosetta.with_mempool(orchestrator);
+                }
                 rosetta.serve(addr).await??;
             }
         };
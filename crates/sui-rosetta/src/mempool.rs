@@ -0,0 +1,66 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::operations::Operations;
+use crate::types::{
+    MempoolRequest, MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse,
+    Transaction, TransactionIdentifier,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+/// This module implements the [Rosetta Mempool API](https://www.rosetta-api.org/docs/MempoolApi.html)
+
+/// Get all Transaction Identifiers in the mempool, i.e. the transactions submitted to the full
+/// node which have not been finalized yet.
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+pub async fn mempool(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<MempoolRequest>, Error>,
+) -> Result<MempoolResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let transaction_identifiers = context
+        .mempool()?
+        .pending_transactions()
+        .iter()
+        .map(|tx| TransactionIdentifier { hash: *tx.digest() })
+        .collect();
+    Ok(MempoolResponse {
+        transaction_identifiers,
+    })
+}
+
+/// Get a transaction in the mempool by its Transaction Identifier. The operations of pending
+/// transactions have no status, as they have not been executed yet.
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempooltransaction)
+pub async fn transaction(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<MempoolTransactionRequest>, Error>,
+) -> Result<MempoolTransactionResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let digest = request.transaction_identifier.hash;
+    let tx = context
+        .mempool()?
+        .pending_transactions()
+        .into_iter()
+        .find(|tx| tx.digest() == &digest)
+        .ok_or(Error::TransactionNotInMempool(digest))?;
+
+    let operations = Operations::try_from(tx.data().transaction_data().clone())?
+        .resolve_currencies(&context.coin_metadata_cache)
+        .await?;
+    Ok(MempoolTransactionResponse {
+        transaction: Transaction {
+            transaction_identifier: TransactionIdentifier { hash: digest },
+            operations,
+            related_transactions: vec![],
+            metadata: None,
+        },
+        metadata: None,
+    })
+}
@@ -8,10 +8,11 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransaction;
@@ -22,18 +23,22 @@ use sui_sdk::rpc_types::{
     SuiTransactionResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
-use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::coin::COIN_MODULE_NAME;
+use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::TransactionData;
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
-use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
+use sui_types::{
+    parse_sui_type_tag, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID,
+};
 
+use crate::state::CoinMetadataCache;
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType, COIN_SPLIT_FUN_NAME,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -83,6 +88,10 @@ impl Operations {
         true
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Operation> {
+        self.0.iter()
+    }
+
     pub fn set_status(mut self, status: Option<OperationStatus>) -> Self {
         for op in &mut self.0 {
             op.status = status
@@ -101,6 +110,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -135,6 +145,58 @@ impl Operations {
         })
     }
 
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut recipients = vec![];
+        let mut amounts = vec![];
+        let mut sender = None;
+        let mut currency: Option<Currency> = None;
+        for op in self {
+            if let (Some(amount), Some(account)) = (op.amount, op.account) {
+                match &currency {
+                    Some(currency) if !currency.same_as(&amount.currency) => {
+                        return Err(Error::MalformedOperationError(
+                            "PayCoin operations should all be in the same currency.".into(),
+                        ))
+                    }
+                    Some(_) => {}
+                    None => {
+                        let coin_type = amount.currency.coin_type()?;
+                        currency = Some(Currency::new(
+                            amount.currency.symbol,
+                            amount.currency.decimals,
+                            &coin_type,
+                        ));
+                    }
+                }
+                if amount.value.is_negative() {
+                    sender = Some(account.address)
+                } else {
+                    recipients.push(account.address);
+                    let amount = amount.value.abs();
+                    if amount > u64::MAX as i128 {
+                        return Err(Error::InvalidInput(
+                            "Input amount exceed u64::MAX".to_string(),
+                        ));
+                    }
+                    amounts.push(amount as u64)
+                }
+            }
+        }
+        let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Amount".to_string()))?;
+        if currency.is_sui() {
+            return Err(Error::InvalidInput(
+                "SUI should be paid with PaySui operations".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
             .0
@@ -230,6 +292,8 @@ impl Operations {
         #[derive(Debug)]
         enum KnownValue {
             GasCoin(u64),
+            // A coin split off with `coin::split`, see `InternalOperation::try_into_data`.
+            Coin(TypeTag, u64),
         }
         fn resolve_result(
             known_results: &[Vec<KnownValue>],
@@ -248,10 +312,14 @@ impl Operations {
         ) -> Option<Vec<KnownValue>> {
             match coin {
                 SuiArgument::Result(i) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)?;
+                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)? else {
+                        return None;
+                    };
                 }
                 SuiArgument::NestedResult(i, j) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
+                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)? else {
+                        return None;
+                    };
                 }
                 SuiArgument::GasCoin => (),
                 // Might not be a SUI coin
@@ -274,8 +342,30 @@ impl Operations {
                 .collect::<Option<_>>()?;
             Some(amounts)
         }
+        fn coin_split(
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<Vec<KnownValue>> {
+            let [coin_type] = &call.type_arguments[..] else {
+                return None;
+            };
+            let [SuiArgument::Input(_), SuiArgument::Input(amount)] = call.arguments[..] else {
+                return None;
+            };
+            let amount = inputs[amount as usize].pure()?.to_json_value();
+            // Pure inputs are only decoded when their type can be inferred from the modules.
+            let amount = match amount.as_str() {
+                Some(amount) => u64::from_str(amount).ok()?,
+                None => bcs::from_bytes(&json_bytes(&amount)?).ok()?,
+            };
+            Some(vec![KnownValue::Coin(
+                parse_sui_type_tag(coin_type).ok()?,
+                amount,
+            )])
+        }
         fn transfer_object(
             aggregated_recipients: &mut HashMap<SuiAddress, u64>,
+            aggregated_coin_recipients: &mut HashMap<TypeTag, HashMap<SuiAddress, u64>>,
             inputs: &[SuiCallArg],
             known_results: &[Vec<KnownValue>],
             objs: &[SuiArgument],
@@ -289,18 +379,24 @@ impl Operations {
             };
             for obj in objs {
                 let value = match *obj {
-                    SuiArgument::Result(i) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, 0)?;
-                        value
-                    }
-                    SuiArgument::NestedResult(i, j) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, j)?;
-                        value
-                    }
+                    SuiArgument::Result(i) => resolve_result(known_results, i, 0)?,
+                    SuiArgument::NestedResult(i, j) => resolve_result(known_results, i, j)?,
                     SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
                 };
-                let aggregate = aggregated_recipients.entry(addr).or_default();
-                *aggregate += value;
+                let aggregate = match value {
+                    KnownValue::GasCoin(value) => {
+                        (aggregated_recipients.entry(addr).or_default(), value)
+                    }
+                    KnownValue::Coin(coin_type, value) => (
+                        aggregated_coin_recipients
+                            .entry(coin_type.clone())
+                            .or_default()
+                            .entry(addr)
+                            .or_default(),
+                        value,
+                    ),
+                };
+                *aggregate.0 += aggregate.1;
             }
             Some(vec![])
         }
@@ -314,7 +410,9 @@ impl Operations {
                 [_, coin, validator] => {
                     let amount = match coin {
                         SuiArgument::Result(i) =>{
-                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))?;
+                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))? else {
+                                return Ok(None);
+                            };
                             value
                         },
                         _ => return Ok(None),
@@ -359,6 +457,8 @@ impl Operations {
         let SuiProgrammableTransaction { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut aggregated_coin_recipients: HashMap<TypeTag, HashMap<SuiAddress, u64>> =
+            HashMap::new();
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
@@ -369,6 +469,7 @@ impl Operations {
                 }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
+                    &mut aggregated_coin_recipients,
                     inputs,
                     &known_results,
                     objs,
@@ -389,6 +490,12 @@ impl Operations {
                         vec![]
                     })
                 }
+                SuiCommand::MergeCoins(SuiArgument::Input(_), coins)
+                    if coins.iter().all(|c| matches!(c, SuiArgument::Input(_))) =>
+                {
+                    Some(vec![])
+                }
+                SuiCommand::MoveCall(m) if Self::is_coin_split_call(m) => coin_split(inputs, m),
                 SuiCommand::MoveCall(m) if Self::is_unstake_call(m) => {
                     let stake_id = unstake_call(inputs, m)?;
                     stake_ids.push(stake_id);
@@ -404,7 +511,41 @@ impl Operations {
             }
         }
 
-        if !needs_generic && !aggregated_recipients.is_empty() {
+        let pay_coin = if needs_generic || aggregated_coin_recipients.is_empty() {
+            None
+        } else if !aggregated_recipients.is_empty() {
+            // SUI and other coins paid together.
+            needs_generic = true;
+            None
+        } else {
+            // Only a single currency can be paid, see `InternalOperation::try_into_data`.
+            let mut payments = aggregated_coin_recipients.into_iter();
+            match (payments.next(), payments.next()) {
+                (Some((coin_type, recipients)), None) => {
+                    Some((Currency::of_coin_type(&coin_type), recipients))
+                }
+                _ => {
+                    needs_generic = true;
+                    None
+                }
+            }
+        };
+
+        if let Some((currency, recipients)) = pay_coin {
+            let total_paid: u64 = recipients.values().copied().sum();
+            operations.extend(recipients.into_iter().map(|(recipient, amount)| {
+                Operation::pay_coin(
+                    status,
+                    recipient,
+                    Amount::new_with_currency(amount.into(), currency.clone()),
+                )
+            }));
+            operations.push(Operation::pay_coin(
+                status,
+                sender,
+                Amount::new_with_currency(-(total_paid as i128), currency),
+            ));
+        } else if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
             operations.extend(
                 aggregated_recipients
@@ -445,6 +586,12 @@ impl Operations {
             && tx.function == ADD_STAKE_FUN_NAME.as_str()
     }
 
+    fn is_coin_split_call(tx: &SuiProgrammableMoveCall) -> bool {
+        tx.package == SUI_FRAMEWORK_OBJECT_ID
+            && tx.module == COIN_MODULE_NAME.as_str()
+            && tx.function == COIN_SPLIT_FUN_NAME.as_str()
+    }
+
     fn is_unstake_call(tx: &SuiProgrammableMoveCall) -> bool {
         tx.package == SUI_SYSTEM_PACKAGE_ID
             && tx.module == SUI_SYSTEM_MODULE_NAME.as_str()
@@ -455,27 +602,30 @@ impl Operations {
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
+        currencies: &HashMap<TypeTag, Currency>,
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
+        balances: HashMap<(SuiAddress, Currency), i128>,
     ) -> impl Iterator<Item = Operation> {
         let mut balances = balance_changes
             .iter()
             .fold(balances, |mut balances, balance_change| {
                 // Rosetta only care about address owner
                 if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
+                    if let Some(currency) = currencies.get(&balance_change.coin_type) {
+                        *balances.entry((owner, currency.clone())).or_default() +=
+                            balance_change.amount;
                     }
                 }
                 balances
             });
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, Amount::new_with_currency(amount, currency))
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -499,9 +649,30 @@ impl TryFrom<SuiTransactionData> for Operations {
     }
 }
 
-impl TryFrom<SuiTransactionResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionResponse) -> Result<Self, Self::Error> {
+impl Operations {
+    /// Looks up the symbol and decimals of the currencies other than SUI in the coin metadata, as
+    /// operations parsed from a transaction only know the coin types of the coins paid.
+    pub async fn resolve_currencies(
+        mut self,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
+        for op in &mut self.0 {
+            if let Some(amount) = &mut op.amount {
+                if amount.currency.metadata.is_some() {
+                    let coin_type = amount.currency.coin_type()?;
+                    amount.currency = coin_metadata_cache.get_currency(&coin_type).await?;
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Extracts the operations of an executed transaction, including the balance changes of all
+    /// currencies, which are looked up in `coin_metadata_cache`.
+    pub async fn try_from_response(
+        response: SuiTransactionResponse,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -517,7 +688,11 @@ impl TryFrom<SuiTransactionResponse> for Operations {
 
         let status = Some(effect.into_status().into());
         let ops: Operations = tx.data.try_into()?;
-        let ops = ops.set_status(status).into_iter();
+        let ops = ops
+            .resolve_currencies(coin_metadata_cache)
+            .await?
+            .set_status(status)
+            .into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
         // change amount extracted from event to prevent double counting.
@@ -528,7 +703,9 @@ impl TryFrom<SuiTransactionResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -560,8 +737,8 @@ impl TryFrom<SuiTransactionResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -571,12 +748,23 @@ impl TryFrom<SuiTransactionResponse> for Operations {
         };
 
         // Extract coin change operations from balance changes
+        let balance_changes = response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+        let mut currencies = HashMap::new();
+        for balance_change in &balance_changes {
+            if !currencies.contains_key(&balance_change.coin_type) {
+                let currency = coin_metadata_cache
+                    .get_currency(&balance_change.coin_type)
+                    .await?;
+                currencies.insert(balance_change.coin_type.clone(), currency);
+            }
+        }
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            &balance_changes,
+            &currencies,
             status,
             accounted_balances,
         );
@@ -589,6 +777,15 @@ impl TryFrom<SuiTransactionResponse> for Operations {
     }
 }
 
+/// The BCS bytes of a pure input whose type is unknown.
+fn json_bytes(value: &Value) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect()
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
@@ -694,13 +891,25 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(status: Option<OperationStatus>, address: SuiAddress, amount: Amount) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(amount),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: Amount) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(amount),
             coin_change: None,
             metadata: None,
         }
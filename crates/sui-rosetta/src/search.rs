@@ -0,0 +1,369 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use tracing::debug;
+
+use sui_json_rpc_types::{
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionsPage,
+};
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::query::TransactionFilter;
+
+use crate::operations::Operations;
+use crate::types::{
+    BlockIdentifier, BlockTransaction, OperationStatus, OperationType, Operator,
+    SearchTransactionsRequest, SearchTransactionsResponse, SubAccount, Transaction,
+    TransactionIdentifier,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+#[cfg(test)]
+#[path = "unit_tests/search_tests.rs"]
+mod search_tests;
+
+/// This module implements the [Rosetta Search API](https://www.rosetta-api.org/docs/SearchApi.html)
+
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 1000;
+const QUERY_PAGE_SIZE: usize = 50;
+/// The number of transactions fetched from the full node at most per search request.
+const MAX_SCANNED_TRANSACTIONS: u64 = 10_000;
+/// The number of search offsets remembered to resume searches from.
+const MAX_SEARCH_CURSORS: usize = 10_000;
+
+/// Search for transactions matching a set of conditions, combined with the request's operator.
+///
+/// Searches must be narrowed down by one of the full node's indexes, i.e. by a transaction
+/// identifier or addresses. Results are returned in execution order. `next_offset` is a resume
+/// point issued by this server, the search carries on from where the previous page stopped
+/// rather than walking the history again. Matching transactions are only counted until the page
+/// is filled, hence `total_count` is exact only when there is no `next_offset`.
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+pub async fn transactions(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<SearchTransactionsRequest>, Error>,
+) -> Result<SearchTransactionsResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let offset = request.offset.unwrap_or_default();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    if limit == 0 {
+        return Err(Error::InvalidInput("limit must be positive".to_string()));
+    }
+    let search = TransactionSearch::try_from(request)?;
+    search.ensure_indexed()?;
+    debug!("Called /search/transactions endpoint: {:?}", search);
+
+    let search_key = format!("{search:?}");
+    let SearchCursor {
+        mut last_scanned,
+        mut matched,
+    } = if offset == 0 {
+        SearchCursor::default()
+    } else {
+        context
+            .search_cursors
+            .get(&search_key, offset)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Unknown offset {offset}, offsets must be a next_offset returned by this server"
+                ))
+            })?
+    };
+
+    let options = SuiTransactionResponseOptions::new()
+        .with_input()
+        .with_effects()
+        .with_balance_changes()
+        .with_events();
+    let query = SuiTransactionResponseQuery::new(search.filter(), Some(options.clone()));
+
+    let mut scanned = offset;
+    let mut found = vec![];
+    let mut has_more = false;
+    'pages: loop {
+        let read_api = context.client.read_api();
+        let page = match search.required_transaction() {
+            Some(digest) => TransactionsPage {
+                data: vec![
+                    read_api
+                        .get_transaction_with_options(digest, options.clone())
+                        .await?,
+                ],
+                next_cursor: None,
+                has_next_page: false,
+            },
+            None => {
+                read_api
+                    .query_transactions(query.clone(), last_scanned, Some(QUERY_PAGE_SIZE), false)
+                    .await?
+            }
+        };
+        for response in page.data {
+            // The scan is bounded per request, the client resumes from `next_offset`.
+            if (found.len() as u64) == limit || scanned - offset == MAX_SCANNED_TRANSACTIONS {
+                has_more = true;
+                break 'pages;
+            }
+            // Transactions which are not in a checkpoint yet are not part of any block, stop
+            // before them so that a later page picks them up.
+            let checkpoint = match response.checkpoint {
+                Some(checkpoint) => checkpoint,
+                None => {
+                    has_more = true;
+                    break 'pages;
+                }
+            };
+            last_scanned = Some(response.digest);
+            scanned += 1;
+            let transaction = Transaction {
+                transaction_identifier: TransactionIdentifier {
+                    hash: response.digest,
+                },
+                operations: Operations::try_from_response(response, &context.coin_metadata_cache)
+                    .await?,
+                related_transactions: vec![],
+                metadata: None,
+            };
+            if search.matches(&transaction) {
+                matched += 1;
+                found.push((checkpoint, transaction));
+            }
+        }
+        if !page.has_next_page {
+            break;
+        }
+    }
+
+    if has_more {
+        context.search_cursors.insert(
+            search_key,
+            scanned,
+            SearchCursor {
+                last_scanned,
+                matched,
+            },
+        );
+    }
+
+    let mut block_identifiers = BTreeMap::new();
+    let mut transactions = vec![];
+    for (index, transaction) in found {
+        if !block_identifiers.contains_key(&index) {
+            let checkpoint = context
+                .client
+                .read_api()
+                .get_checkpoint(index.into())
+                .await?;
+            let block_identifier = BlockIdentifier {
+                index,
+                hash: checkpoint.digest,
+            };
+            block_identifiers.insert(index, block_identifier);
+        }
+        transactions.push(BlockTransaction {
+            block_identifier: block_identifiers[&index],
+            transaction,
+        });
+    }
+
+    Ok(SearchTransactionsResponse {
+        transactions,
+        total_count: matched + has_more as u64,
+        next_offset: has_more.then_some(scanned),
+    })
+}
+
+/// Where a search stopped: the last transaction fetched from the full node, and the number of
+/// matching transactions up to it.
+#[derive(Debug, Clone, Copy, Default)]
+struct SearchCursor {
+    last_scanned: Option<TransactionDigest>,
+    matched: u64,
+}
+
+/// The resume points of recent searches, keyed by search and by the offset handed out to the
+/// client. Only the latest [MAX_SEARCH_CURSORS] are kept.
+#[derive(Clone, Default)]
+pub struct SearchCursors(Arc<Mutex<SearchCursorsInner>>);
+
+#[derive(Default)]
+struct SearchCursorsInner {
+    cursors: HashMap<(String, u64), SearchCursor>,
+    insertion_order: VecDeque<(String, u64)>,
+}
+
+impl SearchCursors {
+    fn get(&self, search: &str, offset: u64) -> Option<SearchCursor> {
+        let inner = self.0.lock().unwrap();
+        inner.cursors.get(&(search.to_string(), offset)).copied()
+    }
+
+    fn insert(&self, search: String, offset: u64, cursor: SearchCursor) {
+        let mut inner = self.0.lock().unwrap();
+        let key = (search, offset);
+        if inner.cursors.insert(key.clone(), cursor).is_none() {
+            inner.insertion_order.push_back(key);
+        }
+        while inner.insertion_order.len() > MAX_SEARCH_CURSORS {
+            if let Some(key) = inner.insertion_order.pop_front() {
+                inner.cursors.remove(&key);
+            }
+        }
+    }
+}
+
+/// A condition on the operations of a transaction, which it matches if any of them does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Condition {
+    Transaction(TransactionDigest),
+    Address(SuiAddress),
+    Type(OperationType),
+    Status(OperationStatus),
+}
+
+impl Condition {
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let mut operations = transaction.operations.iter();
+        match self {
+            Condition::Transaction(digest) => &transaction.transaction_identifier.hash == digest,
+            Condition::Address(address) => operations.any(|op| {
+                op.account
+                    .as_ref()
+                    .map_or(false, |account| &account.address == address)
+            }),
+            Condition::Type(type_) => operations.any(|op| &op.type_ == type_),
+            Condition::Status(status) => operations.any(|op| op.status.as_ref() == Some(status)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TransactionSearch {
+    operator: Operator,
+    conditions: Vec<Condition>,
+    max_block: Option<u64>,
+}
+
+impl TryFrom<SearchTransactionsRequest> for TransactionSearch {
+    type Error = Error;
+
+    fn try_from(request: SearchTransactionsRequest) -> Result<Self, Error> {
+        let mut conditions = vec![];
+        if let Some(transaction) = request.transaction_identifier {
+            conditions.push(Condition::Transaction(transaction.hash));
+        }
+        if let Some(account) = request.account_identifier {
+            if let Some(SubAccount { account_type }) = account.sub_account {
+                return Err(Error::InvalidInput(format!(
+                    "Searching by sub account is not supported: {account_type:?}"
+                )));
+            }
+            conditions.push(Condition::Address(account.address));
+        }
+        if let Some(address) = request.address {
+            conditions.push(Condition::Address(address));
+        }
+        if let Some(type_) = request.type_ {
+            conditions.push(Condition::Type(type_));
+        }
+        if let Some(status) = request.status {
+            conditions.push(Condition::Status(status));
+        }
+        if let Some(success) = request.success {
+            conditions.push(Condition::Status(if success {
+                OperationStatus::Success
+            } else {
+                OperationStatus::Failure
+            }));
+        }
+        Ok(Self {
+            operator: request.operator,
+            conditions,
+            max_block: request.max_block,
+        })
+    }
+}
+
+impl TransactionSearch {
+    /// The filter narrowing down the transactions to fetch from the full node. Address
+    /// conditions are looked up in the full node's indexes when they must all hold, or when they
+    /// are the only conditions, the other conditions are checked against the operations of the
+    /// fetched transactions.
+    fn filter(&self) -> Option<TransactionFilter> {
+        let addresses = self
+            .conditions
+            .iter()
+            .filter_map(|condition| match condition {
+                Condition::Address(address) => Some(
+                    TransactionFilter::FromAddress(*address)
+                        .or(TransactionFilter::ToAddress(*address)),
+                ),
+                _ => None,
+            });
+        let filter = match self.operator {
+            Operator::And => addresses.reduce(TransactionFilter::and)?,
+            Operator::Or
+                if self
+                    .conditions
+                    .iter()
+                    .all(|condition| matches!(condition, Condition::Address(_))) =>
+            {
+                addresses.reduce(TransactionFilter::or)?
+            }
+            Operator::Or => return None,
+        };
+        Some(match self.max_block {
+            Some(max_block) => TransactionFilter::Checkpoint {
+                start: 0,
+                end: max_block.saturating_add(1),
+            }
+            .and(filter),
+            None => filter,
+        })
+    }
+
+    /// Searches which can't be served from the full node's indexes would have to scan the whole
+    /// history, they are rejected.
+    fn ensure_indexed(&self) -> Result<(), Error> {
+        if self.required_transaction().is_none() && self.filter().is_none() {
+            return Err(Error::InvalidInput(
+                "Searches must include a transaction identifier or an address which every \
+                 condition applies to, or only addresses"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The only transaction which can match, when its identifier is one of the conditions which
+    /// must all hold.
+    fn required_transaction(&self) -> Option<TransactionDigest> {
+        if self.operator != Operator::And {
+            return None;
+        }
+        self.conditions
+            .iter()
+            .find_map(|condition| match condition {
+                Condition::Transaction(digest) => Some(*digest),
+                _ => None,
+            })
+    }
+
+    fn matches(&self, transaction: &Transaction) -> bool {
+        if self.conditions.is_empty() {
+            return true;
+        }
+        let mut results = self.conditions.iter().map(|c| c.matches(transaction));
+        match self.operator {
+            Operator::And => results.all(|matches| matches),
+            Operator::Or => results.any(|matches| matches),
+        }
+    }
+}
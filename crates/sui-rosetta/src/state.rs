@@ -2,24 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::operations::Operations;
+use crate::search::SearchCursors;
 use crate::types::{
-    Block, BlockHash, BlockIdentifier, BlockResponse, OperationStatus, OperationType, Transaction,
-    TransactionIdentifier,
+    Block, BlockHash, BlockIdentifier, BlockResponse, Currency, OperationStatus, OperationType,
+    Transaction, TransactionIdentifier,
 };
-use crate::Error;
+use crate::{Error, SUI};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use rocksdb::Options;
 use std::collections::HashMap;
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, UNIX_EPOCH};
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_types::SuiTransactionResponseOptions;
 use sui_sdk::rpc_types::Checkpoint;
 use sui_sdk::SuiClient;
 use sui_types::base_types::{EpochId, SuiAddress};
+use sui_types::gas_coin::GAS;
+use sui_types::messages::VerifiedTransaction;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use tracing::{debug, error, info, warn};
 use typed_store::rocks::{point_lookup_db_options, DBMap, DBOptions, MetricConf};
@@ -35,20 +41,44 @@ mod balance_changing_tx_tests;
 #[derive(Clone)]
 pub struct OnlineServerContext {
     pub client: SuiClient,
+    pub coin_metadata_cache: CoinMetadataCache,
+    pub search_cursors: SearchCursors,
     block_provider: Arc<dyn BlockProvider + Send + Sync>,
+    mempool_provider: Option<Arc<dyn MempoolProvider + Send + Sync>>,
 }
 
 impl OnlineServerContext {
-    pub fn new(client: SuiClient, block_provider: Arc<dyn BlockProvider + Send + Sync>) -> Self {
+    pub fn new(
+        client: SuiClient,
+        block_provider: Arc<dyn BlockProvider + Send + Sync>,
+        coin_metadata_cache: CoinMetadataCache,
+    ) -> Self {
         Self {
             client,
+            coin_metadata_cache,
+            search_cursors: SearchCursors::default(),
             block_provider,
+            mempool_provider: None,
         }
     }
 
+    pub fn with_mempool(
+        mut self,
+        mempool_provider: Arc<dyn MempoolProvider + Send + Sync>,
+    ) -> Self {
+        self.mempool_provider = Some(mempool_provider);
+        self
+    }
+
     pub fn blocks(&self) -> &(dyn BlockProvider + Sync + Send) {
         &*self.block_provider
     }
+
+    pub fn mempool(&self) -> Result<&(dyn MempoolProvider + Sync + Send), Error> {
+        self.mempool_provider
+            .as_deref()
+            .ok_or(Error::MempoolUnavailable)
+    }
 }
 
 #[async_trait]
@@ -66,10 +96,61 @@ pub trait BlockProvider {
     ) -> Result<i128, Error>;
 }
 
+/// Transactions submitted to the full node which have not been finalized yet.
+pub trait MempoolProvider {
+    fn pending_transactions(&self) -> Vec<VerifiedTransaction>;
+}
+
+/// The transaction orchestrator keeps the transactions it is driving to finality in the full
+/// node's pending transaction log until they are executed locally.
+impl MempoolProvider for TransactiondOrchestrator<NetworkAuthorityClient> {
+    fn pending_transactions(&self) -> Vec<VerifiedTransaction> {
+        self.load_all_pending_transactions()
+    }
+}
+
+/// The Rosetta currencies of the coin types, looked up in the coin metadata on first use.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<HashMap<TypeTag, Currency>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Default::default(),
+        }
+    }
+
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Currency, Error> {
+        if GAS::is_gas_type(coin_type) {
+            return Ok(SUI.clone());
+        }
+        let cached = self.currencies.lock().unwrap().get(coin_type).cloned();
+        if let Some(currency) = cached {
+            return Ok(currency);
+        }
+        let metadata = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type.to_string())
+            .await?;
+        let currency = Currency::new(metadata.symbol, metadata.decimals.into(), coin_type);
+        self.currencies
+            .lock()
+            .unwrap()
+            .insert(coin_type.clone(), currency.clone());
+        Ok(currency)
+    }
+}
+
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     index_store: Arc<CheckpointIndexStore>,
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -127,10 +208,15 @@ impl BlockProvider for CheckpointBlockProvider {
 }
 
 impl CheckpointBlockProvider {
-    pub fn spawn(client: SuiClient, db_path: &Path) -> Self {
+    pub fn spawn(
+        client: SuiClient,
+        coin_metadata_cache: CoinMetadataCache,
+        db_path: &Path,
+    ) -> Self {
         let blocks = Self {
             index_store: Arc::new(CheckpointIndexStore::open(db_path, None)),
             client,
+            coin_metadata_cache,
         };
 
         let update_interval = option_env!("CHECKPOINT_UPDATE_INTERVAL")
@@ -246,7 +332,7 @@ impl CheckpointBlockProvider {
                 .await?;
             transactions.push(Transaction {
                 transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                operations: Operations::try_from(tx)?,
+                operations: Operations::try_from_response(tx, &self.coin_metadata_cache).await?,
                 related_transactions: vec![],
                 metadata: None,
             })
@@ -314,8 +400,11 @@ fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128
                     | OperationType::StakeReward
                     | OperationType::StakePrinciple
                     | OperationType::Stake => {
+                        // Only SUI balances are indexed.
                         if let (Some(addr), Some(amount)) = (op.account, op.amount) {
-                            *changes.entry(addr.address).or_default() += amount.value
+                            if amount.currency == *SUI {
+                                *changes.entry(addr.address).or_default() += amount.value
+                            }
                         }
                     }
                     _ => {}
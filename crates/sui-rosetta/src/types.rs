@@ -8,6 +8,9 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use fastcrypto::encoding::Hex;
 use fastcrypto::traits::ToFromBytes;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::TypeTag;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
@@ -17,15 +20,18 @@ use strum_macros::EnumString;
 
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::coin::COIN_MODULE_NAME;
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{Argument, CallArg, Command, ObjectArg, TransactionData};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::{
-    SUI_SYSTEM_PACKAGE_ID, SUI_SYSTEM_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+    parse_sui_type_tag, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_PACKAGE_ID, SUI_SYSTEM_STATE_OBJECT_ID,
+    SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
 };
 
 use crate::errors::{Error, ErrorType};
@@ -34,6 +40,8 @@ use crate::SUI;
 
 pub type BlockHeight = u64;
 
+pub(crate) const COIN_SPLIT_FUN_NAME: &IdentStr = ident_str!("split");
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NetworkIdentifier {
     pub blockchain: String,
@@ -98,10 +106,63 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
+}
+
+/// Identifies the `Coin<T>` type of the currencies other than SUI.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
+impl Currency {
+    pub fn new(symbol: String, decimals: u64, coin_type: &TypeTag) -> Self {
+        if GAS::is_gas_type(coin_type) {
+            return SUI.clone();
+        }
+        Self {
+            symbol,
+            decimals,
+            metadata: Some(CurrencyMetadata {
+                coin_type: coin_type.to_string(),
+            }),
+        }
+    }
+
+    /// A currency known by its coin type only, whose symbol and decimals are yet to be looked up
+    /// in the coin metadata, see `Operations::resolve_currencies`.
+    pub fn of_coin_type(coin_type: &TypeTag) -> Self {
+        Self::new(String::new(), 0, coin_type)
+    }
+
+    /// The type `T` of the `Coin<T>` objects holding this currency.
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        match &self.metadata {
+            Some(CurrencyMetadata { coin_type }) => parse_sui_type_tag(coin_type)
+                .map_err(|e| Error::InvalidInput(format!("Invalid coin type {coin_type}: {e}"))),
+            None if self == &*SUI => Ok(GAS::type_tag()),
+            None => Err(Error::InvalidInput(format!(
+                "Missing coin type of currency {}",
+                self.symbol
+            ))),
+        }
+    }
+
+    pub fn is_sui(&self) -> bool {
+        matches!(self.coin_type(), Ok(coin_type) if GAS::is_gas_type(&coin_type))
+    }
+
+    /// Whether both currencies are the same coin, regardless of how their coin types are written.
+    pub fn same_as(&self, other: &Currency) -> bool {
+        self.symbol == other.symbol
+            && self.decimals == other.decimals
+            && matches!((self.coin_type(), other.coin_type()), (Ok(a), Ok(b)) if a == b)
+    }
 }
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
@@ -156,9 +217,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -200,6 +264,8 @@ pub struct AccountCoinsRequest {
     pub network_identifier: NetworkIdentifier,
     pub account_identifier: AccountIdentifier,
     pub include_mempool: bool,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
 }
 #[derive(Serialize)]
 pub struct AccountCoinsResponse {
@@ -217,8 +283,8 @@ pub struct Coin {
     pub amount: Amount,
 }
 
-impl From<sui_sdk::rpc_types::Coin> for Coin {
-    fn from(coin: sui_sdk::rpc_types::Coin) -> Self {
+impl Coin {
+    pub fn new(coin: sui_sdk::rpc_types::Coin, currency: Currency) -> Self {
         Self {
             coin_identifier: CoinIdentifier {
                 identifier: CoinID {
@@ -226,11 +292,7 @@ impl From<sui_sdk::rpc_types::Coin> for Coin {
                     version: coin.version,
                 },
             },
-            amount: Amount {
-                value: coin.balance as i128,
-                currency: SUI.clone(),
-                metadata: None,
-            },
+            amount: Amount::new_with_currency(coin.balance as i128, currency),
         }
     }
 }
@@ -402,6 +464,7 @@ pub enum OperationType {
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -829,6 +892,86 @@ impl IntoResponse for BlockTransactionResponse {
     }
 }
 
+#[derive(Deserialize)]
+pub struct MempoolRequest {
+    pub network_identifier: NetworkIdentifier,
+}
+
+#[derive(Serialize)]
+pub struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+impl IntoResponse for MempoolResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MempoolTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+#[derive(Serialize)]
+pub struct MempoolTransactionResponse {
+    pub transaction: Transaction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl IntoResponse for MempoolTransactionResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Operator {
+    Or,
+    #[default]
+    And,
+}
+
+#[derive(Deserialize)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+    #[serde(default)]
+    pub operator: Operator,
+    pub max_block: Option<u64>,
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
+    pub transaction_identifier: Option<TransactionIdentifier>,
+    pub account_identifier: Option<AccountIdentifier>,
+    pub status: Option<OperationStatus>,
+    #[serde(rename = "type")]
+    pub type_: Option<OperationType>,
+    pub address: Option<SuiAddress>,
+    pub success: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+    pub total_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
+impl IntoResponse for SearchTransactionsResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Serialize)]
+pub struct BlockTransaction {
+    pub block_identifier: BlockIdentifier,
+    pub transaction: Transaction,
+}
+
 #[derive(Serialize, Clone)]
 pub struct PrefundedAccount {
     pub privkey: String,
@@ -844,6 +987,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -860,6 +1009,7 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
@@ -876,6 +1026,49 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            Self::PayCoin {
+                recipients,
+                amounts,
+                currency,
+                ..
+            } => {
+                if recipients.len() != amounts.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "Got {} recipients but {} amounts",
+                        recipients.len(),
+                        amounts.len()
+                    )));
+                }
+                let coin_type = currency.coin_type()?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let mut coins = metadata
+                    .objects
+                    .into_iter()
+                    .map(|coin| builder.obj(ObjectArg::ImmOrOwnedObject(coin)))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter();
+                let coin = coins
+                    .next()
+                    .ok_or_else(|| Error::InvalidInput(format!("No {} coin", currency.symbol)))?;
+                let coins: Vec<_> = coins.collect();
+                if !coins.is_empty() {
+                    builder.command(Command::MergeCoins(coin, coins));
+                }
+                // Coins are split with `coin::split` rather than the `SplitCoins` command, so that
+                // their type can be read back from the transaction.
+                for (recipient, amount) in recipients.into_iter().zip(amounts) {
+                    let amount = builder.pure(amount)?;
+                    let split = builder.programmable_move_call(
+                        SUI_FRAMEWORK_OBJECT_ID,
+                        COIN_MODULE_NAME.to_owned(),
+                        COIN_SPLIT_FUN_NAME.to_owned(),
+                        vec![coin_type.clone()],
+                        vec![coin, amount],
+                    );
+                    builder.transfer_arg(recipient, split);
+                }
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
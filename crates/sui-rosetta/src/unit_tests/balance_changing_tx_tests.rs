@@ -33,7 +33,7 @@ use sui_types::messages::{
 };
 use test_utils::network::TestClusterBuilder;
 
-use crate::state::extract_balance_changes_from_ops;
+use crate::state::{extract_balance_changes_from_ops, CoinMetadataCache};
 use crate::types::ConstructionMetadata;
 
 #[tokio::test]
@@ -637,7 +637,10 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
+    let ops =
+        Operations::try_from_response(response.clone(), &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
     let balances_from_ops = extract_balance_changes_from_ops(ops);
 
     // get actual balance changed after transaction
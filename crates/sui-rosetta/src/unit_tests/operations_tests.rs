@@ -5,10 +5,11 @@ use move_core_types::value::MoveTypeLayout;
 use sui_json_rpc_types::SuiCallArg;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::messages::{CallArg, TransactionData};
+use sui_types::parse_sui_type_tag;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, Currency, InternalOperation, OperationType};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -43,6 +44,55 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pay_coin_data_parsing() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let gas = random_ref();
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let coin_type = parse_sui_type_tag(&format!("{}::usdc::USDC", ObjectID::random()))?;
+    let currency = Currency::new("USDC".to_string(), 6, &coin_type);
+
+    let objects = vec![random_ref(), random_ref()];
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: objects.clone(),
+        total_coin_value: 0,
+        gas_price: 1,
+        budget: 1000,
+    };
+    let data = InternalOperation::PayCoin {
+        sender,
+        recipients: vec![recipient],
+        amounts: vec![10000],
+        currency: currency.clone(),
+    }
+    .try_into_data(metadata())?;
+
+    // The transaction only holds the coin type of the currency, its symbol and decimals are
+    // looked up in the coin metadata.
+    let ops: Operations = data.clone().try_into()?;
+    for op in ops.iter() {
+        assert_eq!(op.type_, OperationType::PayCoin);
+        assert_eq!(
+            op.amount.as_ref().unwrap().currency,
+            Currency::of_coin_type(&coin_type)
+        );
+    }
+    let parsed_data = ops.into_internal()?.try_into_data(metadata())?;
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
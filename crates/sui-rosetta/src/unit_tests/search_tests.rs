@@ -0,0 +1,155 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::{json, Value};
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::query::TransactionFilter;
+
+use crate::search::TransactionSearch;
+use crate::types::{SearchTransactionsRequest, Transaction};
+
+fn search(conditions: Value) -> TransactionSearch {
+    let mut request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+    });
+    request
+        .as_object_mut()
+        .unwrap()
+        .extend(conditions.as_object().unwrap().clone());
+    serde_json::from_value::<SearchTransactionsRequest>(request)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+fn pay_sui(digest: TransactionDigest, sender: SuiAddress, recipient: SuiAddress) -> Transaction {
+    serde_json::from_value(json!({
+        "transaction_identifier": { "hash": digest },
+        "operations": [
+            {
+                "operation_identifier": { "index": 0 },
+                "type": "SuiBalanceChange",
+                "status": "SUCCESS",
+                "account": { "address": recipient },
+                "amount": { "value": "1000", "currency": { "symbol": "SUI", "decimals": 9 } },
+            },
+            {
+                "operation_identifier": { "index": 1 },
+                "type": "Gas",
+                "status": "SUCCESS",
+                "account": { "address": sender },
+                "amount": { "value": "-1000", "currency": { "symbol": "SUI", "decimals": 9 } },
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_search_conditions() {
+    let digest = TransactionDigest::random();
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let other = SuiAddress::random_for_testing_only();
+    let tx = pay_sui(digest, sender, recipient);
+
+    assert!(search(json!({})).matches(&tx));
+    assert!(search(json!({ "address": recipient })).matches(&tx));
+    assert!(search(json!({ "account_identifier": { "address": sender } })).matches(&tx));
+    assert!(!search(json!({ "address": other })).matches(&tx));
+    assert!(search(json!({ "transaction_identifier": { "hash": digest } })).matches(&tx));
+    assert!(search(json!({ "type": "Gas", "success": true })).matches(&tx));
+    assert!(!search(json!({ "type": "PaySui" })).matches(&tx));
+    assert!(!search(json!({ "status": "FAILURE" })).matches(&tx));
+
+    // All conditions must hold by default.
+    assert!(!search(json!({ "address": recipient, "type": "PaySui" })).matches(&tx));
+    assert!(
+        search(json!({ "operator": "or", "address": recipient, "type": "PaySui" })).matches(&tx)
+    );
+    assert!(!search(json!({ "operator": "or", "address": other, "type": "PaySui" })).matches(&tx));
+}
+
+#[test]
+fn test_search_filter() {
+    let address = SuiAddress::random_for_testing_only();
+    let digest = TransactionDigest::random();
+
+    assert!(search(json!({ "type": "Gas" })).filter().is_none());
+    assert_eq!(
+        json!(search(json!({ "max_block": 10 })).filter()),
+        json!(TransactionFilter::Checkpoint { start: 0, end: 11 })
+    );
+    assert_eq!(
+        json!(search(json!({ "address": address, "max_block": 10 })).filter()),
+        json!(TransactionFilter::Checkpoint { start: 0, end: 11 }.and(
+            TransactionFilter::FromAddress(address).or(TransactionFilter::ToAddress(address))
+        ))
+    );
+    let other = SuiAddress::random_for_testing_only();
+    assert_eq!(
+        json!(search(json!({
+            "operator": "or",
+            "address": address,
+            "account_identifier": { "address": other },
+        }))
+        .filter()),
+        json!(
+            (TransactionFilter::FromAddress(other).or(TransactionFilter::ToAddress(other)))
+                .or(TransactionFilter::FromAddress(address)
+                    .or(TransactionFilter::ToAddress(address)))
+        )
+    );
+    // Transactions matching either condition can only be found by scanning.
+    assert!(
+        search(json!({ "operator": "or", "address": address, "type": "Gas" }))
+            .filter()
+            .is_none()
+    );
+
+    let search_by_digest = search(json!({ "transaction_identifier": { "hash": digest } }));
+    assert_eq!(search_by_digest.required_transaction(), Some(digest));
+    let search_by_digest = search(json!({
+        "operator": "or",
+        "transaction_identifier": { "hash": digest },
+        "address": address,
+    }));
+    assert_eq!(search_by_digest.required_transaction(), None);
+}
+
+#[test]
+fn test_search_rejects_unindexed_searches() {
+    let address = SuiAddress::random_for_testing_only();
+    let digest = TransactionDigest::random();
+
+    assert!(search(json!({})).ensure_indexed().is_err());
+    assert!(search(json!({ "type": "Gas", "max_block": 10 }))
+        .ensure_indexed()
+        .is_err());
+    assert!(
+        search(json!({ "operator": "or", "address": address, "type": "Gas" }))
+            .ensure_indexed()
+            .is_err()
+    );
+    assert!(search(json!({ "address": address, "type": "Gas" }))
+        .ensure_indexed()
+        .is_ok());
+    assert!(
+        search(json!({ "transaction_identifier": { "hash": digest } }))
+            .ensure_indexed()
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_search_rejects_sub_accounts() {
+    let request: SearchTransactionsRequest = serde_json::from_value(json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "account_identifier": {
+            "address": SuiAddress::random_for_testing_only(),
+            "sub_account": { "address": "Stake" },
+        },
+    }))
+    .unwrap();
+    assert!(TransactionSearch::try_from(request).is_err());
+}
@@ -14,6 +14,7 @@ use sui_rosetta::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
     SubAccount, SubAccountType, SuiEnv,
 };
+use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionEffectsAPI};
 use sui_types::messages::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
@@ -174,7 +175,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -235,7 +238,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -351,7 +356,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -421,7 +428,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -481,7 +490,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
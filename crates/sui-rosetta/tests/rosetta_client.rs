@@ -24,7 +24,7 @@ use sui_rosetta::types::{
     ConstructionPreprocessResponse, ConstructionSubmitRequest, NetworkIdentifier, Signature,
     SignatureType, SubAccount, SubAccountType, SuiEnv, TransactionIdentifierResponse,
 };
-use sui_rosetta::{RosettaOfflineServer, RosettaOnlineServer};
+use sui_rosetta::{CoinMetadataCache, RosettaOfflineServer, RosettaOnlineServer};
 use sui_sdk::SuiClient;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::SuiSignature;
@@ -33,9 +33,10 @@ pub async fn start_rosetta_test_server(
     client: SuiClient,
     dir: &Path,
 ) -> (RosettaClient, Vec<JoinHandle<hyper::Result<()>>>) {
+    let offline_server = RosettaOfflineServer::new(SuiEnv::LocalNet)
+        .with_coin_metadata_cache(CoinMetadataCache::new(client.clone()));
     let online_server =
         RosettaOnlineServer::new(SuiEnv::LocalNet, client, &dir.join("rosetta_data"));
-    let offline_server = RosettaOfflineServer::new(SuiEnv::LocalNet);
     let local_ip = utils::get_local_ip_for_tests().to_string();
     let port = utils::get_available_port(&local_ip);
     let rosetta_address = format!("{}:{}", local_ip, port);
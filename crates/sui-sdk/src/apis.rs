@@ -3,6 +3,8 @@
 
 use crate::error::{Error, SuiRpcResult};
 use crate::{RpcClient, WAIT_FOR_TX_TIMEOUT_SEC};
+use anyhow::anyhow;
+use async_trait::async_trait;
 use fastcrypto::encoding::Base64;
 use futures::stream;
 use futures_core::Stream;
@@ -15,22 +17,25 @@ use sui_json_rpc::api::GovernanceReadApiClient;
 use sui_json_rpc::api::IndexerApiClient;
 use sui_json_rpc::api::MoveUtilsClient;
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DryRunTransactionResponse,
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, SuiCoinMetadata, SuiCommittee, SuiEvent,
-    SuiGetPastObjectRequest, SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiPastObjectResponse, SuiTransactionEffectsAPI,
-    SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResponseQuery,
-    TransactionsPage,
+    Balance, Checkpoint, CheckpointBalance, CheckpointId, Coin, CoinPage, DelegatedStake,
+    DryRunTransactionResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, PackageStore,
+    SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest, SuiMoveNormalizedModule,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
+    SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
+    SuiTransactionResponseQuery, TransactionsPage,
 };
 use sui_types::balance::Supply;
 use sui_types::base_types::{
     ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
 };
 use sui_types::committee::EpochId;
-use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
+use sui_types::error::{SuiObjectResponseError, TRANSACTION_NOT_FOUND_MSG_PREFIX};
 use sui_types::event::EventID;
 use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::move_package::MovePackage;
+use sui_types::object::Object;
+use sui_types::query::TransactionFilter;
 
 use futures::StreamExt;
 use sui_json_rpc::api::{CoinReadApiClient, ReadApiClient, WriteApiClient};
@@ -173,6 +178,22 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn subscribe_transaction(
+        &self,
+        filter: TransactionFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiTransactionResponse>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiTransactionResponse> =
+                    c.subscribe_transaction(filter).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
     /// Return a checkpoint
     pub async fn get_checkpoint(&self, id: CheckpointId) -> SuiRpcResult<Checkpoint> {
         Ok(self.api.http.get_checkpoint(id).await?)
@@ -240,11 +261,28 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?))
+            .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?), None)
             .await?)
     }
 }
 
+#[async_trait]
+impl PackageStore for ReadApi {
+    async fn fetch(&self, id: ObjectID) -> anyhow::Result<Option<MovePackage>> {
+        let response = self
+            .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+            .await?;
+        match (response.data, response.error) {
+            (Some(data), _) => {
+                let object: Object = data.try_into()?;
+                Ok(object.data.try_as_package().cloned())
+            }
+            (None, Some(SuiObjectResponseError::NotExists { .. })) => Ok(None),
+            (None, error) => Err(anyhow!("Failed to fetch package {id}: {error:?}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CoinReadApi {
     api: Arc<RpcClient>,
@@ -343,6 +381,20 @@ impl CoinReadApi {
         Ok(self.api.http.get_balance(owner, coin_type).await?)
     }
 
+    /// The balance of `coin_type` owned by `owner` after the execution of `checkpoint`.
+    pub async fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<CheckpointBalance> {
+        Ok(self
+            .api
+            .http
+            .get_balance_at_checkpoint(owner, coin_type, checkpoint.into())
+            .await?)
+    }
+
     pub async fn get_all_balances(&self, owner: SuiAddress) -> SuiRpcResult<Vec<Balance>> {
         Ok(self.api.http.get_all_balances(owner).await?)
     }
@@ -369,10 +421,22 @@ impl EventApi {
     pub async fn subscribe_event(
         &self,
         filter: EventFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiEvent>>> {
+        self.subscribe_event_from(filter, None).await
+    }
+
+    /// Subscribe to events matching `filter`, replaying the events emitted after the exclusive
+    /// `cursor` before switching to live events. This can be used to resume a dropped
+    /// subscription from the last received event.
+    pub async fn subscribe_event_from(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
     ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiEvent>>> {
         match &self.api.ws {
             Some(c) => {
-                let subscription: Subscription<SuiEvent> = c.subscribe_event(filter).await?;
+                let subscription: Subscription<SuiEvent> =
+                    c.subscribe_event(filter, cursor).await?;
                 Ok(subscription.map(|item| Ok(item?)))
             }
             _ => Err(Error::Subscription(
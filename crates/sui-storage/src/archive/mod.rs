@@ -0,0 +1,353 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Continuous archival of certified checkpoints to an object store.
+//!
+//! The archive is laid out as follows:
+//! ```text
+//! MANIFEST
+//! checkpoints/
+//!     0.chk
+//!     1000.chk
+//!     2000.chk
+//!     ...
+//! ```
+//! Each bundle file holds `checkpoints_per_bundle` consecutive checkpoints, starting at the
+//! sequence number in its name, together with their full contents (transactions and effects).
+//! Bundles are a short header followed by a snappy-compressed, BCS-encoded list of
+//! [`CheckpointBundleEntry`]. The `MANIFEST` records the bundle size, the sequence number of the
+//! first archived checkpoint and the one of the first checkpoint which has not been archived yet,
+//! i.e. the upload watermark. Archives written by nodes which no longer hold every checkpoint since
+//! genesis start at the first complete bundle still available to them.
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use object_store::path::Path;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+
+pub mod reader;
+pub mod writer;
+
+pub use reader::ArchiveReader;
+pub use writer::ArchiveWriter;
+
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const CHECKPOINT_DIR: &str = "checkpoints";
+pub const BUNDLE_FILE_SUFFIX: &str = "chk";
+pub const BUNDLE_MAGIC: u32 = 0x5C4B_A5C4;
+pub const ARCHIVE_FORMAT_VERSION: u8 = 1;
+pub const DEFAULT_CHECKPOINTS_PER_BUNDLE: u64 = 1000;
+
+const BUNDLE_HEADER_LEN: usize = 5;
+
+/// Describes the layout of an archive and how far it has been written.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Manifest {
+    pub version: u8,
+    pub checkpoints_per_bundle: u64,
+    /// Sequence number of the first checkpoint in the archive, at the start of a bundle
+    pub first_checkpoint_seq_num: CheckpointSequenceNumber,
+    /// Sequence number of the first checkpoint which is not yet in the archive
+    pub next_checkpoint_seq_num: CheckpointSequenceNumber,
+}
+
+impl Manifest {
+    pub fn new(
+        checkpoints_per_bundle: u64,
+        first_checkpoint_seq_num: CheckpointSequenceNumber,
+    ) -> Self {
+        Manifest {
+            version: ARCHIVE_FORMAT_VERSION,
+            checkpoints_per_bundle,
+            first_checkpoint_seq_num,
+            next_checkpoint_seq_num: first_checkpoint_seq_num,
+        }
+    }
+
+    /// Sequence number of the first checkpoint in the bundle containing `sequence_number`.
+    pub fn bundle_start(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> CheckpointSequenceNumber {
+        sequence_number - sequence_number % self.checkpoints_per_bundle
+    }
+
+    /// Highest checkpoint available in the archive, if any.
+    pub fn latest_checkpoint(&self) -> Option<CheckpointSequenceNumber> {
+        (self.next_checkpoint_seq_num > self.first_checkpoint_seq_num)
+            .then(|| self.next_checkpoint_seq_num - 1)
+    }
+}
+
+/// A single archived checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CheckpointBundleEntry {
+    pub summary: CertifiedCheckpointSummary,
+    pub contents: FullCheckpointContents,
+}
+
+pub fn manifest_path() -> Path {
+    Path::from(MANIFEST_FILENAME)
+}
+
+pub fn bundle_path(first_sequence_number: CheckpointSequenceNumber) -> Path {
+    Path::from(CHECKPOINT_DIR).child(format!("{first_sequence_number}.{BUNDLE_FILE_SUFFIX}"))
+}
+
+pub fn encode_manifest(manifest: &Manifest) -> Result<Bytes> {
+    Ok(Bytes::from(bcs::to_bytes(manifest)?))
+}
+
+pub fn decode_manifest(bytes: &[u8]) -> Result<Manifest> {
+    let manifest: Manifest = bcs::from_bytes(bytes)?;
+    if manifest.version != ARCHIVE_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Unsupported archive format version: {}",
+            manifest.version
+        ));
+    }
+    if manifest.checkpoints_per_bundle == 0 {
+        return Err(anyhow!("Archive manifest has an empty bundle size"));
+    }
+    if manifest.first_checkpoint_seq_num > manifest.next_checkpoint_seq_num {
+        return Err(anyhow!(
+            "Archive manifest starts at checkpoint {} after its watermark {}",
+            manifest.first_checkpoint_seq_num,
+            manifest.next_checkpoint_seq_num
+        ));
+    }
+    Ok(manifest)
+}
+
+pub fn encode_bundle(entries: &[CheckpointBundleEntry]) -> Result<Bytes> {
+    let mut buf = Vec::with_capacity(BUNDLE_HEADER_LEN);
+    buf.extend_from_slice(&BUNDLE_MAGIC.to_be_bytes());
+    buf.push(ARCHIVE_FORMAT_VERSION);
+    let mut encoder = snap::write::FrameEncoder::new(buf);
+    encoder.write_all(&bcs::to_bytes(entries)?)?;
+    let buf = encoder
+        .into_inner()
+        .map_err(|e| anyhow!("Failed to compress checkpoint bundle: {e}"))?;
+    Ok(Bytes::from(buf))
+}
+
+pub fn decode_bundle(bytes: &[u8]) -> Result<Vec<CheckpointBundleEntry>> {
+    if bytes.len() < BUNDLE_HEADER_LEN {
+        return Err(anyhow!("Checkpoint bundle is too short"));
+    }
+    let (header, compressed) = bytes.split_at(BUNDLE_HEADER_LEN);
+    let magic = u32::from_be_bytes(header[..4].try_into()?);
+    if magic != BUNDLE_MAGIC {
+        return Err(anyhow!("Unexpected checkpoint bundle magic: {magic:#x}"));
+    }
+    if header[4] != ARCHIVE_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Unsupported checkpoint bundle version: {}",
+            header[4]
+        ));
+    }
+    let mut raw = Vec::new();
+    snap::read::FrameDecoder::new(compressed).read_to_end(&mut raw)?;
+    Ok(bcs::from_bytes(&raw)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::archive::{
+        decode_bundle, encode_bundle, ArchiveReader, ArchiveWriter, CheckpointBundleEntry,
+    };
+    use crate::object_store::{ObjectStoreConfig, ObjectStoreType};
+    use std::num::NonZeroUsize;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::{AuthorityKeyPair, KeypairTraits};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointSummary, FullCheckpointContents,
+        SignedCheckpointSummary, VerifiedCheckpoint, VerifiedCheckpointContents,
+    };
+    use sui_types::storage::{ReadStore, SharedInMemoryStore};
+    use tempfile::TempDir;
+
+    fn empty_contents() -> FullCheckpointContents {
+        FullCheckpointContents::new_with_causally_ordered_transactions(std::iter::empty())
+    }
+
+    fn make_checkpoints(
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+        count: u64,
+    ) -> Vec<VerifiedCheckpoint> {
+        let contents = empty_contents().into_checkpoint_contents();
+        let mut previous_digest = None;
+        (0..count)
+            .map(|sequence_number| {
+                let summary = CheckpointSummary::new(
+                    committee.epoch,
+                    sequence_number,
+                    0,
+                    &contents,
+                    previous_digest,
+                    GasCostSummary::default(),
+                    None,
+                    0,
+                );
+                let signatures = keys
+                    .iter()
+                    .map(|key| {
+                        SignedCheckpointSummary::new(
+                            committee.epoch,
+                            summary.clone(),
+                            key,
+                            key.public().into(),
+                        )
+                        .auth_sig()
+                        .clone()
+                    })
+                    .collect();
+                let checkpoint = VerifiedCheckpoint::new_unchecked(
+                    CertifiedCheckpointSummary::new(summary, signatures, committee).unwrap(),
+                );
+                previous_digest = Some(*checkpoint.digest());
+                checkpoint
+            })
+            .collect()
+    }
+
+    fn make_store(
+        committee: &Committee,
+        checkpoints: &[VerifiedCheckpoint],
+    ) -> SharedInMemoryStore {
+        let store = SharedInMemoryStore::default();
+        let (genesis, rest) = checkpoints.split_first().unwrap();
+        store.inner_mut().insert_genesis_state(
+            genesis.clone(),
+            VerifiedCheckpointContents::new_unchecked(empty_contents()),
+            committee.clone(),
+        );
+        for checkpoint in rest {
+            store.inner_mut().insert_checkpoint(checkpoint.clone());
+            store.inner_mut().insert_checkpoint_contents(
+                VerifiedCheckpointContents::new_unchecked(empty_contents()),
+            );
+            store
+                .inner_mut()
+                .update_highest_synced_checkpoint(checkpoint);
+        }
+        store
+    }
+
+    #[test]
+    fn test_bundle_encoding_round_trip() -> anyhow::Result<()> {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let entries: Vec<_> = make_checkpoints(&committee, &keys, 3)
+            .into_iter()
+            .map(|checkpoint| CheckpointBundleEntry {
+                summary: checkpoint.into_inner(),
+                contents: empty_contents(),
+            })
+            .collect();
+        let bytes = encode_bundle(&entries)?;
+        assert_eq!(decode_bundle(&bytes)?, entries);
+
+        let mut corrupted = bytes.to_vec();
+        corrupted[0] ^= 0xff;
+        assert!(decode_bundle(&corrupted).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archive_write_and_sync() -> anyhow::Result<()> {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let checkpoints = make_checkpoints(&committee, &keys, 10);
+        let source = make_store(&committee, &checkpoints);
+
+        let archive = TempDir::new()?;
+        let archive_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(archive.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        // Only the two complete bundles of four checkpoints are written
+        let writer = ArchiveWriter::new(source, &archive_config, 4, 60)?;
+        assert_eq!(writer.upload_pending_bundles().await?, 8);
+        assert_eq!(writer.upload_pending_bundles().await?, 8);
+
+        let reader = ArchiveReader::new(&archive_config, NonZeroUsize::new(2).unwrap())?;
+        assert_eq!(reader.latest_available_checkpoint().await?, Some(7));
+        assert_eq!(reader.read_bundle(4).await?.len(), 4);
+
+        let target = make_store(&committee, &checkpoints[..2]);
+        let highest = reader.sync_to_store(&target, Some(5)).await?;
+        assert_eq!(highest.sequence_number(), checkpoints[5].sequence_number());
+        let highest = reader.sync_to_store(&target, None).await?;
+        assert_eq!(highest.digest(), checkpoints[7].digest());
+        assert_eq!(
+            target.get_highest_synced_checkpoint().unwrap().digest(),
+            checkpoints[7].digest()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archive_starts_at_lowest_available_checkpoint() -> anyhow::Result<()> {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let checkpoints = make_checkpoints(&committee, &keys, 10);
+        // A node restored from a snapshot, without the first three checkpoints
+        let source = make_store(&committee, &checkpoints[3..]);
+
+        let archive = TempDir::new()?;
+        let archive_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(archive.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        // The archive starts at the first complete bundle available
+        let writer = ArchiveWriter::new(source, &archive_config, 4, 60)?;
+        assert_eq!(writer.upload_pending_bundles().await?, 8);
+        let reader = ArchiveReader::new(&archive_config, NonZeroUsize::new(2).unwrap())?;
+        let manifest = reader.read_manifest().await?;
+        assert_eq!(manifest.first_checkpoint_seq_num, 4);
+        assert_eq!(manifest.latest_checkpoint(), Some(7));
+
+        // Stores missing the checkpoints before the archive can't be synced from it
+        let target = make_store(&committee, &checkpoints[..2]);
+        assert!(reader.sync_to_store(&target, None).await.is_err());
+
+        let target = make_store(&committee, &checkpoints[..4]);
+        let highest = reader.sync_to_store(&target, None).await?;
+        assert_eq!(highest.digest(), checkpoints[7].digest());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archive_sync_rejects_unknown_committee() -> anyhow::Result<()> {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let checkpoints = make_checkpoints(&committee, &keys, 4);
+        let archive = TempDir::new()?;
+        let archive_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(archive.path().to_path_buf()),
+            ..Default::default()
+        };
+        let writer =
+            ArchiveWriter::new(make_store(&committee, &checkpoints), &archive_config, 2, 60)?;
+        writer.upload_pending_bundles().await?;
+
+        // Checkpoints signed by a committee the target store doesn't know about are rejected
+        let (other_committee, _) = Committee::new_simple_test_committee();
+        let target = make_store(&other_committee, &checkpoints[..1]);
+        let reader = ArchiveReader::new(&archive_config, NonZeroUsize::new(1).unwrap())?;
+        assert!(reader.sync_to_store(&target, None).await.is_err());
+        assert_eq!(
+            target.get_highest_synced_checkpoint().unwrap().digest(),
+            checkpoints[0].digest()
+        );
+        Ok(())
+    }
+}
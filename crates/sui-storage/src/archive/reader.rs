@@ -0,0 +1,153 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::archive::{
+    bundle_path, decode_bundle, decode_manifest, manifest_path, CheckpointBundleEntry, Manifest,
+};
+use crate::object_store::ObjectStoreConfig;
+use anyhow::{anyhow, Result};
+use futures::{Stream, StreamExt, TryStreamExt};
+use object_store::DynObjectStore;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::sync::Arc;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, VerifiedCheckpoint, VerifiedCheckpointContents,
+};
+use sui_types::storage::{ReadStore, WriteStore};
+use tracing::info;
+
+/// Reads checkpoints back out of an archive written by [`crate::archive::ArchiveWriter`].
+pub struct ArchiveReader {
+    remote_object_store: Arc<DynObjectStore>,
+    /// Number of bundles to download in parallel
+    concurrency: NonZeroUsize,
+}
+
+impl ArchiveReader {
+    pub fn new(remote_store_config: &ObjectStoreConfig, concurrency: NonZeroUsize) -> Result<Self> {
+        Ok(ArchiveReader {
+            remote_object_store: remote_store_config.make()?,
+            concurrency,
+        })
+    }
+
+    pub async fn read_manifest(&self) -> Result<Manifest> {
+        let result = self.remote_object_store.get(&manifest_path()).await?;
+        decode_manifest(&result.bytes().await?)
+    }
+
+    /// Highest checkpoint available in the archive, if any.
+    pub async fn latest_available_checkpoint(&self) -> Result<Option<CheckpointSequenceNumber>> {
+        Ok(self.read_manifest().await?.latest_checkpoint())
+    }
+
+    /// Reads the bundle starting at checkpoint `first_sequence_number`.
+    pub async fn read_bundle(
+        &self,
+        first_sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Vec<CheckpointBundleEntry>> {
+        read_bundle(self.remote_object_store.clone(), first_sequence_number).await
+    }
+
+    /// Streams the archived checkpoints in `range` in sequence number order. Entries are returned
+    /// as stored and have not been verified.
+    pub fn stream_checkpoints(
+        &self,
+        manifest: &Manifest,
+        range: Range<CheckpointSequenceNumber>,
+    ) -> impl Stream<Item = Result<CheckpointBundleEntry>> {
+        let end = range.end.min(manifest.next_checkpoint_seq_num);
+        let start = range.start.max(manifest.first_checkpoint_seq_num);
+        let bundle_starts = if start < end {
+            (manifest.bundle_start(start)..end)
+                .step_by(manifest.checkpoints_per_bundle as usize)
+                .collect()
+        } else {
+            vec![]
+        };
+        let store = self.remote_object_store.clone();
+        futures::stream::iter(bundle_starts)
+            .map(move |first| read_bundle(store.clone(), first))
+            .buffered(self.concurrency.get())
+            .map_ok(|entries| futures::stream::iter(entries.into_iter().map(Ok)))
+            .try_flatten()
+            .try_filter(move |entry| {
+                futures::future::ready((start..end).contains(entry.summary.sequence_number()))
+            })
+    }
+
+    /// Backfills `store` from the archive, starting after its highest synced checkpoint and
+    /// stopping at `up_to` (inclusive) or the end of the archive, whichever comes first. Every
+    /// checkpoint is verified against its predecessor and the committee of its epoch, and its
+    /// contents against the checkpoint's content digest, exactly as if it had been received from
+    /// a peer during state sync. Returns the highest synced checkpoint of `store` afterwards.
+    pub async fn sync_to_store<S>(
+        &self,
+        store: S,
+        up_to: Option<CheckpointSequenceNumber>,
+    ) -> Result<VerifiedCheckpoint>
+    where
+        S: WriteStore,
+        <S as ReadStore>::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let manifest = self.read_manifest().await?;
+        let mut current = store.get_highest_synced_checkpoint()?;
+        let start = current.sequence_number().saturating_add(1);
+        let end = up_to.map_or(manifest.next_checkpoint_seq_num, |seq| {
+            seq.saturating_add(1)
+        });
+        if start < manifest.first_checkpoint_seq_num && start < end {
+            return Err(anyhow!(
+                "Archive starts at checkpoint {}, after checkpoint {start} to sync",
+                manifest.first_checkpoint_seq_num
+            ));
+        }
+        info!(
+            "Syncing checkpoints {start} to {} from archive",
+            end.min(manifest.next_checkpoint_seq_num).saturating_sub(1)
+        );
+
+        let mut entries = Box::pin(self.stream_checkpoints(&manifest, start..end));
+        while let Some(CheckpointBundleEntry { summary, contents }) = entries.try_next().await? {
+            let sequence_number = *summary.sequence_number();
+            if summary.previous_digest != Some(*current.digest()) {
+                return Err(anyhow!(
+                    "Archived checkpoint {sequence_number} does not extend checkpoint {}",
+                    current.sequence_number()
+                ));
+            }
+            let current_epoch = current.epoch();
+            if summary.epoch() != current_epoch
+                && !(summary.epoch() == current_epoch.saturating_add(1)
+                    && current.next_epoch_committee().is_some())
+            {
+                return Err(anyhow!(
+                    "Archived checkpoint {sequence_number} has unexpected epoch {}",
+                    summary.epoch()
+                ));
+            }
+            let committee = store
+                .get_committee(summary.epoch())?
+                .ok_or_else(|| anyhow!("Missing committee for epoch {}", summary.epoch()))?;
+            summary.verify_signature(&committee)?;
+            contents.verify_digests(summary.content_digest)?;
+
+            let checkpoint = VerifiedCheckpoint::new_unchecked(summary);
+            store.insert_checkpoint(checkpoint.clone())?;
+            store
+                .insert_checkpoint_contents(VerifiedCheckpointContents::new_unchecked(contents))?;
+            store.update_highest_synced_checkpoint(&checkpoint)?;
+            current = checkpoint;
+        }
+        Ok(current)
+    }
+}
+
+async fn read_bundle(
+    store: Arc<DynObjectStore>,
+    first_sequence_number: CheckpointSequenceNumber,
+) -> Result<Vec<CheckpointBundleEntry>> {
+    let result = store.get(&bundle_path(first_sequence_number)).await?;
+    decode_bundle(&result.bytes().await?)
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::archive::{
+    bundle_path, decode_manifest, encode_bundle, encode_manifest, manifest_path,
+    CheckpointBundleEntry, Manifest,
+};
+use crate::object_store::util::put;
+use crate::object_store::ObjectStoreConfig;
+use anyhow::{anyhow, Result};
+use object_store::{DynObjectStore, Error};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ReadStore;
+use tokio::sync::oneshot::{self, Sender};
+use tracing::{debug, error, info, warn};
+
+/// Periodically uploads synced checkpoints from `store` to a remote object store, one bundle of
+/// `checkpoints_per_bundle` checkpoints at a time. Only complete bundles are uploaded, so the
+/// archive may trail the local node by up to `checkpoints_per_bundle - 1` checkpoints. A new
+/// archive starts at the first complete bundle of checkpoints still available in `store`.
+pub struct ArchiveWriter<S> {
+    store: S,
+    /// Bucket on cloud object store where checkpoint bundles are written
+    remote_object_store: Arc<DynObjectStore>,
+    /// Number of checkpoints in each bundle of a newly created archive
+    checkpoints_per_bundle: u64,
+    /// Time interval to check for new checkpoints to archive
+    interval: Duration,
+}
+
+impl<S> ArchiveWriter<S>
+where
+    S: ReadStore + Send + Sync + 'static,
+    <S as ReadStore>::Error: std::error::Error + Send + Sync + 'static,
+{
+    pub fn new(
+        store: S,
+        remote_store_config: &ObjectStoreConfig,
+        checkpoints_per_bundle: u64,
+        interval_s: u64,
+    ) -> Result<Self> {
+        if checkpoints_per_bundle == 0 {
+            return Err(anyhow!("Checkpoints per bundle must be non zero"));
+        }
+        Ok(ArchiveWriter {
+            store,
+            remote_object_store: remote_store_config.make()?,
+            checkpoints_per_bundle,
+            interval: Duration::from_secs(interval_s),
+        })
+    }
+
+    pub fn start(self) -> Sender<()> {
+        let (sender, mut recv) = oneshot::channel::<()>();
+        let mut interval = tokio::time::interval(self.interval);
+        tokio::task::spawn(async move {
+            info!("Checkpoint archive writer loop started");
+            loop {
+                tokio::select! {
+                    _now = interval.tick() => {
+                        if let Err(err) = self.upload_pending_bundles().await {
+                            error!("Failed to upload checkpoints to archive with err: {:?}", err);
+                        }
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+        });
+        sender
+    }
+
+    /// Uploads every complete bundle of synced checkpoints which is not yet in the archive and
+    /// returns the new upload watermark.
+    pub async fn upload_pending_bundles(&self) -> Result<CheckpointSequenceNumber> {
+        let highest_synced = *self
+            .store
+            .get_highest_synced_checkpoint()?
+            .sequence_number();
+        let mut manifest = self.read_or_create_manifest(highest_synced).await?;
+        while manifest.next_checkpoint_seq_num + manifest.checkpoints_per_bundle - 1
+            <= highest_synced
+        {
+            let first = manifest.next_checkpoint_seq_num;
+            let entries = self.read_bundle_entries(first, manifest.checkpoints_per_bundle)?;
+            put(
+                &bundle_path(first),
+                encode_bundle(&entries)?,
+                self.remote_object_store.clone(),
+            )
+            .await?;
+            // The manifest is only advanced once the bundle is durably written, so a crash in
+            // between results in the same bundle being uploaded again.
+            manifest.next_checkpoint_seq_num += manifest.checkpoints_per_bundle;
+            put(
+                &manifest_path(),
+                encode_manifest(&manifest)?,
+                self.remote_object_store.clone(),
+            )
+            .await?;
+            debug!(
+                "Archived checkpoints {first} to {}",
+                manifest.next_checkpoint_seq_num - 1
+            );
+        }
+        Ok(manifest.next_checkpoint_seq_num)
+    }
+
+    async fn read_or_create_manifest(
+        &self,
+        highest_synced: CheckpointSequenceNumber,
+    ) -> Result<Manifest> {
+        match self.remote_object_store.get(&manifest_path()).await {
+            Ok(result) => {
+                let manifest = decode_manifest(&result.bytes().await?)?;
+                if manifest.checkpoints_per_bundle != self.checkpoints_per_bundle {
+                    warn!(
+                        "Existing archive uses {} checkpoints per bundle instead of configured {}",
+                        manifest.checkpoints_per_bundle, self.checkpoints_per_bundle
+                    );
+                }
+                Ok(manifest)
+            }
+            Err(Error::NotFound { .. }) => {
+                let first = self.first_complete_bundle(highest_synced)?;
+                info!("No archive manifest found, starting a new archive at checkpoint {first}");
+                Ok(Manifest::new(self.checkpoints_per_bundle, first))
+            }
+            Err(err) => Err(anyhow!(
+                "Failed to read archive manifest with error: {:?}",
+                err
+            )),
+        }
+    }
+
+    /// Start of the first bundle whose checkpoints are all available in the store. Nodes restored
+    /// from a snapshot or pruning their history only hold the checkpoints from some sequence
+    /// number on, which is found by bisection.
+    fn first_complete_bundle(
+        &self,
+        highest_synced: CheckpointSequenceNumber,
+    ) -> Result<CheckpointSequenceNumber> {
+        let (mut low, mut high) = (0, highest_synced);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.is_available(mid)? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let bundle_size = self.checkpoints_per_bundle;
+        Ok((low + bundle_size - 1) / bundle_size * bundle_size)
+    }
+
+    /// Whether checkpoint `sequence_number` and its full contents are in the store.
+    fn is_available(&self, sequence_number: CheckpointSequenceNumber) -> Result<bool> {
+        Ok(
+            match self
+                .store
+                .get_checkpoint_by_sequence_number(sequence_number)?
+            {
+                Some(checkpoint) => self
+                    .store
+                    .get_full_checkpoint_contents(&checkpoint.content_digest)?
+                    .is_some(),
+                None => false,
+            },
+        )
+    }
+
+    fn read_bundle_entries(
+        &self,
+        first: CheckpointSequenceNumber,
+        count: u64,
+    ) -> Result<Vec<CheckpointBundleEntry>> {
+        (first..first + count)
+            .map(|sequence_number| {
+                let checkpoint = self
+                    .store
+                    .get_checkpoint_by_sequence_number(sequence_number)?
+                    .ok_or_else(|| anyhow!("Missing checkpoint {sequence_number}"))?;
+                let contents = self
+                    .store
+                    .get_full_checkpoint_contents(&checkpoint.content_digest)?
+                    .ok_or_else(|| anyhow!("Missing contents for checkpoint {sequence_number}"))?;
+                Ok(CheckpointBundleEntry {
+                    summary: checkpoint.into_inner(),
+                    contents,
+                })
+            })
+            .collect()
+    }
+}
@@ -4,13 +4,15 @@
 //! IndexStore supports creation of various ancillary indexes of state in SuiDataStore.
 //! The main user of this data is the explorer.
 
-use std::cmp::{max, min};
+use std::cmp::{max, min, Ordering as CmpOrdering};
+use std::collections::BTreeMap;
+use std::iter::Peekable;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::debug;
 
@@ -24,6 +26,7 @@ use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::fp_ensure;
 use sui_types::messages::TransactionEvents;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 use sui_types::query::TransactionFilter;
 use typed_store::rocks::DBOptions;
@@ -34,8 +37,11 @@ use typed_store_derive::DBMapUtils;
 
 type OwnerIndexKey = (SuiAddress, ObjectID);
 type DynamicFieldKey = (ObjectID, ObjectID);
+type BalanceKey = (SuiAddress, TypeTag, CheckpointSequenceNumber);
 type EventId = (TxSequenceNumber, usize);
 type EventIndex = (TransactionEventsDigest, TransactionDigest, u64);
+type TxSeqIter<'a> =
+    Box<dyn Iterator<Item = SuiResult<(TxSequenceNumber, TransactionDigest)>> + 'a>;
 
 pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
@@ -86,6 +92,31 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "transactions_seq_table_default_config"]
     transactions_seq: DBMap<TransactionDigest, TxSequenceNumber>,
 
+    /// Transactions of each checkpoint in sequence order. Only the checkpoints executed since
+    /// the node indexes them are in it, see `IndexStore::first_indexed_checkpoint`.
+    #[default_options_override_fn = "transactions_by_checkpoint_table_default_config"]
+    transaction_seqs_by_checkpoint:
+        DBMap<(CheckpointSequenceNumber, TxSequenceNumber), TransactionDigest>,
+
+    /// Index from checkpoint timestamp to checkpoint sequence number, used to translate a
+    /// time range into a checkpoint range.
+    #[default_options_override_fn = "index_table_default_config"]
+    checkpoints_by_timestamp: DBMap<(u64, CheckpointSequenceNumber), ()>,
+
+    /// Balance of address owned coins of each coin type, as of every checkpoint changing it.
+    /// The balance at any other checkpoint is the one of the latest entry before it. Balances are
+    /// accounted from genesis on, so the history is only complete up to
+    /// `balance_history_watermark`.
+    #[default_options_override_fn = "index_table_default_config"]
+    balance_history: DBMap<BalanceKey, i128>,
+
+    /// The last checkpoint up to which `balance_history` is complete, i.e. every checkpoint from
+    /// genesis to it was indexed with its balance changes. It is missing on nodes which did not
+    /// index from genesis, and stops at the last checkpoint before one whose balance changes
+    /// could not be computed.
+    #[default_options_override_fn = "index_table_default_config"]
+    balance_history_watermark: DBMap<(), CheckpointSequenceNumber>,
+
     /// This is an index of object references to currently existing objects, indexed by the
     /// composite key of the SuiAddress of their owner and the object ID of the object.
     /// This composite index allows an efficient iterator to list all objected currently owned
@@ -124,6 +155,9 @@ fn transactions_order_table_default_config() -> DBOptions {
 fn transactions_seq_table_default_config() -> DBOptions {
     default_db_options()
 }
+fn transactions_by_checkpoint_table_default_config() -> DBOptions {
+    default_db_options()
+}
 fn transactions_from_addr_table_default_config() -> DBOptions {
     default_db_options()
 }
@@ -314,17 +348,130 @@ impl IndexStore {
         Ok(sequence)
     }
 
+    /// Index the transactions of an executed checkpoint, and the balance changes of the address
+    /// owned coins they made, `None` if they could not be computed. Checkpoints must be indexed
+    /// in sequence number order.
+    pub fn index_checkpoint(
+        &self,
+        sequence: CheckpointSequenceNumber,
+        timestamp_ms: u64,
+        digests: &[TransactionDigest],
+        balance_changes: Option<Vec<(SuiAddress, TypeTag, i128)>>,
+    ) -> SuiResult {
+        // Balances are only accounted as long as no checkpoint is missing since genesis.
+        let balance_changes = match self.tables.balance_history_watermark.get(&())? {
+            Some(watermark) if watermark + 1 == sequence => balance_changes,
+            None if sequence == 0 => balance_changes,
+            _ => None,
+        };
+        let mut new_balances = vec![];
+        let mut balance_history_watermark = vec![];
+        if let Some(balance_changes) = balance_changes {
+            let mut balances = BTreeMap::<_, i128>::new();
+            for (owner, coin_type, amount) in balance_changes {
+                *balances.entry((owner, coin_type)).or_default() += amount;
+            }
+            for ((owner, coin_type), amount) in balances {
+                if amount != 0 {
+                    let balance = self.get_balance_at_checkpoint(owner, &coin_type, sequence)?;
+                    new_balances.push(((owner, coin_type, sequence), balance + amount));
+                }
+            }
+            balance_history_watermark.push(((), sequence));
+        }
+
+        let mut transaction_seqs = vec![];
+        for digest in digests {
+            // Transactions which were not indexed are not in any sequence ordered index either.
+            if let Some(seq) = self.tables.transactions_seq.get(digest)? {
+                transaction_seqs.push(((sequence, seq), *digest));
+            }
+        }
+        let batch = self.tables.transaction_seqs_by_checkpoint.batch();
+        let batch = batch.insert_batch(
+            &self.tables.transaction_seqs_by_checkpoint,
+            transaction_seqs,
+        )?;
+        let batch = batch.insert_batch(
+            &self.tables.checkpoints_by_timestamp,
+            std::iter::once(((timestamp_ms, sequence), ())),
+        )?;
+        let batch = batch.insert_batch(&self.tables.balance_history, new_balances)?;
+        let batch = batch.insert_batch(
+            &self.tables.balance_history_watermark,
+            balance_history_watermark,
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    /// The last checkpoint up to which historical balances are available, if any.
+    pub fn get_balance_history_watermark(&self) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self.tables.balance_history_watermark.get(&())?)
+    }
+
+    /// The total balance of the coins of `coin_type` owned by `owner` after the execution of
+    /// `checkpoint`, which is only accurate up to the balance history watermark.
+    pub fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: &TypeTag,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<i128> {
+        Ok(self
+            .tables
+            .balance_history
+            .iter()
+            .skip_prior_to(&(owner, coin_type.clone(), checkpoint))?
+            .next()
+            .and_then(|((address, type_, _), balance)| {
+                (address == owner && &type_ == coin_type).then_some(balance)
+            })
+            .unwrap_or_default())
+    }
+
     pub fn next_sequence_number(&self) -> TxSequenceNumber {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
 
+    /// The transactions matching `filter`, from (and excluding) `cursor`. `Checkpoint` and
+    /// `TimeRange` filters are served in checkpoint order, which needs `cursor_checkpoint`, the
+    /// checkpoint `cursor` was executed in.
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
         cursor: Option<TransactionDigest>,
+        cursor_checkpoint: Option<CheckpointSequenceNumber>,
         limit: Option<usize>,
         reverse: bool,
     ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        // Checkpoint ranges are served in checkpoint order, with a cursor into that order.
+        match &filter {
+            Some(checkpoint_filter @ TransactionFilter::Checkpoint { start, end }) => {
+                self.check_checkpoints_indexed(checkpoint_filter)?;
+                return self.get_transactions_by_checkpoint(
+                    *start,
+                    *end,
+                    cursor,
+                    cursor_checkpoint,
+                    limit,
+                    reverse,
+                );
+            }
+            Some(checkpoint_filter @ TransactionFilter::TimeRange { start_ms, end_ms }) => {
+                self.check_checkpoints_indexed(checkpoint_filter)?;
+                let (start, end) = self.checkpoint_range_for_time(*start_ms, *end_ms)?;
+                return self.get_transactions_by_checkpoint(
+                    start,
+                    end,
+                    cursor,
+                    cursor_checkpoint,
+                    limit,
+                    reverse,
+                );
+            }
+            _ => {}
+        }
         // Lookup TransactionDigest sequence number,
         let cursor = if let Some(cursor) = cursor {
             Some(
@@ -354,6 +501,22 @@ impl IndexStore {
             Some(TransactionFilter::ToAddress(address)) => {
                 Ok(self.get_transactions_to_addr(address, cursor, limit, reverse)?)
             }
+            Some(
+                filter @ (TransactionFilter::FromAndToAddress { .. }
+                | TransactionFilter::And(..)
+                | TransactionFilter::Or(..)),
+            ) => {
+                let iter = self
+                    .transaction_seq_iter(&filter, cursor, reverse)?
+                    // the cursor is exclusive
+                    .skip_while(|item| matches!(item, Ok((seq, _)) if Some(*seq) == cursor))
+                    .map(|item| item.map(|(_, digest)| digest));
+                if let Some(limit) = limit {
+                    Ok(iter.take(limit).collect::<SuiResult<_>>()?)
+                } else {
+                    Ok(iter.collect::<SuiResult<_>>()?)
+                }
+            }
             Some(_) => Err(anyhow!("Unsupported filter: {:?}", filter)),
             None => {
                 let iter = self.tables.transaction_order.iter();
@@ -494,6 +657,336 @@ impl IndexStore {
         })
     }
 
+    /// Iterate over the transactions matching `filter` in sequence order, starting from (and
+    /// including) `cursor`. `And` and `Or` are evaluated by intersecting and merging the
+    /// iterators of their operands, which is only valid because every index yields its
+    /// transactions ordered by sequence number.
+    fn transaction_seq_iter(
+        &self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> Result<TxSeqIter<'_>, anyhow::Error> {
+        Ok(match filter {
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => self.move_function_seq_iter(*package, module, function, cursor, reverse)?,
+            TransactionFilter::InputObject(object_id) => Self::index_seq_iter(
+                &self.tables.transactions_by_input_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::ChangedObject(object_id) => Self::index_seq_iter(
+                &self.tables.transactions_by_mutated_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::FromAddress(address) => Self::index_seq_iter(
+                &self.tables.transactions_from_addr,
+                *address,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::ToAddress(address) => {
+                Self::index_seq_iter(&self.tables.transactions_to_addr, *address, cursor, reverse)?
+            }
+            TransactionFilter::FromAndToAddress { from, to } => intersect_seq_iters(
+                Self::index_seq_iter(&self.tables.transactions_from_addr, *from, cursor, reverse)?,
+                Self::index_seq_iter(&self.tables.transactions_to_addr, *to, cursor, reverse)?,
+                reverse,
+            ),
+            TransactionFilter::Checkpoint { start, end } => {
+                self.check_checkpoints_indexed(filter)?;
+                self.checkpoint_seq_iter(*start, *end, cursor, reverse)?
+            }
+            TransactionFilter::TimeRange { start_ms, end_ms } => {
+                self.check_checkpoints_indexed(filter)?;
+                let (start, end) = self.checkpoint_range_for_time(*start_ms, *end_ms)?;
+                self.checkpoint_seq_iter(start, end, cursor, reverse)?
+            }
+            TransactionFilter::And(f1, f2) => intersect_seq_iters(
+                self.transaction_seq_iter(f1, cursor, reverse)?,
+                self.transaction_seq_iter(f2, cursor, reverse)?,
+                reverse,
+            ),
+            TransactionFilter::Or(f1, f2) => merge_seq_iters(
+                self.transaction_seq_iter(f1, cursor, reverse)?,
+                self.transaction_seq_iter(f2, cursor, reverse)?,
+                reverse,
+            ),
+            TransactionFilter::TransactionKind(_) => {
+                return Err(anyhow!(
+                    "Unsupported filter: {:?}, full nodes don't index transactions by kind",
+                    filter
+                ))
+            }
+        })
+    }
+
+    /// Iterate over the transactions of the checkpoints in `[start, end)` in sequence order,
+    /// starting from (and including) `cursor`. Transactions are ordered by checkpoint first in
+    /// `transaction_seqs_by_checkpoint`, so those of the range are read and sorted, which limits
+    /// the range to `MAX_TX_RANGE_SIZE` transactions.
+    fn checkpoint_seq_iter(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TxSeqIter<'_>> {
+        let mut transactions = vec![];
+        let iter = self
+            .tables
+            .transaction_seqs_by_checkpoint
+            .iter()
+            .skip_to(&(start, TxSequenceNumber::MIN))?
+            .take_while(|((checkpoint, _), _)| *checkpoint < end);
+        for (count, ((_, seq), digest)) in iter.enumerate() {
+            fp_ensure!(
+                count < MAX_TX_RANGE_SIZE as usize,
+                SuiError::FullNodeInvalidTxRangeQuery {
+                    error: format!(
+                        "Checkpoints {start} to {end} hold more than {MAX_TX_RANGE_SIZE} \
+                         transactions, which is too many to combine with other filters"
+                    ),
+                }
+            );
+            let from_cursor = cursor.map_or(true, |cursor| {
+                seq_order(seq, cursor, reverse) != CmpOrdering::Less
+            });
+            if from_cursor {
+                transactions.push((seq, digest));
+            }
+        }
+        transactions.sort_unstable_by(|(left, _), (right, _)| seq_order(*left, *right, reverse));
+        Ok(Box::new(transactions.into_iter().map(Ok)))
+    }
+
+    /// The first checkpoint indexed by the node, with its timestamp. The transactions of the
+    /// checkpoints executed before, by a node which did not index them yet or which was restored
+    /// from a snapshot, are not in the checkpoint index.
+    fn first_indexed_checkpoint(&self) -> Option<(u64, CheckpointSequenceNumber)> {
+        self.tables
+            .checkpoints_by_timestamp
+            .iter()
+            .next()
+            .map(|(key, _)| key)
+    }
+
+    /// Rejects a `Checkpoint` or `TimeRange` filter reaching back before the first indexed
+    /// checkpoint, whose transactions would be silently missing from the results.
+    fn check_checkpoints_indexed(&self, filter: &TransactionFilter) -> SuiResult {
+        let (first_timestamp_ms, first_checkpoint) = match self.first_indexed_checkpoint() {
+            Some((_, 0)) | None => return Ok(()),
+            Some(first) => first,
+        };
+        let indexed = match filter {
+            TransactionFilter::Checkpoint { start, .. } => *start >= first_checkpoint,
+            TransactionFilter::TimeRange { start_ms, .. } => *start_ms >= first_timestamp_ms,
+            _ => true,
+        };
+        fp_ensure!(
+            indexed,
+            SuiError::FullNodeInvalidTxRangeQuery {
+                error: format!(
+                    "Transactions are only indexed by checkpoint from checkpoint \
+                     {first_checkpoint} (timestamp {first_timestamp_ms}ms) on, the earlier \
+                     checkpoints were executed before this node indexed them"
+                ),
+            }
+        );
+        Ok(())
+    }
+
+    /// Translate a `[start_ms, end_ms)` time range into the range of checkpoints whose
+    /// timestamps fall within it, relying on checkpoint timestamps being non-decreasing.
+    fn checkpoint_range_for_time(
+        &self,
+        start_ms: u64,
+        end_ms: u64,
+    ) -> SuiResult<(CheckpointSequenceNumber, CheckpointSequenceNumber)> {
+        let first_checkpoint_at = |timestamp_ms: u64| -> SuiResult<CheckpointSequenceNumber> {
+            Ok(self
+                .tables
+                .checkpoints_by_timestamp
+                .iter()
+                .skip_to(&(timestamp_ms, CheckpointSequenceNumber::MIN))?
+                .next()
+                .map(|((_, sequence), _)| sequence)
+                .unwrap_or(CheckpointSequenceNumber::MAX))
+        };
+        Ok((first_checkpoint_at(start_ms)?, first_checkpoint_at(end_ms)?))
+    }
+
+    /// The transactions of the checkpoints in `[start, end)`, in checkpoint order and in sequence
+    /// order within a checkpoint, from (and excluding) `cursor`, executed in `cursor_checkpoint`.
+    fn get_transactions_by_checkpoint(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+        cursor: Option<TransactionDigest>,
+        cursor_checkpoint: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        let cursor = match (cursor, cursor_checkpoint) {
+            (Some(cursor), Some(checkpoint)) => Some((
+                checkpoint,
+                self.get_transaction_seq(&cursor)?
+                    .ok_or_else(|| anyhow!("Transaction [{cursor:?}] not found."))?,
+            )),
+            (Some(cursor), None) => {
+                return Err(anyhow!(
+                    "Transaction [{cursor:?}] not found in a checkpoint."
+                ))
+            }
+            (None, _) => None,
+        };
+        let iter = self.tables.transaction_seqs_by_checkpoint.iter();
+        let iter: Box<dyn Iterator<Item = _> + '_> = if reverse {
+            let from = cursor.map_or((end, 0), |cursor| min(cursor, (end, 0)));
+            Box::new(
+                iter.skip_prior_to(&from)?
+                    .reverse()
+                    // the cursor is exclusive, as is the end of the range
+                    .skip_while(move |(key, _)| Some(*key) == cursor || key.0 >= end)
+                    .take_while(move |((checkpoint, _), _)| *checkpoint >= start),
+            )
+        } else {
+            let from = cursor.map_or((start, 0), |cursor| max(cursor, (start, 0)));
+            Box::new(
+                iter.skip_to(&from)?
+                    // the cursor is exclusive
+                    .skip_while(move |(key, _)| Some(*key) == cursor)
+                    .take_while(move |((checkpoint, _), _)| *checkpoint < end),
+            )
+        };
+        let iter = iter.map(|(_, digest)| digest);
+        if let Some(limit) = limit {
+            Ok(iter.take(limit).collect())
+        } else {
+            Ok(iter.collect())
+        }
+    }
+
+    fn index_seq_iter<'a, KeyT: Clone + Serialize + DeserializeOwned + PartialEq + 'a>(
+        index: &'a DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: KeyT,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TxSeqIter<'a>> {
+        let iter = index.iter();
+        Ok(if reverse {
+            Box::new(
+                iter.skip_prior_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MAX)))?
+                    .reverse()
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| Ok((seq, digest))),
+            )
+        } else {
+            Box::new(
+                iter.skip_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MIN)))?
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| Ok((seq, digest))),
+            )
+        })
+    }
+
+    /// The move function index is only ordered by sequence number for a fully specified
+    /// function, so a partial filter is evaluated by merging the iterators of every function
+    /// it covers.
+    fn move_function_seq_iter(
+        &self,
+        package: ObjectID,
+        module: &Option<String>,
+        function: &Option<String>,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TxSeqIter<'_>> {
+        let functions = match (module, function) {
+            (Some(module), Some(function)) => vec![(module.clone(), function.clone())],
+            _ => self.move_functions_called(package, module, function)?,
+        };
+        let mut iters = vec![];
+        for (module, function) in functions {
+            let iter = self.tables.transactions_by_move_function.iter();
+            let iter: TxSeqIter<'_> = if reverse {
+                let key = (
+                    package,
+                    module.clone(),
+                    function.clone(),
+                    cursor.unwrap_or(TxSequenceNumber::MAX),
+                );
+                Box::new(
+                    iter.skip_prior_to(&key)?
+                        .reverse()
+                        .take_while(move |((id, m, f, _), _)| {
+                            *id == package && *m == module && *f == function
+                        })
+                        .map(|((_, _, _, seq), digest)| Ok((seq, digest))),
+                )
+            } else {
+                let key = (
+                    package,
+                    module.clone(),
+                    function.clone(),
+                    cursor.unwrap_or(TxSequenceNumber::MIN),
+                );
+                Box::new(
+                    iter.skip_to(&key)?
+                        .take_while(move |((id, m, f, _), _)| {
+                            *id == package && *m == module && *f == function
+                        })
+                        .map(|((_, _, _, seq), digest)| Ok((seq, digest))),
+                )
+            };
+            iters.push(iter);
+        }
+        Ok(iters
+            .into_iter()
+            .reduce(|left, right| merge_seq_iters(left, right, reverse))
+            .unwrap_or_else(|| Box::new(std::iter::empty())))
+    }
+
+    /// Distinct (module, function) pairs of `package` present in the move function index,
+    /// found by seeking past the entries of each function in turn.
+    fn move_functions_called(
+        &self,
+        package: ObjectID,
+        module: &Option<String>,
+        function: &Option<String>,
+    ) -> SuiResult<Vec<(String, String)>> {
+        let mut functions = vec![];
+        let mut next = (
+            package,
+            module.clone().unwrap_or_default(),
+            String::new(),
+            TxSequenceNumber::MIN,
+        );
+        loop {
+            let Some(((id, m, f, _), _)) = self
+                .tables
+                .transactions_by_move_function
+                .iter()
+                .skip_to(&next)?
+                .next() else {
+                break;
+            };
+            if id != package || module.as_ref().map(|x| *x != m).unwrap_or(false) {
+                break;
+            }
+            next = (package, m.clone(), f.clone(), TxSequenceNumber::MAX);
+            if function.as_ref().map(|x| *x == f).unwrap_or(true) {
+                functions.push((m, f));
+            }
+        }
+        Ok(functions)
+    }
+
     pub fn get_transactions_by_input_object(
         &self,
         input_object: ObjectID,
@@ -777,6 +1270,128 @@ impl IndexStore {
         )
     }
 
+    /// Return the events emitted by the transactions found under `key` in a transaction `index`,
+    /// starting from the event at (tx_seq, event_seq).
+    fn get_events_from_transaction_index<KeyT: Clone + PartialEq + Serialize + DeserializeOwned>(
+        &self,
+        index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: &KeyT,
+        tx_seq: TxSequenceNumber,
+        event_seq: usize,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        let mut events = vec![];
+        if descending {
+            let transactions = index
+                .iter()
+                .skip_prior_to(&(key.clone(), tx_seq))?
+                .reverse()
+                .take_while(|((k, _), _)| k == key);
+            for ((_, seq), _) in transactions {
+                let start = if seq == tx_seq { event_seq } else { usize::MAX };
+                events.extend(
+                    self.tables
+                        .event_order
+                        .iter()
+                        .skip_prior_to(&(seq, start))?
+                        .reverse()
+                        .take_while(|((tx, _), _)| tx == &seq)
+                        .take(limit - events.len())
+                        .map(|((_, event_seq), (digest, tx_digest, time))| {
+                            (digest, tx_digest, event_seq, time)
+                        }),
+                );
+                if events.len() >= limit {
+                    break;
+                }
+            }
+        } else {
+            let transactions = index
+                .iter()
+                .skip_to(&(key.clone(), tx_seq))?
+                .take_while(|((k, _), _)| k == key);
+            for ((_, seq), _) in transactions {
+                let start = if seq == tx_seq { event_seq } else { 0 };
+                events.extend(
+                    self.tables
+                        .event_order
+                        .iter()
+                        .skip_to(&(seq, start))?
+                        .take_while(|((tx, _), _)| tx == &seq)
+                        .take(limit - events.len())
+                        .map(|((_, event_seq), (digest, tx_digest, time))| {
+                            (digest, tx_digest, event_seq, time)
+                        }),
+                );
+                if events.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    /// Events emitted by transactions which created, mutated or unwrapped an object owned by
+    /// `recipient`.
+    pub fn events_by_recipient(
+        &self,
+        recipient: &SuiAddress,
+        tx_seq: TxSequenceNumber,
+        event_seq: usize,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.get_events_from_transaction_index(
+            &self.tables.transactions_to_addr,
+            recipient,
+            tx_seq,
+            event_seq,
+            limit,
+            descending,
+        )
+    }
+
+    /// Events emitted by transactions which created, mutated or unwrapped `object`.
+    pub fn events_by_affected_object(
+        &self,
+        object: &ObjectID,
+        tx_seq: TxSequenceNumber,
+        event_seq: usize,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.get_events_from_transaction_index(
+            &self.tables.transactions_by_mutated_object_id,
+            object,
+            tx_seq,
+            event_seq,
+            limit,
+            descending,
+        )
+    }
+
+    /// Iterate over all events in index order starting from the event at (tx_seq, event_seq),
+    /// for queries which cannot be served by a dedicated index.
+    pub fn all_events_iterator(
+        &self,
+        tx_seq: TxSequenceNumber,
+        event_seq: usize,
+        descending: bool,
+    ) -> SuiResult<
+        Box<dyn Iterator<Item = (TransactionEventsDigest, TransactionDigest, usize, u64)> + '_>,
+    > {
+        let iter = self.tables.event_order.iter();
+        let iter: Box<dyn Iterator<Item = _> + '_> = if descending {
+            Box::new(iter.skip_prior_to(&(tx_seq, event_seq))?.reverse())
+        } else {
+            Box::new(iter.skip_to(&(tx_seq, event_seq))?)
+        };
+        Ok(Box::new(iter.map(
+            |((_, event_seq), (digest, tx_digest, time))| (digest, tx_digest, event_seq, time),
+        )))
+    }
+
     pub fn event_iterator(
         &self,
         start_time: u64,
@@ -950,3 +1565,108 @@ impl IndexStore {
         self.tables.owner_index.is_empty()
     }
 }
+
+fn seq_order(left: TxSequenceNumber, right: TxSequenceNumber, reverse: bool) -> CmpOrdering {
+    if reverse {
+        right.cmp(&left)
+    } else {
+        left.cmp(&right)
+    }
+}
+
+/// The sequence number of the next transaction of `iter`, or `Err` if its next item is an error,
+/// which the iterators combining it yield as is.
+fn peek_seq(iter: &mut Peekable<TxSeqIter<'_>>) -> Option<Result<TxSequenceNumber, ()>> {
+    iter.peek()
+        .map(|item| item.as_ref().map(|(seq, _)| *seq).map_err(|_| ()))
+}
+
+/// Yields the transactions present in both sequence-ordered iterators.
+fn intersect_seq_iters<'a>(
+    left: TxSeqIter<'a>,
+    right: TxSeqIter<'a>,
+    reverse: bool,
+) -> TxSeqIter<'a> {
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    Box::new(std::iter::from_fn(move || loop {
+        let l = match peek_seq(&mut left)? {
+            Ok(l) => l,
+            Err(()) => return left.next(),
+        };
+        let r = match peek_seq(&mut right)? {
+            Ok(r) => r,
+            Err(()) => return right.next(),
+        };
+        match seq_order(l, r, reverse) {
+            CmpOrdering::Less => {
+                left.next();
+            }
+            CmpOrdering::Greater => {
+                right.next();
+            }
+            CmpOrdering::Equal => {
+                right.next();
+                return left.next();
+            }
+        }
+    }))
+}
+
+/// Yields the transactions present in either sequence-ordered iterator, without duplicates.
+fn merge_seq_iters<'a>(left: TxSeqIter<'a>, right: TxSeqIter<'a>, reverse: bool) -> TxSeqIter<'a> {
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    Box::new(std::iter::from_fn(move || {
+        match (peek_seq(&mut left), peek_seq(&mut right)) {
+            (Some(Err(())), _) => left.next(),
+            (_, Some(Err(()))) => right.next(),
+            (Some(Ok(l)), Some(Ok(r))) => match seq_order(l, r, reverse) {
+                CmpOrdering::Less => left.next(),
+                CmpOrdering::Greater => right.next(),
+                CmpOrdering::Equal => {
+                    right.next();
+                    left.next()
+                }
+            },
+            (Some(_), None) => left.next(),
+            (None, _) => right.next(),
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_filters_before_first_indexed_checkpoint() -> Result<(), anyhow::Error> {
+        let dir = tempfile::tempdir()?;
+        let store = IndexStore::new(dir.path().to_path_buf());
+        // The node was upgraded, or restored from a snapshot, after checkpoint 4.
+        store.index_checkpoint(5, 1_000, &[], None)?;
+        store.index_checkpoint(6, 2_000, &[], None)?;
+        let query = |filter: TransactionFilter| {
+            store.get_transactions(Some(filter), None, None, None, false)
+        };
+
+        assert!(query(TransactionFilter::Checkpoint { start: 5, end: 7 })?.is_empty());
+        assert!(query(TransactionFilter::Checkpoint { start: 4, end: 7 }).is_err());
+        assert!(query(TransactionFilter::TimeRange {
+            start_ms: 1_000,
+            end_ms: 3_000
+        })?
+        .is_empty());
+        assert!(query(TransactionFilter::TimeRange {
+            start_ms: 999,
+            end_ms: 3_000
+        })
+        .is_err());
+        assert!(query(
+            TransactionFilter::FromAddress(SuiAddress::ZERO)
+                .and(TransactionFilter::Checkpoint { start: 0, end: 7 })
+        )
+        .is_err());
+        Ok(())
+    }
+}
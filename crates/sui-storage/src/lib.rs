@@ -4,6 +4,7 @@
 pub mod indexes;
 pub use indexes::{IndexStore, IndexStoreTables};
 
+pub mod archive;
 pub mod mutex_table;
 pub mod object_store;
 pub mod write_path_pending_tx_log;
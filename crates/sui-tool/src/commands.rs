@@ -3,13 +3,18 @@
 
 use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
-    get_object, get_transaction, make_clients, restore_from_db_checkpoint, ConciseObjectOutput,
-    GroupedObjectOutput, VerboseObjectOutput,
+    get_object, get_transaction, make_clients,
+    replay::replay_transaction,
+    replay_archive::replay_archive,
+    restore_from_db_checkpoint, ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
 };
 use anyhow::Result;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use sui_config::genesis::Genesis;
 use sui_core::authority_client::AuthorityAPI;
+use sui_protocol_config::ProtocolVersion;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 
 use sui_types::{base_types::*, object::Owner};
 
@@ -139,6 +144,43 @@ pub enum ToolCommand {
         #[clap(long = "db-checkpoint-path")]
         db_checkpoint_path: PathBuf,
     },
+
+    /// Rebuild a node database from genesis by re-executing the checkpoints in a checkpoint
+    /// archive, verifying effects and root state hashes along the way.
+    #[clap(name = "replay-archive")]
+    ReplayArchive {
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+        /// Path of the new database to create
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        /// Local directory containing the checkpoint archive
+        #[clap(long = "archive-path", required_unless_present = "archive_config_path")]
+        archive_path: Option<PathBuf>,
+        /// YAML object store config of a remote checkpoint archive
+        #[clap(long = "archive-config-path", conflicts_with = "archive_path")]
+        archive_config_path: Option<PathBuf>,
+        /// Stop after executing this checkpoint instead of at the end of the archive
+        #[clap(long = "end-checkpoint")]
+        end_checkpoint: Option<CheckpointSequenceNumber>,
+        /// Number of archive files to download in parallel
+        #[clap(long = "download-concurrency", default_value = "5")]
+        download_concurrency: NonZeroUsize,
+    },
+
+    /// Re-execute a transaction locally against the object versions it used on chain, fetched
+    /// from a fullnode, and report any difference with its on chain effects.
+    #[clap(name = "replay")]
+    Replay {
+        #[clap(long = "rpc-url", default_value = "http://127.0.0.1:9000")]
+        rpc_url: String,
+        /// Digest of the transaction to replay
+        #[clap(long = "tx")]
+        tx_digest: TransactionDigest,
+        /// Execute under this protocol version instead of the one of the transaction's epoch
+        #[clap(long = "protocol-version")]
+        protocol_version: Option<u64>,
+    },
 }
 
 trait OptionDebug<T> {
@@ -290,6 +332,75 @@ impl ToolCommand {
                 let config = sui_config::NodeConfig::load(config_path)?;
                 restore_from_db_checkpoint(&config, &db_checkpoint_path).await?;
             }
+            ToolCommand::ReplayArchive {
+                genesis,
+                db_path,
+                archive_path,
+                archive_config_path,
+                end_checkpoint,
+                download_concurrency,
+            } => {
+                let genesis = Genesis::load(genesis)?;
+                let archive_store_config = match archive_config_path {
+                    Some(path) => serde_yaml::from_reader(std::fs::File::open(path)?)?,
+                    None => ObjectStoreConfig {
+                        object_store: Some(ObjectStoreType::File),
+                        directory: archive_path,
+                        ..Default::default()
+                    },
+                };
+                let result = replay_archive(
+                    &genesis,
+                    &archive_store_config,
+                    &db_path,
+                    end_checkpoint,
+                    download_concurrency,
+                )
+                .await?;
+                if result.differences.is_empty() {
+                    println!(
+                        "Replayed checkpoints up to {} matching the archive",
+                        result.last_checkpoint
+                    );
+                } else {
+                    println!(
+                        "Replay of checkpoints up to {} diverges from the archive:",
+                        result.last_checkpoint
+                    );
+                    for difference in result.differences {
+                        println!("  {difference}");
+                    }
+                }
+            }
+            ToolCommand::Replay {
+                rpc_url,
+                tx_digest,
+                protocol_version,
+            } => {
+                let result = replay_transaction(
+                    &rpc_url,
+                    tx_digest,
+                    protocol_version.map(ProtocolVersion::new),
+                )
+                .await?;
+                let differences = result.diff()?;
+                if differences.is_empty() {
+                    println!(
+                        "Replay of {} under protocol version {} matches on chain effects",
+                        result.digest,
+                        result.protocol_version.as_u64()
+                    );
+                } else {
+                    println!(
+                        "Replay of {} under protocol version {} diverges from on chain effects:",
+                        result.digest,
+                        result.protocol_version.as_u64()
+                    );
+                    for difference in differences {
+                        println!("  {difference}");
+                    }
+                }
+            }
         };
         Ok(())
     }
@@ -0,0 +1,422 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-executes a single transaction locally against the exact object versions it was executed
+//! with on chain, fetched from a fullnode, and compares the resulting effects with the effects
+//! recorded on chain.
+
+use anyhow::{anyhow, Result};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use sui_adapter::{adapter, execution_engine, execution_mode};
+use sui_core::transaction_input_checker::check_objects;
+use sui_json_rpc_types::{
+    Checkpoint, SuiGetPastObjectRequest, SuiObjectDataOptions, SuiTransactionEffects,
+    SuiTransactionEffectsAPI, SuiTransactionResponseOptions,
+};
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::committee::EpochId;
+use sui_types::epoch_data::EpochData;
+use sui_types::gas::{start_gas_metering, SuiCostTable, SuiGasStatus};
+use sui_types::in_memory_storage::InMemoryStorage;
+use sui_types::messages::{InputObjectKind, SenderSignedData, TransactionDataAPI};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::{Data, Object};
+use sui_types::temporary_store::TemporaryStore;
+use sui_types::{
+    is_system_package, MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_PACKAGE_ID,
+};
+use tracing::info;
+
+/// Outcome of re-executing a transaction locally.
+pub struct ReplayResult {
+    pub digest: TransactionDigest,
+    pub protocol_version: ProtocolVersion,
+    pub local_effects: SuiTransactionEffects,
+    pub on_chain_effects: SuiTransactionEffects,
+}
+
+impl ReplayResult {
+    /// Returns one line per field that differs between the local and the on chain effects,
+    /// or nothing if the replay reproduced the on chain effects exactly.
+    pub fn diff(&self) -> Result<Vec<String>> {
+        let local = serde_json::to_value(&self.local_effects)?;
+        let on_chain = serde_json::to_value(&self.on_chain_effects)?;
+        let mut differences = vec![];
+        diff_values("effects", &on_chain, &local, &mut differences);
+        Ok(differences)
+    }
+}
+
+/// Fetches transaction `digest` together with every object it read or modified, at the versions
+/// it saw them, from the fullnode at `rpc_url`, and executes it again under the protocol config
+/// of the epoch it was executed in.
+///
+/// Only objects that appear in the transaction inputs or in the modified versions of its effects
+/// are fetched, so a transaction which read a dynamic field without modifying it cannot be
+/// replayed. `protocol_version` overrides the protocol version of the transaction's epoch, which
+/// is required for transactions from epoch 0 once the network has moved past it.
+pub async fn replay_transaction(
+    rpc_url: &str,
+    digest: TransactionDigest,
+    protocol_version: Option<ProtocolVersion>,
+) -> Result<ReplayResult> {
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let response = client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionResponseOptions::new()
+                .with_raw_input()
+                .with_effects(),
+        )
+        .await?;
+    let on_chain_effects = response
+        .effects
+        .ok_or_else(|| anyhow!("Fullnode did not return effects for {digest}"))?;
+    let checkpoint = response
+        .checkpoint
+        .ok_or_else(|| anyhow!("Transaction {digest} has not been checkpointed yet"))?;
+    let sender_signed_data: SenderSignedData = bcs::from_bytes(&response.raw_transaction)?;
+    let tx_data = sender_signed_data.transaction_data();
+
+    let epoch = on_chain_effects.executed_epoch();
+    let (epoch_data, epoch_protocol_version) = epoch_data(&client, epoch, checkpoint).await?;
+    let protocol_version = match protocol_version.or(epoch_protocol_version) {
+        Some(version) => version,
+        None => {
+            return Err(anyhow!(
+                "Cannot determine the protocol version of epoch {epoch}, use --protocol-version"
+            ))
+        }
+    };
+    if protocol_version > ProtocolVersion::MAX {
+        return Err(anyhow!(
+            "Protocol version {} is newer than the latest version supported by this binary ({})",
+            protocol_version.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        ));
+    }
+    let protocol_config = ProtocolConfig::get_for_version(protocol_version);
+    info!(
+        ?digest,
+        epoch,
+        protocol_version = protocol_version.as_u64(),
+        "Replaying transaction"
+    );
+
+    // Shared objects are listed in the inputs without a version, the version they were read at
+    // is only recorded in the effects.
+    let shared_versions: BTreeMap<_, _> = on_chain_effects
+        .shared_objects()
+        .iter()
+        .map(|o| (o.object_id, o.version))
+        .collect();
+    let input_object_kinds = tx_data.input_objects()?;
+    let mut input_requests = vec![];
+    let mut packages = BTreeSet::from([
+        MOVE_STDLIB_OBJECT_ID,
+        SUI_FRAMEWORK_OBJECT_ID,
+        SUI_SYSTEM_PACKAGE_ID,
+    ]);
+    for kind in &input_object_kinds {
+        match kind {
+            InputObjectKind::MovePackage(id) => {
+                packages.insert(*id);
+            }
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                input_requests.push(past_object_request(*id, *version));
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => {
+                let version = shared_versions.get(id).ok_or_else(|| {
+                    anyhow!("Effects of {digest} do not record the version of shared object {id}")
+                })?;
+                input_requests.push(past_object_request(*id, *version));
+            }
+        }
+    }
+    let input_ids: BTreeSet<_> = input_requests.iter().map(|r| r.object_id).collect();
+    // Objects modified but not listed in the inputs are dynamic fields loaded during execution.
+    let child_requests = on_chain_effects
+        .modified_at_versions()
+        .into_iter()
+        .filter(|(id, _)| !input_ids.contains(id))
+        .map(|(id, version)| past_object_request(id, version))
+        .collect();
+
+    let input_objects = fetch_past_objects(&client, input_requests).await?;
+    let child_objects = fetch_past_objects(&client, child_requests).await?;
+    for object in input_objects.iter().chain(&child_objects) {
+        if let Some(tag) = object.struct_tag() {
+            add_struct_tag_packages(&mut packages, &tag);
+        }
+    }
+    let package_objects = fetch_packages(&client, packages, epoch).await?;
+
+    let mut store = InMemoryStorage::new(package_objects);
+    for object in child_objects {
+        store.insert_object(object);
+    }
+
+    // Inputs must be handed to the temporary store in the same order as their kinds.
+    let mut objects_by_id: BTreeMap<_, _> =
+        input_objects.into_iter().map(|o| (o.id(), o)).collect();
+    let objects = input_object_kinds
+        .iter()
+        .map(|kind| {
+            let id = kind.object_id();
+            let object = match kind {
+                InputObjectKind::MovePackage(_) => store.get_object(&id).cloned(),
+                _ => objects_by_id.remove(&id),
+            };
+            object.ok_or_else(|| anyhow!("Missing input object {id}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let input_objects = check_objects(tx_data, input_object_kinds, objects)?;
+
+    let gas_status = if tx_data.kind().is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        start_gas_metering(
+            tx_data.gas_budget(),
+            tx_data.gas_price(),
+            protocol_config.storage_gas_price(),
+            SuiCostTable::new(&protocol_config),
+        )?
+    };
+    let shared_object_refs = input_objects.filter_shared_objects();
+    let transaction_dependencies = input_objects.transaction_dependencies();
+    let temporary_store = TemporaryStore::new(&store, input_objects, digest, &protocol_config);
+    let move_vm = Arc::new(
+        adapter::new_move_vm(sui_framework::natives::all_natives(), &protocol_config)
+            .expect("We defined natives to not fail here"),
+    );
+    let (kind, signer, gas) = tx_data.execution_parts();
+    let (_inner_temp_store, local_effects, execution_error) =
+        execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+            shared_object_refs,
+            temporary_store,
+            kind,
+            signer,
+            &gas,
+            digest,
+            transaction_dependencies,
+            &move_vm,
+            gas_status,
+            &epoch_data,
+            &protocol_config,
+        );
+    if let Err(error) = execution_error {
+        info!(?digest, "Replayed transaction failed: {error:?}");
+    }
+
+    Ok(ReplayResult {
+        digest,
+        protocol_version,
+        local_effects: local_effects.try_into()?,
+        on_chain_effects,
+    })
+}
+
+/// Returns the epoch data of `epoch` and, if known, the protocol version it ran at. Both are taken
+/// from the last checkpoint of the previous epoch, which is found by searching the checkpoints
+/// before `checkpoint`, a checkpoint of `epoch`.
+async fn epoch_data(
+    client: &SuiClient,
+    epoch: EpochId,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<(EpochData, Option<ProtocolVersion>)> {
+    if epoch == 0 {
+        let genesis = get_checkpoint(client, 0).await?;
+        let system_state = client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let protocol_version =
+            (system_state.epoch == 0).then(|| ProtocolVersion::new(system_state.protocol_version));
+        return Ok((
+            EpochData::new_genesis(genesis.timestamp_ms),
+            protocol_version,
+        ));
+    }
+
+    // Checkpoint `low` always belongs to an earlier epoch and checkpoint `high` to `epoch`.
+    let mut low = 0;
+    let mut high = checkpoint;
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if get_checkpoint(client, mid).await?.epoch < epoch {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    let last_checkpoint = get_checkpoint(client, low).await?;
+    let end_of_epoch_data = last_checkpoint.end_of_epoch_data.ok_or_else(|| {
+        anyhow!(
+            "Checkpoint {} is expected to be the last checkpoint of epoch {}",
+            low,
+            epoch - 1
+        )
+    })?;
+    Ok((
+        EpochData::new(epoch, last_checkpoint.timestamp_ms, last_checkpoint.digest),
+        Some(end_of_epoch_data.next_epoch_protocol_version),
+    ))
+}
+
+async fn get_checkpoint(
+    client: &SuiClient,
+    sequence_number: CheckpointSequenceNumber,
+) -> Result<Checkpoint> {
+    Ok(client
+        .read_api()
+        .get_checkpoint(sequence_number.into())
+        .await?)
+}
+
+fn past_object_request(object_id: ObjectID, version: SequenceNumber) -> SuiGetPastObjectRequest {
+    SuiGetPastObjectRequest { object_id, version }
+}
+
+async fn fetch_past_objects(
+    client: &SuiClient,
+    requests: Vec<SuiGetPastObjectRequest>,
+) -> Result<Vec<Object>> {
+    if requests.is_empty() {
+        return Ok(vec![]);
+    }
+    client
+        .read_api()
+        .try_multi_get_parsed_past_object(requests, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_iter()
+        .map(|response| response.into_object()?.try_into())
+        .collect()
+}
+
+/// Fetches `packages` and, transitively, every package they link against, with the system
+/// packages at the versions they had in `epoch`.
+async fn fetch_packages(
+    client: &SuiClient,
+    packages: BTreeSet<ObjectID>,
+    epoch: EpochId,
+) -> Result<Vec<Object>> {
+    let mut fetched = BTreeMap::new();
+    let mut pending: Vec<_> = packages.into_iter().collect();
+    while let Some(id) = pending.pop() {
+        if fetched.contains_key(&id) {
+            continue;
+        }
+        let object = if is_system_package(id) {
+            fetch_system_package(client, id, epoch).await?
+        } else {
+            fetch_latest_package(client, id).await?
+        };
+        if let Data::Package(package) = &object.data {
+            pending.extend(
+                package
+                    .linkage_table()
+                    .values()
+                    .map(|upgrade_info| upgrade_info.upgraded_id),
+            );
+        } else {
+            return Err(anyhow!("Object {id} is not a package"));
+        }
+        fetched.insert(id, object);
+    }
+    Ok(fetched.into_values().collect())
+}
+
+async fn fetch_latest_package(client: &SuiClient, id: ObjectID) -> Result<Object> {
+    client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_object()
+        .map_err(|e| anyhow!("Failed to fetch package {id}: {e:?}"))?
+        .try_into()
+}
+
+/// Fetches the version of system package `id` in effect in `epoch`. System packages are upgraded
+/// in place by the transaction ending an epoch, so this walks back from the latest version to the
+/// one written before `epoch`, or at genesis.
+async fn fetch_system_package(client: &SuiClient, id: ObjectID, epoch: EpochId) -> Result<Object> {
+    let mut package = fetch_latest_package(client, id).await?;
+    loop {
+        let writer = package.previous_transaction;
+        let effects = client
+            .read_api()
+            .get_transaction_with_options(
+                writer,
+                SuiTransactionResponseOptions::new().with_effects(),
+            )
+            .await?
+            .effects
+            .ok_or_else(|| anyhow!("Fullnode did not return effects for {writer}"))?;
+        if effects.executed_epoch() < epoch {
+            return Ok(package);
+        }
+        let previous_version = effects
+            .modified_at_versions()
+            .into_iter()
+            .find_map(|(modified, version)| (modified == id).then_some(version));
+        match previous_version {
+            Some(version) => {
+                package = fetch_past_objects(client, vec![past_object_request(id, version)])
+                    .await?
+                    .pop()
+                    .ok_or_else(|| anyhow!("Failed to fetch package {id} at version {version}"))?;
+            }
+            // The package was created by `writer`, at genesis.
+            None => return Ok(package),
+        }
+    }
+}
+
+fn add_struct_tag_packages(packages: &mut BTreeSet<ObjectID>, tag: &StructTag) {
+    packages.insert(tag.address.into());
+    let mut stack: Vec<_> = tag.type_params.iter().collect();
+    while let Some(type_param) = stack.pop() {
+        match type_param {
+            TypeTag::Vector(inner) => stack.push(inner),
+            TypeTag::Struct(tag) => {
+                packages.insert(tag.address.into());
+                stack.extend(tag.type_params.iter())
+            }
+            _ => (),
+        }
+    }
+}
+
+fn diff_values(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let keys: BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+            for key in keys {
+                let path = format!("{path}.{key}");
+                match (expected.get(key), actual.get(key)) {
+                    (Some(e), Some(a)) => diff_values(&path, e, a, differences),
+                    (e, a) => differences.push(format!(
+                        "{path}: on chain {}, replayed {}",
+                        e.unwrap_or(&Value::Null),
+                        a.unwrap_or(&Value::Null)
+                    )),
+                }
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (e, a)) in e.iter().zip(a).enumerate() {
+                diff_values(&format!("{path}[{i}]"), e, a, differences);
+            }
+        }
+        (e, a) if e != a => {
+            differences.push(format!("{path}: on chain {e}, replayed {a}"));
+        }
+        _ => (),
+    }
+}
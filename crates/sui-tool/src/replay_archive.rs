@@ -0,0 +1,319 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rebuilds a node database from genesis by re-executing every checkpoint found in a checkpoint
+//! archive, so that a trusted node can be restored without trusting a database snapshot.
+
+use anyhow::{anyhow, Result};
+use prometheus::Registry;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::genesis::Genesis;
+use sui_config::node::{AuthorityStorePruningConfig, CheckpointExecutorConfig, DBCheckpointConfig};
+use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use sui_core::authority::epoch_start_configuration::EpochStartConfiguration;
+use sui_core::authority::{AuthorityState, AuthorityStore};
+use sui_core::checkpoints::checkpoint_executor::CheckpointExecutor;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::epoch::epoch_metrics::EpochMetrics;
+use sui_core::module_cache_metrics::ResolverMetrics;
+use sui_core::signature_verifier::VerifiedDigestCacheMetrics;
+use sui_core::state_accumulator::StateAccumulator;
+use sui_core::storage::RocksDbStore;
+use sui_protocol_config::SupportedProtocolVersions;
+use sui_storage::archive::ArchiveReader;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::crypto::{get_key_pair, AuthorityKeyPair, KeypairTraits};
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{CheckpointCommitment, CheckpointSequenceNumber};
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use tokio::sync::broadcast;
+use tracing::info;
+
+/// Outcome of replaying a checkpoint archive.
+pub struct ArchiveReplayResult {
+    /// The last checkpoint which was executed.
+    pub last_checkpoint: CheckpointSequenceNumber,
+    /// One line per effects digest or root state hash which differs from the one committed to by
+    /// the archive, empty if the replay reproduced the archived chain exactly.
+    pub differences: Vec<String>,
+}
+
+/// Re-executes the checkpoints of the archive described by `archive_store_config` into a new
+/// database at `db_path`, up to `end_checkpoint` or the end of the archive.
+///
+/// Checkpoints are verified against the committee of their epoch before being executed. The
+/// effects of every executed transaction are compared with the effects digests committed to by
+/// its checkpoint, and at every epoch boundary the accumulated root state hash is compared with
+/// the live object set as well as any commitment in the end of epoch checkpoint.
+pub async fn replay_archive(
+    genesis: &Genesis,
+    archive_store_config: &ObjectStoreConfig,
+    db_path: &Path,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    download_concurrency: NonZeroUsize,
+) -> Result<ArchiveReplayResult> {
+    if db_path.exists() && db_path.read_dir()?.next().is_some() {
+        return Err(anyhow!(
+            "Database path {} already exists and is not empty",
+            db_path.display()
+        ));
+    }
+
+    let reader = ArchiveReader::new(archive_store_config, download_concurrency)?;
+    let archive_end = reader
+        .latest_available_checkpoint()
+        .await?
+        .ok_or_else(|| anyhow!("Checkpoint archive is empty"))?;
+    let end = end_checkpoint.map_or(archive_end, |end| end.min(archive_end));
+
+    let registry = Registry::new();
+    let genesis_committee = genesis.committee()?;
+    let committee_store = Arc::new(CommitteeStore::new(
+        db_path.join("epochs"),
+        &genesis_committee,
+        None,
+    ));
+    let store = Arc::new(
+        AuthorityStore::open(
+            &db_path.join("store"),
+            None,
+            genesis,
+            &committee_store,
+            usize::MAX,
+        )
+        .await?,
+    );
+    let epoch_start_configuration = store
+        .get_epoch_start_configuration()?
+        .expect("EpochStartConfiguration of the genesis epoch must exist");
+
+    // The replayed node never signs anything, so any key will do.
+    let (_, keypair): (_, AuthorityKeyPair) = get_key_pair();
+    let name = keypair.public().into();
+    let mut epoch_store = AuthorityPerEpochStore::new(
+        name,
+        Arc::new(genesis_committee),
+        &db_path.join("store"),
+        None,
+        EpochMetrics::new(&registry),
+        epoch_start_configuration,
+        store.clone(),
+        Arc::new(ResolverMetrics::new(&registry)),
+        VerifiedDigestCacheMetrics::new(&registry),
+    );
+
+    let checkpoint_store = CheckpointStore::new(&db_path.join("checkpoints"));
+    checkpoint_store.insert_genesis_checkpoint(
+        genesis.checkpoint(),
+        genesis.checkpoint_contents().clone(),
+        &epoch_store,
+    );
+
+    let state = AuthorityState::new(
+        name,
+        Arc::pin(keypair),
+        SupportedProtocolVersions::SYSTEM_DEFAULT,
+        store.clone(),
+        epoch_store.clone(),
+        committee_store.clone(),
+        None,
+        checkpoint_store.clone(),
+        &registry,
+        AuthorityStorePruningConfig::fullnode_config(),
+        genesis.objects(),
+        &DBCheckpointConfig::default(),
+    )
+    .await;
+    let genesis_transaction = sui_types::messages::VerifiedExecutableTransaction::new_unchecked(
+        sui_types::messages::ExecutableTransaction::new_from_data_and_sig(
+            genesis.transaction().data().clone(),
+            sui_types::certificate_proof::CertificateProof::Checkpoint(0, 0),
+        ),
+    );
+    state
+        .try_execute_immediately(&genesis_transaction, &epoch_store)
+        .await?;
+
+    // Download and verify the checkpoints first, the executor then picks them up from the
+    // checkpoint store as if they had been synced from peers.
+    info!("Syncing checkpoints up to {end} from archive");
+    let sync_store = RocksDbStore::new(
+        store.clone(),
+        committee_store.clone(),
+        checkpoint_store.clone(),
+    );
+    reader.sync_to_store(&sync_store, Some(end)).await?;
+
+    let accumulator = Arc::new(StateAccumulator::new(store.clone()));
+    // Nothing is ever sent on the mailbox, but it must stay open while the executor runs.
+    let (_mailbox, mailbox_receiver) = broadcast::channel(1);
+    let mut executor = CheckpointExecutor::new(
+        mailbox_receiver,
+        checkpoint_store.clone(),
+        store.clone(),
+        state.transaction_manager().clone(),
+        accumulator.clone(),
+        None,
+        CheckpointExecutorConfig::default(),
+        &registry,
+    );
+
+    // A replay ending on the last checkpoint of an epoch waits for the epoch to be accumulated,
+    // so that its root state hash is verified as well.
+    let end_is_last_of_epoch = checkpoint_store
+        .get_checkpoint_by_sequence_number(end)?
+        .ok_or_else(|| anyhow!("Missing checkpoint {end}"))?
+        .next_epoch_committee()
+        .is_some();
+    let mut next_to_verify = 1;
+    let mut differences = vec![];
+    loop {
+        let epoch = epoch_store.epoch();
+        info!(epoch, "Replaying epoch");
+        let run_epoch = executor.run_epoch(epoch_store.clone());
+        tokio::pin!(run_epoch);
+        tokio::select! {
+            _ = &mut run_epoch => {},
+            result = wait_for_checkpoint(
+                &checkpoint_store,
+                &store,
+                &mut next_to_verify,
+                end,
+                &mut differences,
+            ) => {
+                result?;
+                if !end_is_last_of_epoch {
+                    break;
+                }
+                run_epoch.await;
+            }
+        }
+        verify_executed_checkpoints(
+            &checkpoint_store,
+            &store,
+            &mut next_to_verify,
+            &mut differences,
+        )?;
+
+        // run_epoch only returns once the last checkpoint of the epoch has been executed and
+        // the epoch has been accumulated.
+        let last_checkpoint = checkpoint_store
+            .get_epoch_last_checkpoint(epoch)?
+            .ok_or_else(|| anyhow!("Missing last checkpoint of epoch {epoch}"))?;
+        let root_state_hash = store.get_root_state_hash(epoch)?;
+        let live_object_set_hash = accumulator.digest_live_object_set();
+        if root_state_hash != live_object_set_hash {
+            differences.push(format!(
+                "Inconsistent state at the end of epoch {epoch}: root state hash {:?}, \
+                live object set hash {:?}",
+                root_state_hash, live_object_set_hash
+            ));
+        }
+        let end_of_epoch_data = last_checkpoint
+            .end_of_epoch_data
+            .as_ref()
+            .ok_or_else(|| anyhow!("Last checkpoint of epoch {epoch} has no end of epoch data"))?;
+        for commitment in &end_of_epoch_data.epoch_commitments {
+            let CheckpointCommitment::ECMHLiveObjectSetDigest(expected) = commitment;
+            if *expected != root_state_hash {
+                differences.push(format!(
+                    "Root state hash of epoch {epoch} is {:?}, but checkpoint {} commits to {:?}",
+                    root_state_hash,
+                    last_checkpoint.sequence_number(),
+                    expected
+                ));
+            }
+        }
+        info!(epoch, ?root_state_hash, "Compared root state hash");
+        if *last_checkpoint.sequence_number() >= end {
+            break;
+        }
+
+        let epoch_start_state = state
+            .get_sui_system_state_object_during_reconfig()?
+            .into_epoch_start_state();
+        let next_epoch_committee = epoch_start_state.get_sui_committee();
+        let epoch_start_configuration =
+            EpochStartConfiguration::new_v1(epoch_start_state, *last_checkpoint.digest());
+        epoch_store = state
+            .reconfigure(
+                &epoch_store,
+                SupportedProtocolVersions::SYSTEM_DEFAULT,
+                next_epoch_committee,
+                epoch_start_configuration,
+            )
+            .await?;
+    }
+
+    info!("Replayed checkpoints up to {end}");
+    Ok(ArchiveReplayResult {
+        last_checkpoint: end,
+        differences,
+    })
+}
+
+/// Verifies executed checkpoints as they become available, until checkpoint `end` has been
+/// verified.
+async fn wait_for_checkpoint(
+    checkpoint_store: &CheckpointStore,
+    store: &AuthorityStore,
+    next_to_verify: &mut CheckpointSequenceNumber,
+    end: CheckpointSequenceNumber,
+    differences: &mut Vec<String>,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_millis(100));
+    loop {
+        verify_executed_checkpoints(checkpoint_store, store, next_to_verify, differences)?;
+        if *next_to_verify > end {
+            return Ok(());
+        }
+        interval.tick().await;
+    }
+}
+
+/// Compares the effects of every executed checkpoint from `next_to_verify` onwards with the
+/// effects digests in its contents, adding any mismatch to `differences`.
+fn verify_executed_checkpoints(
+    checkpoint_store: &CheckpointStore,
+    store: &AuthorityStore,
+    next_to_verify: &mut CheckpointSequenceNumber,
+    differences: &mut Vec<String>,
+) -> Result<()> {
+    let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint_seq_number()? else {
+        return Ok(());
+    };
+    while *next_to_verify <= highest_executed {
+        let checkpoint = checkpoint_store
+            .get_checkpoint_by_sequence_number(*next_to_verify)?
+            .ok_or_else(|| anyhow!("Missing checkpoint {next_to_verify}"))?;
+        let contents = checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or_else(|| anyhow!("Missing contents of checkpoint {next_to_verify}"))?;
+        let tx_digests: Vec<_> = contents.iter().map(|digests| digests.transaction).collect();
+        let effects = store.multi_get_executed_effects(&tx_digests)?;
+        for (digests, effects) in contents.iter().zip(effects) {
+            let effects = effects.ok_or_else(|| {
+                anyhow!(
+                    "Transaction {} of checkpoint {next_to_verify} was not executed",
+                    digests.transaction
+                )
+            })?;
+            if effects.digest() != digests.effects {
+                differences.push(format!(
+                    "Transaction {} of checkpoint {next_to_verify} has effects digest {}, \
+                    expected {}",
+                    digests.transaction,
+                    effects.digest(),
+                    digests.effects
+                ));
+            }
+        }
+        *next_to_verify += 1;
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroUsize;
+use std::time::Duration;
+use sui_config::node::CheckpointArchiveConfig;
+use sui_core::test_utils::wait_for_tx;
+use sui_json_rpc_types::SuiTransactionResponseOptions;
+use sui_sdk::SuiClientBuilder;
+use sui_storage::archive::ArchiveReader;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_tool::replay::replay_transaction;
+use sui_tool::replay_archive::replay_archive;
+use test_utils::network::{start_fullnode_from_config, TestClusterBuilder};
+use test_utils::transaction::transfer_coin;
+
+#[tokio::test]
+async fn test_replay_transaction() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let (_, _, _, digest, _, _) = transfer_coin(&mut test_cluster.wallet).await?;
+
+    // Only checkpointed transactions can be replayed.
+    let client = SuiClientBuilder::default()
+        .build(test_cluster.rpc_url())
+        .await?;
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionResponseOptions::new())
+            .await?
+            .checkpoint
+            .is_none()
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+    .await??;
+
+    let result = replay_transaction(test_cluster.rpc_url(), digest, None).await?;
+    assert_eq!(result.digest, digest);
+    assert_eq!(result.diff()?, Vec::<String>::new());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_archive() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let archive_dir = tempfile::tempdir()?;
+    let archive_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(archive_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut config = test_cluster.fullnode_config_builder().build()?;
+    config.checkpoint_archive_config = Some(CheckpointArchiveConfig {
+        object_store_config: archive_store_config.clone(),
+        checkpoints_per_bundle: 1,
+        upload_interval_s: 1,
+    });
+    let archiving_node = start_fullnode_from_config(config).await?.sui_node;
+
+    let (_, _, _, digest, _, _) = transfer_coin(&mut test_cluster.wallet).await?;
+    wait_for_tx(digest, archiving_node.state().clone()).await;
+
+    // Wait for the checkpoint of the transfer to be archived.
+    let reader = ArchiveReader::new(&archive_store_config, NonZeroUsize::new(1).unwrap())?;
+    let checkpoint = tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            if let Some((_, checkpoint)) = archiving_node
+                .state()
+                .get_transaction_checkpoint_sequence(&digest)?
+            {
+                // The manifest only exists once the first bundle is uploaded.
+                if matches!(
+                    reader.latest_available_checkpoint().await,
+                    Ok(Some(latest)) if latest >= checkpoint
+                ) {
+                    return Ok::<_, anyhow::Error>(checkpoint);
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await??;
+
+    let db_dir = tempfile::tempdir()?;
+    let result = replay_archive(
+        &test_cluster.swarm.config().genesis,
+        &archive_store_config,
+        &db_dir.path().join("db"),
+        Some(checkpoint),
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    assert_eq!(result.last_checkpoint, checkpoint);
+    assert_eq!(result.differences, Vec::<String>::new());
+    Ok(())
+}
@@ -27,7 +27,8 @@ use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, ProgrammableTransaction,
+    TransactionData, TransactionKind,
 };
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Object, Owner};
@@ -668,9 +669,44 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                 }
             };
         }
-        let pt = builder.finish();
-        let all_inputs = pt.input_objects()?;
-        let inputs = all_inputs
+        self.programmable_transaction(signer, builder.finish(), gas, gas_budget)
+            .await
+    }
+
+    /// Wraps an already built programmable transaction into transaction data paid for by
+    /// `signer`, selecting a gas object which is not among the transaction inputs if `gas` is
+    /// not provided.
+    pub async fn programmable_transaction(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        self.sponsored_transaction(
+            signer,
+            TransactionKind::programmable(pt),
+            signer,
+            gas,
+            gas_budget,
+        )
+        .await
+    }
+
+    /// Wraps a transaction kind sent by `sender` into transaction data whose gas is paid by
+    /// `sponsor`, selecting a gas object of the sponsor which is not among the transaction
+    /// inputs if `gas` is not provided. Unless the sponsor is the sender, the transaction must
+    /// be signed by both of them.
+    pub async fn sponsored_transaction(
+        &self,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        sponsor: SuiAddress,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let inputs = kind
+            .input_objects()?
             .iter()
             .flat_map(|obj| match obj {
                 InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
@@ -679,15 +715,16 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .collect();
         let gas_price = self.0.get_reference_gas_price().await?;
         let gas = self
-            .select_gas(signer, gas, gas_budget, inputs, gas_price)
+            .select_gas(sponsor, gas, gas_budget, inputs, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
-            TransactionKind::programmable(pt),
-            signer,
-            gas,
+        Ok(TransactionData::new_with_gas_coins_allow_sponsor(
+            kind,
+            sender,
+            vec![gas],
             gas_budget,
             gas_price,
+            sponsor,
         ))
     }
 
@@ -5,6 +5,7 @@
 use crate::error::{UserInputError, UserInputResult};
 use crate::messages::TransactionEffects;
 use crate::{
+    base_types::ObjectID,
     error::{ExecutionError, ExecutionErrorKind},
     gas_coin::GasCoin,
     messages::TransactionEffectsAPI,
@@ -24,6 +25,7 @@ use std::{
 };
 use sui_cost_tables::{
     bytecode_tables::{GasStatus, INITIAL_COST_SCHEDULE},
+    gas_profiler::GasProfiler,
     units_types::GasUnit,
 };
 use sui_protocol_config::*;
@@ -204,6 +206,72 @@ impl std::fmt::Display for GasCostSummary {
     }
 }
 
+/// Breakdown of the gas charged by a transaction, collected when the transaction is executed
+/// with gas profiling enabled. Computation is measured in internal gas units, a thousandth of a
+/// gas unit each.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    /// Computation charged to each programmable transaction command, including the Move functions
+    /// it called, in execution order.
+    pub commands: Vec<CommandGasProfile>,
+    /// Computation charged to each stack of frames, excluding the frames it called. The outermost
+    /// frame is the command the stack ran in. Computation charged outside of any command, e.g. for
+    /// reading the input objects or rounding the computation cost up to its bucket, has an empty
+    /// stack.
+    pub frames: Vec<FrameGasProfile>,
+    /// Storage charged and rebated for each object written or deleted by the transaction.
+    pub objects: Vec<ObjectStorageProfile>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandGasProfile {
+    pub command: String,
+    pub computation_units: u64,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameGasProfile {
+    /// Names of the frames, outermost first.
+    pub stack: Vec<String>,
+    pub computation_units: u64,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStorageProfile {
+    pub object_id: ObjectID,
+    /// Size of the object written by the transaction, 0 if it was deleted.
+    pub bytes_written: u64,
+    /// Size of the version of the object the transaction replaced or deleted, 0 if it was created.
+    pub bytes_deleted: u64,
+    /// Storage cost of the written object.
+    pub storage_cost: u64,
+    /// Storage rebate of the replaced or deleted version of the object, before the
+    /// non-refundable storage fee is taken out.
+    pub storage_rebate: u64,
+}
+
+impl GasProfile {
+    /// Renders the computation of every stack of frames as a line of the folded stacks format
+    /// consumed by flamegraph tools, rooted at a `transaction` frame.
+    pub fn folded_stacks(&self) -> String {
+        self.frames
+            .iter()
+            .map(|frame| {
+                let mut line = String::from("transaction");
+                for name in &frame.stack {
+                    line.push(';');
+                    line.push_str(name);
+                }
+                format!("{line} {}\n", frame.computation_units)
+            })
+            .collect()
+    }
+}
+
 // Fixed cost type
 #[derive(Clone)]
 pub struct FixedCost(InternalGas);
@@ -349,6 +417,8 @@ pub struct SuiGasStatus<'a> {
     storage_rebate: SuiGas,
 
     cost_table: SuiCostTable,
+    /// Storage charged for each object, if gas profiling is enabled.
+    storage_profile: Option<Vec<ObjectStorageProfile>>,
 }
 
 fn to_internal(external_units: GasUnits) -> InternalGas {
@@ -430,6 +500,77 @@ impl<'a> SuiGasStatus<'a> {
     pub fn reset_storage_cost_and_rebate(&mut self) {
         self.storage_gas_units = GasQuantity::zero();
         self.storage_rebate = GasQuantity::zero();
+        if let Some(storage_profile) = &mut self.storage_profile {
+            storage_profile.clear();
+        }
+    }
+
+    /// Start collecting a `GasProfile` of the charges made from now on.
+    pub fn enable_profiling(&mut self) {
+        self.gas_status.enable_profiling();
+        self.storage_profile = Some(vec![]);
+    }
+
+    /// Return the profiler attributing computation to frames, if profiling is enabled.
+    pub fn profiler_mut(&mut self) -> Option<&mut GasProfiler> {
+        self.gas_status.profiler_mut()
+    }
+
+    /// Record the storage charged for `object_id`, if profiling is enabled.
+    pub fn record_object_storage(
+        &mut self,
+        object_id: ObjectID,
+        bytes_written: usize,
+        bytes_deleted: usize,
+        storage_cost: u64,
+        storage_rebate: u64,
+    ) {
+        if let Some(storage_profile) = &mut self.storage_profile {
+            storage_profile.push(ObjectStorageProfile {
+                object_id,
+                bytes_written: bytes_written as u64,
+                bytes_deleted: bytes_deleted as u64,
+                storage_cost,
+                storage_rebate,
+            });
+        }
+    }
+
+    /// Stop profiling and return the profile of the charges made since profiling was enabled.
+    pub fn take_profile(&mut self) -> Option<GasProfile> {
+        let profiler = self.gas_status.take_profiler()?;
+        let objects = self.storage_profile.take().unwrap_or_default();
+        // Storage is deducted from the same budget as computation, once no frame is open. Take it
+        // out of the empty stack so that computation adds up as in `summary`.
+        let mut storage = to_internal(self.storage_gas_units);
+        let mut commands: Vec<CommandGasProfile> = vec![];
+        let mut frames = vec![];
+        for (stack, mut cost) in profiler.into_costs() {
+            if stack.is_empty() {
+                let storage_charged = std::cmp::min(cost, storage);
+                cost = cost.checked_sub(storage_charged).unwrap();
+                storage = storage.checked_sub(storage_charged).unwrap();
+            }
+            let computation_units = u64::from(cost);
+            if let Some(command) = stack.first() {
+                match commands.iter_mut().find(|c| &c.command == command) {
+                    Some(c) => c.computation_units += computation_units,
+                    None => commands.push(CommandGasProfile {
+                        command: command.clone(),
+                        computation_units,
+                    }),
+                }
+            }
+            frames.push(FrameGasProfile {
+                stack,
+                computation_units,
+            });
+        }
+        Some(GasProfile {
+            commands,
+            frames,
+            objects,
+        })
     }
 
     /// Try to charge the minimal amount of gas from the gas object.
@@ -531,6 +672,7 @@ impl<'a> SuiGasStatus<'a> {
             storage_gas_units: GasUnits::new(0),
             storage_rebate: 0.into(),
             cost_table,
+            storage_profile: None,
         }
     }
 
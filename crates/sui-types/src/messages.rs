@@ -1087,6 +1087,29 @@ impl TransactionData {
         })
     }
 
+    /// Creates a sponsored transaction, whose gas is paid with `gas_payment` coins owned by
+    /// `gas_sponsor` instead of the sender. It must be signed by both the sender and the sponsor.
+    pub fn new_with_gas_coins_allow_sponsor(
+        kind: TransactionKind,
+        sender: SuiAddress,
+        gas_payment: Vec<ObjectRef>,
+        gas_budget: u64,
+        gas_price: u64,
+        gas_sponsor: SuiAddress,
+    ) -> Self {
+        TransactionData::V1(TransactionDataV1 {
+            kind,
+            sender,
+            gas_data: GasData {
+                price: gas_price,
+                owner: gas_sponsor,
+                payment: gas_payment,
+                budget: gas_budget,
+            },
+            expiration: TransactionExpiration::None,
+        })
+    }
+
     pub fn new_with_gas_data(kind: TransactionKind, sender: SuiAddress, gas_data: GasData) -> Self {
         TransactionData::V1(TransactionDataV1 {
             kind,
@@ -1452,6 +1475,25 @@ impl TransactionData {
         Self::new_with_gas_coins(kind, sender, gas_payment, gas_budget, gas_price)
     }
 
+    pub fn new_programmable_allow_sponsor(
+        sender: SuiAddress,
+        gas_payment: Vec<ObjectRef>,
+        pt: ProgrammableTransaction,
+        gas_budget: u64,
+        gas_price: u64,
+        gas_sponsor: SuiAddress,
+    ) -> Self {
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        Self::new_with_gas_coins_allow_sponsor(
+            kind,
+            sender,
+            gas_payment,
+            gas_budget,
+            gas_price,
+            gas_sponsor,
+        )
+    }
+
     pub fn execution_parts(&self) -> (TransactionKind, SuiAddress, Vec<ObjectRef>) {
         (
             self.kind().clone(),
@@ -1896,6 +1938,37 @@ impl Transaction {
         Self::new(SenderSignedData::new(data, intent, signatures))
     }
 
+    /// Assembles a transaction from signatures collected separately from each of its signers,
+    /// such as the sender and the gas sponsor of a sponsored transaction. Signatures can be given
+    /// in any order and repeated signatures of the same signer are ignored. Fails if the
+    /// signature of a signer is missing, or if a signature is not from one of the signers. The
+    /// signatures themselves are only checked when verifying the transaction.
+    pub fn from_signer_signatures(
+        data: TransactionData,
+        intent: Intent,
+        signatures: Vec<GenericSignature>,
+    ) -> SuiResult<Self> {
+        let mut by_signer = BTreeMap::new();
+        for signature in signatures {
+            let signer: SuiAddress = (&signature).try_into()?;
+            by_signer.insert(signer, signature);
+        }
+        let mut tx_signatures = Vec::new();
+        for signer in data.signers() {
+            tx_signatures.push(by_signer.remove(&signer).ok_or_else(|| {
+                SuiError::SignerSignatureAbsent {
+                    signer: signer.to_string(),
+                }
+            })?);
+        }
+        if let Some(signer) = by_signer.keys().next() {
+            return Err(SuiError::IncorrectSigner {
+                error: format!("{signer} is not a signer of the transaction"),
+            });
+        }
+        Ok(Self::from_generic_sig_data(data, intent, tx_signatures))
+    }
+
     /// Returns the Base64 encoded tx_bytes
     /// and a list of Base64 encoded [enum GenericSignature].
     pub fn to_tx_bytes_and_signatures(&self) -> (Base64, Vec<Base64>) {
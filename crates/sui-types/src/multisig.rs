@@ -208,6 +208,51 @@ impl MultiSig {
         })
     }
 
+    /// Like [MultiSig::combine], but accepts the partial signatures in any order and rejects
+    /// combinations which can never verify: a signer appearing more than once, or signers whose
+    /// total weight is below the threshold of `multisig_pk`. The signatures themselves are not
+    /// verified.
+    pub fn combine_checked(
+        full_sigs: Vec<Signature>,
+        multisig_pk: MultiSigPublicKey,
+    ) -> Result<Self, SuiError> {
+        let mut indexed_sigs = full_sigs
+            .into_iter()
+            .map(|s| {
+                let index =
+                    multisig_pk
+                        .get_index(s.to_public_key()?)
+                        .ok_or(SuiError::IncorrectSigner {
+                            error: "pk does not exist".to_string(),
+                        })?;
+                Ok((index, s))
+            })
+            .collect::<Result<Vec<_>, SuiError>>()?;
+        // Signatures are verified in the order of the public keys they belong to.
+        indexed_sigs.sort_by_key(|(index, _)| *index);
+        if indexed_sigs.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(SuiError::IncorrectSigner {
+                error: "Duplicate signatures for the same pk".to_string(),
+            });
+        }
+        let weight_sum: u16 = indexed_sigs
+            .iter()
+            .map(|(index, _)| multisig_pk.pk_map[*index as usize].1 as u16)
+            .sum();
+        if weight_sum < multisig_pk.threshold {
+            return Err(SuiError::InvalidSignature {
+                error: format!(
+                    "Insufficient weight {} for threshold {}",
+                    weight_sum, multisig_pk.threshold
+                ),
+            });
+        }
+        Self::combine(
+            indexed_sigs.into_iter().map(|(_, s)| s).collect(),
+            multisig_pk,
+        )
+    }
+
     pub fn validate(&self) -> Result<(), FastCryptoError> {
         if self.sigs.len() > self.multisig_pk.pk_map.len() || self.sigs.is_empty() {
             return Err(FastCryptoError::InvalidInput);
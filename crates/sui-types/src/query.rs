@@ -6,6 +6,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::base_types::SuiAddress;
+use crate::messages_checkpoint::CheckpointSequenceNumber;
 use crate::ObjectID;
 
 #[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
@@ -26,6 +27,46 @@ pub enum TransactionFilter {
     ToAddress(SuiAddress),
     /// Query by sender and recipient address.
     FromAndToAddress { from: SuiAddress, to: SuiAddress },
-    /// Query by transaction kind
+    /// Query by transaction kind. Only the indexer supports it, full nodes reject it, including
+    /// nested in `And` or `Or`.
     TransactionKind(String),
+    /// Query by checkpoint sequence number, start inclusive, end exclusive. Nested in `And` or
+    /// `Or`, the range may hold at most 4096 transactions. Full nodes only index the checkpoints
+    /// they executed since they index transactions by checkpoint, and reject ranges starting
+    /// before the first of them.
+    Checkpoint {
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    },
+    /// Query by the timestamp of the checkpoint including the transaction, in milliseconds,
+    /// start inclusive, end exclusive. Nested in `And` or `Or`, the range may hold at most 4096
+    /// transactions. Full nodes reject ranges starting before their first checkpoint indexed by
+    /// checkpoint, as for `Checkpoint`.
+    TimeRange { start_ms: u64, end_ms: u64 },
+    /// Query for transactions matching both filters. Full nodes reject a nested
+    /// `TransactionKind` filter, as they don't index transactions by kind.
+    And(Box<TransactionFilter>, Box<TransactionFilter>),
+    /// Query for transactions matching either filter. Full nodes reject a nested
+    /// `TransactionKind` filter, as they don't index transactions by kind.
+    Or(Box<TransactionFilter>, Box<TransactionFilter>),
+}
+
+impl TransactionFilter {
+    pub fn and(self, other_filter: TransactionFilter) -> Self {
+        Self::And(Box::new(self), Box::new(other_filter))
+    }
+    pub fn or(self, other_filter: TransactionFilter) -> Self {
+        Self::Or(Box::new(self), Box::new(other_filter))
+    }
+
+    /// Whether the filter, or a filter nested in it, selects transactions by checkpoint.
+    pub fn filters_by_checkpoint(&self) -> bool {
+        match self {
+            Self::Checkpoint { .. } => true,
+            Self::And(f1, f2) | Self::Or(f1, f2) => {
+                f1.filters_by_checkpoint() || f2.filters_by_checkpoint()
+            }
+            _ => false,
+        }
+    }
 }
@@ -29,7 +29,7 @@ use crate::{
     error::{ExecutionError, SuiError, SuiResult},
     event::Event,
     fp_bail, gas,
-    gas::{GasCostSummary, SuiGasStatus},
+    gas::{GasCostSummary, GasProfile, SuiGasStatus},
     messages::{ExecutionStatus, InputObjects, TransactionEffects},
     object::Owner,
     object::{Data, Object},
@@ -48,6 +48,8 @@ pub struct InnerTemporaryStore {
     pub deleted: BTreeMap<ObjectID, (SequenceNumber, DeleteKind)>,
     pub events: TransactionEvents,
     pub max_binary_format_version: u32,
+    /// Breakdown of the gas charged, if the transaction was executed with gas profiling enabled.
+    pub gas_profile: Option<GasProfile>,
 }
 
 impl InnerTemporaryStore {
@@ -153,6 +155,7 @@ pub struct TemporaryStore<S> {
     gas_charged: Option<(ObjectID, GasCostSummary)>,
     storage_rebate_rate: u64,
     protocol_config: ProtocolConfig,
+    gas_profile: Option<GasProfile>,
 }
 
 impl<S> TemporaryStore<S> {
@@ -179,6 +182,7 @@ impl<S> TemporaryStore<S> {
             gas_charged: None,
             storage_rebate_rate: protocol_config.storage_rebate_rate(),
             protocol_config: protocol_config.clone(),
+            gas_profile: None,
         }
     }
 
@@ -249,6 +253,7 @@ impl<S> TemporaryStore<S> {
             deleted,
             events: TransactionEvents { data: self.events },
             max_binary_format_version: self.protocol_config.move_binary_format_version(),
+            gas_profile: self.gas_profile,
         }
     }
 
@@ -753,6 +758,7 @@ impl<S: ObjectStore> TemporaryStore<S> {
 
         self.write_object(gas_object, WriteKind::Mutate);
         self.gas_charged = Some((gas_object_id, cost_summary));
+        self.gas_profile = gas_status.take_profile();
     }
 
     /// Return the storage rebate and size of `id` at input
@@ -842,6 +848,13 @@ impl<S: ObjectStore> TemporaryStore<S> {
             let new_storage_rebate =
                 gas_status.charge_storage_mutation(new_object_size, old_storage_rebate.into())?;
             object.storage_rebate = new_storage_rebate;
+            gas_status.record_object_storage(
+                *object_id,
+                new_object_size,
+                old_object_size,
+                new_storage_rebate,
+                old_storage_rebate,
+            );
             if !object.is_immutable() {
                 objects_to_update.push((object.clone(), *write_kind));
             }
@@ -854,6 +867,7 @@ impl<S: ObjectStore> TemporaryStore<S> {
                     let (storage_rebate, object_size) =
                         self.get_input_storage_rebate_and_size(object_id, *version)?;
                     gas_status.charge_storage_mutation(0, storage_rebate.into())?;
+                    gas_status.record_object_storage(*object_id, 0, object_size, 0, storage_rebate);
                     total_bytes_written_deleted += object_size;
                 }
                 DeleteKind::UnwrapThenDelete => {
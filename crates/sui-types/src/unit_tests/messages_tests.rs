@@ -766,6 +766,74 @@ fn test_sponsored_transaction_message() {
     assert_eq!(tx.gas_owner(), sponsor,);
 }
 
+#[test]
+fn test_sponsored_transaction_from_signer_signatures() {
+    let sender_kp = SuiKeyPair::Ed25519(get_key_pair().1);
+    let sender = (&sender_kp.public()).into();
+    let sponsor_kp = SuiKeyPair::Ed25519(get_key_pair().1);
+    let sponsor = (&sponsor_kp.public()).into();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .transfer_object(get_new_address::<AccountKeyPair>(), random_object_ref())
+            .unwrap();
+        builder.finish()
+    };
+    let tx_data = TransactionData::new_programmable_allow_sponsor(
+        sender,
+        vec![random_object_ref()],
+        pt,
+        10000,
+        DUMMY_GAS_PRICE,
+        sponsor,
+    );
+    assert_eq!(tx_data.signers(), vec![sender, sponsor]);
+
+    let intent = Intent::default();
+    let sender_sig: GenericSignature =
+        signature_from_signer(tx_data.clone(), intent.clone(), &sender_kp).into();
+    let sponsor_sig: GenericSignature =
+        signature_from_signer(tx_data.clone(), intent.clone(), &sponsor_kp).into();
+
+    // Signatures are put in the order of the signers, and repeated ones are dropped.
+    let transaction = Transaction::from_signer_signatures(
+        tx_data.clone(),
+        intent.clone(),
+        vec![sponsor_sig.clone(), sender_sig.clone(), sponsor_sig.clone()],
+    )
+    .unwrap();
+    assert_eq!(
+        transaction.data().tx_signatures(),
+        &[sender_sig.clone(), sponsor_sig.clone()]
+    );
+    transaction.verify().unwrap();
+
+    // Missing sponsor signature
+    assert!(matches!(
+        Transaction::from_signer_signatures(
+            tx_data.clone(),
+            intent.clone(),
+            vec![sender_sig.clone()]
+        )
+        .unwrap_err(),
+        SuiError::SignerSignatureAbsent { .. }
+    ));
+
+    // Signature of a third party
+    let third_party_kp = SuiKeyPair::Ed25519(get_key_pair().1);
+    let third_party_sig: GenericSignature =
+        signature_from_signer(tx_data.clone(), intent.clone(), &third_party_kp).into();
+    assert!(matches!(
+        Transaction::from_signer_signatures(
+            tx_data,
+            intent,
+            vec![sender_sig, sponsor_sig, third_party_sig]
+        )
+        .unwrap_err(),
+        SuiError::IncorrectSigner { .. }
+    ));
+}
+
 #[test]
 fn test_sponsored_transaction_validity_check() {
     let sender_kp = SuiKeyPair::Ed25519(get_key_pair().1);
@@ -165,6 +165,42 @@ fn test_combine_sigs() {
     // Cannot create malformed MultiSig.
     assert!(MultiSig::combine(vec![], multisig_pk).is_err());
 }
+
+#[test]
+fn test_combine_checked_sigs() {
+    let keys = keys();
+    let pks: Vec<_> = keys.iter().map(|kp| kp.public()).collect();
+    // pk1 (weight = 1), pk2 (weight = 2), pk3 (weight = 3), threshold 3.
+    let multisig_pk = MultiSigPublicKey::new(pks, vec![1, 2, 3], 3).unwrap();
+    let addr = SuiAddress::from(multisig_pk.clone());
+    let msg = IntentMessage::new(
+        Intent::default(),
+        PersonalMessage {
+            message: "Hello".as_bytes().to_vec(),
+        },
+    );
+    let sig1 = Signature::new_secure(&msg, &keys[0]);
+    let sig2 = Signature::new_secure(&msg, &keys[1]);
+    let sig3 = Signature::new_secure(&msg, &keys[2]);
+
+    // Signatures in any order are combined into a valid MultiSig.
+    let multisig =
+        MultiSig::combine_checked(vec![sig2.clone(), sig1.clone()], multisig_pk.clone()).unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_ok());
+
+    // Weight below the threshold fails to combine.
+    assert!(MultiSig::combine_checked(vec![sig2.clone()], multisig_pk.clone()).is_err());
+
+    // The same signer twice fails to combine, even though the weights meet the threshold.
+    assert!(MultiSig::combine_checked(vec![sig2.clone(), sig2], multisig_pk.clone()).is_err());
+
+    // A signature from a key outside of the MultiSig fails to combine.
+    let kp4: SuiKeyPair = SuiKeyPair::Ed25519(get_key_pair().1);
+    let sig4 = Signature::new_secure(&msg, &kp4);
+    assert!(MultiSig::combine_checked(vec![sig3, sig4], multisig_pk.clone()).is_err());
+
+    assert!(MultiSig::combine_checked(vec![], multisig_pk).is_err());
+}
 #[test]
 fn test_serde_roundtrip() {
     let msg = IntentMessage::new(
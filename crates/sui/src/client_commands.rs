@@ -4,13 +4,14 @@
 use core::fmt;
 use std::sync::Arc;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter, Write},
     path::{Path, PathBuf},
     time::Instant,
 };
 
 use crate::config::{Config, PersistedConfig, SuiClientConfig, SuiEnv};
+use crate::ptb::PtbScript;
 use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
@@ -37,20 +38,23 @@ use sui_framework_build::compiled_package::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiRawData, SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
+    DryRunTransactionResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiRawData, SuiTransactionEffectsAPI, SuiTransactionResponse,
+    SuiTransactionResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::SuiClient;
-use sui_types::crypto::SignatureScheme;
+use sui_types::crypto::{EncodeDecodeBase64, SignatureScheme};
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::move_package::UpgradeCap;
 use sui_types::signature::GenericSignature;
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
     gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    messages::{
+        Transaction, TransactionData, TransactionDataAPI, TransactionKind, VerifiedTransaction,
+    },
     object::Owner,
     parse_sui_type_tag,
 };
@@ -245,6 +249,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
     },
 
     /// Transfer object
@@ -266,6 +274,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this transfer
         #[clap(long)]
         gas_budget: u64,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
     },
     /// Transfer SUI, and pay gas with the same SUI coin object.
     /// If amount is specified, only the amount is transferred; otherwise the entire object
@@ -312,6 +324,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
     },
 
     /// Pay SUI coins to recipients following following specified amounts, with input coins.
@@ -334,6 +350,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -351,6 +371,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
     },
 
     /// Obtain the Addresses managed by the client.
@@ -454,19 +478,106 @@ pub enum SuiClientCommands {
         amount: Option<u64>,
     },
 
+    /// Build, and execute, a programmable transaction block composed of the commands described
+    /// in a YAML or JSON script file. See the `ptb` module for the script format.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Path of the script describing the commands of the transaction
+        #[clap(parse(from_os_str))]
+        file: PathBuf,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// Address of a sponsor paying for gas instead of the active address. The gas object
+        /// must then belong to the sponsor, who signs the transaction as well, see
+        /// `sponsor-sign`.
+        #[clap(long)]
+        gas_sponsor: Option<SuiAddress>,
+
+        /// Dry run the transaction instead of executing it, and output its effects
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Do not Sign transaction, output Base64-encoded Serialized Output
+        #[clap(long)]
+        serialize_output: bool,
+    },
+
+    /// Sign a sponsored transaction as its gas sponsor. The sender signs the same transaction
+    /// bytes with `sui keytool sign`, and the transaction is then executed with
+    /// `execute-signed-tx` given both signatures.
+    SponsorSign {
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        #[clap(long)]
+        tx_bytes: String,
+
+        /// Address of the sponsor, defaults to the active address
+        #[clap(long)]
+        address: Option<SuiAddress>,
+    },
+
     /// Execute a Signed Transaction. This is useful when the user prefers to sign elsewhere and use this command to execute.
+    ///
+    /// The transaction needs one signature from each of its signers, i.e. from both its sender
+    /// and its gas sponsor if it is sponsored.
     ExecuteSignedTx {
         /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
         #[clap(long)]
         tx_bytes: String,
 
-        /// A list of Base64 encoded signatures `flag || signature || pubkey`.
+        /// A list of Base64 encoded signatures `flag || signature || pubkey`, one for each signer
+        /// of the transaction. They can be given in any order and are reordered as the
+        /// transaction expects. Only the last of several signatures of the same signer is kept,
+        /// and signatures of addresses that are not signers of the transaction are rejected.
         #[clap(long)]
         signatures: Vec<String>,
     },
 }
 
 impl SuiClientCommands {
+    /// Whether the command signs with, or adds a key to, the keystore, which must then be
+    /// unlocked before executing it.
+    pub fn requires_unlocked_keystore(&self) -> bool {
+        matches!(
+            self,
+            SuiClientCommands::Publish {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::Call {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::Transfer {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::Pay {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::PaySui {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::PayAllSui {
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::Ptb {
+                dry_run: false,
+                serialize_output: false,
+                ..
+            } | SuiClientCommands::Upgrade { .. }
+                | SuiClientCommands::SponsorSign { .. }
+                | SuiClientCommands::TransferSui { .. }
+                | SuiClientCommands::NewAddress { .. }
+                | SuiClientCommands::SplitCoin { .. }
+                | SuiClientCommands::MergeCoin { .. }
+        )
+    }
+
     pub async fn execute(
         self,
         context: &mut WalletContext,
@@ -640,7 +751,17 @@ impl SuiClientCommands {
                 gas,
                 gas_budget,
                 args,
+                serialize_output,
             } => {
+                if serialize_output {
+                    let (_, data) = move_call_data(
+                        package, &module, &function, type_args, gas, gas_budget, args, context,
+                    )
+                    .await?;
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
                 let response = call_move(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
@@ -653,6 +774,7 @@ impl SuiClientCommands {
                 object_id,
                 gas,
                 gas_budget,
+                serialize_output,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
                 let time_start = Instant::now();
@@ -662,6 +784,11 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
+                if serialize_output {
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
                 let signature =
                     context
                         .config
@@ -725,6 +852,7 @@ impl SuiClientCommands {
                 amounts,
                 gas,
                 gas_budget,
+                serialize_output,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -748,6 +876,11 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
+                if serialize_output {
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
                 let signature =
                     context
                         .config
@@ -776,6 +909,7 @@ impl SuiClientCommands {
                 recipients,
                 amounts,
                 gas_budget,
+                serialize_output,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -799,6 +933,11 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
                     .await?;
+                if serialize_output {
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
                 let signature =
                     context
                         .config
@@ -826,6 +965,7 @@ impl SuiClientCommands {
                 input_coins,
                 recipient,
                 gas_budget,
+                serialize_output,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -837,6 +977,11 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .pay_all_sui(signer, input_coins, recipient, gas_budget)
                     .await?;
+                if serialize_output {
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
 
                 let signature =
                     context
@@ -1010,6 +1155,111 @@ impl SuiClientCommands {
                 ))
             }
 
+            SuiClientCommands::Ptb {
+                file,
+                gas,
+                gas_budget,
+                gas_sponsor,
+                dry_run,
+                serialize_output,
+            } => {
+                let script = PtbScript::read(&file)?;
+                let sender = match gas_sponsor {
+                    // The gas object belongs to the sponsor.
+                    Some(_) => context.active_address()?,
+                    None => {
+                        let sender = context.try_get_object_owner(&gas).await?;
+                        sender.unwrap_or(context.active_address()?)
+                    }
+                };
+
+                let client = context.get_client().await?;
+                let mut objects = BTreeMap::new();
+                for id in script.object_ids()? {
+                    let object = client
+                        .read_api()
+                        .get_object_with_options(id, SuiObjectDataOptions::new().with_owner())
+                        .await?
+                        .into_object()?;
+                    let owner = object
+                        .owner
+                        .ok_or_else(|| anyhow!("Owner of object [{id}] is missing"))?;
+                    objects.insert(id, (object.object_ref(), owner));
+                }
+                let pt = script.build(&objects)?;
+                let data = client
+                    .transaction_builder()
+                    .sponsored_transaction(
+                        sender,
+                        TransactionKind::programmable(pt),
+                        gas_sponsor.unwrap_or(sender),
+                        gas,
+                        gas_budget,
+                    )
+                    .await?;
+
+                if serialize_output {
+                    return Ok(SuiClientCommandResult::SerializeTransaction(
+                        Base64::encode(bcs::to_bytes(&data).unwrap()),
+                    ));
+                }
+                if dry_run {
+                    let response = client.read_api().dry_run_transaction(data).await?;
+                    return Ok(SuiClientCommandResult::DryRun(response));
+                }
+
+                // A sponsored transaction can only be executed directly if the keystore holds
+                // the keys of both the sender and the sponsor.
+                let mut signatures = Vec::new();
+                for signer in data.signers() {
+                    signatures.push(
+                        context
+                            .config
+                            .keystore
+                            .sign_secure(&signer, &data, Intent::default())
+                            .map_err(|e| anyhow!("Cannot sign as [{signer}]: {e}"))?,
+                    );
+                }
+                let response = context
+                    .execute_transaction(
+                        Transaction::from_data(data, Intent::default(), signatures).verify()?,
+                    )
+                    .await?;
+                SuiClientCommandResult::Ptb(response)
+            }
+
+            SuiClientCommands::SponsorSign { tx_bytes, address } => {
+                let data: TransactionData = bcs::from_bytes(
+                    &Base64::try_from(tx_bytes)
+                        .map_err(|e| anyhow!(e))?
+                        .to_vec()
+                        .map_err(|e| anyhow!(e))?,
+                )?;
+                let sponsor = address.unwrap_or(context.active_address()?);
+                ensure!(
+                    data.gas_owner() == sponsor,
+                    "Gas of the transaction is paid by [{}], not by [{sponsor}]",
+                    data.gas_owner()
+                );
+                ensure!(
+                    data.sender() != sponsor,
+                    "Transaction is not sponsored, its sender pays for gas"
+                );
+                let signature =
+                    context
+                        .config
+                        .keystore
+                        .sign_secure(&sponsor, &data, Intent::default())?;
+                SuiClientCommandResult::SponsorSign(SponsorSignResponse {
+                    sender: data.sender(),
+                    sponsor,
+                    gas: data.gas().to_vec(),
+                    gas_budget: data.gas_budget(),
+                    gas_price: data.gas_price(),
+                    signature: signature.encode_base64(),
+                })
+            }
+
             SuiClientCommands::ExecuteSignedTx {
                 tx_bytes,
                 signatures,
@@ -1034,7 +1284,7 @@ impl SuiClientCommands {
                     );
                 }
                 let verified =
-                    Transaction::from_generic_sig_data(data, Intent::default(), sigs).verify()?;
+                    Transaction::from_signer_signatures(data, Intent::default(), sigs)?.verify()?;
 
                 let response = context.execute_transaction(verified).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
@@ -1528,12 +1778,24 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::ExecuteSignedTx(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::SponsorSign(response) => {
+                write!(writer, "{}", response)?;
+            }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::DryRun(response) => {
+                write!(writer, "{}", write_dry_run_response(response)?)?;
+            }
             SuiClientCommandResult::SerializeTransferSui(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
             SuiClientCommandResult::SerializePublish(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
+            SuiClientCommandResult::SerializeTransaction(data) => {
+                writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
+            }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
             }
@@ -1567,6 +1829,38 @@ pub async fn call_move(
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<SuiTransactionResponse, anyhow::Error> {
+    let (sender, data) = move_call_data(
+        package, module, function, type_args, gas, gas_budget, args, context,
+    )
+    .await?;
+    let signature = context
+        .config
+        .keystore
+        .sign_secure(&sender, &data, Intent::default())?;
+    let transaction = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
+
+    let response = context.execute_transaction(transaction).await?;
+    let effects = response
+        .effects
+        .as_ref()
+        .ok_or_else(|| anyhow!("Effects from SuiTransactionResult should not be empty"))?;
+    if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
+        return Err(anyhow!("Error calling module: {:#?}", effects.status()));
+    }
+    Ok(response)
+}
+
+/// Builds the unsigned transaction data of a Move call, returning it with its sender.
+async fn move_call_data(
+    package: ObjectID,
+    module: &str,
+    function: &str,
+    type_args: Vec<TypeTag>,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<(SuiAddress, TransactionData), anyhow::Error> {
     // Convert all numeric input to String, this will allow number input from the CLI without failing SuiJSON's checks.
     let args = args
         .into_iter()
@@ -1593,24 +1887,10 @@ pub async fn call_move(
             gas_budget,
         )
         .await?;
-    let signature = context
-        .config
-        .keystore
-        .sign_secure(&sender, &data, Intent::default())?;
-    let transaction = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
-
-    let response = context.execute_transaction(transaction).await?;
-    let effects = response
-        .effects
-        .as_ref()
-        .ok_or_else(|| anyhow!("Effects from SuiTransactionResult should not be empty"))?;
-    if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
-        return Err(anyhow!("Error calling module: {:#?}", effects.status()));
-    }
-    Ok(response)
+    Ok((sender, data))
 }
 
-fn convert_number_to_string(value: Value) -> Value {
+pub(crate) fn convert_number_to_string(value: Value) -> Value {
     match value {
         Value::Number(n) => Value::String(n.to_string()),
         Value::Array(a) => Value::Array(a.into_iter().map(convert_number_to_string).collect()),
@@ -1655,6 +1935,22 @@ pub fn write_transaction_response(response: &SuiTransactionResponse) -> Result<S
     Ok(writer)
 }
 
+pub fn write_dry_run_response(response: &DryRunTransactionResponse) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "{}", "----- Transaction Effects ----".bold())?;
+    writeln!(writer, "{}", response.effects)?;
+
+    writeln!(writer, "{}", "----- Events ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.events))?;
+
+    writeln!(writer, "{}", "----- Object changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.object_changes))?;
+
+    writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+    Ok(writer)
+}
+
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = unwrap_err_to_string(|| match self {
@@ -1727,10 +2023,43 @@ pub enum SuiClientCommandResult {
     Envs(Vec<SuiEnv>, Option<String>),
     SerializeTransferSui(String),
     SerializePublish(String),
+    SerializeTransaction(String),
     ExecuteSignedTx(SuiTransactionResponse),
+    SponsorSign(SponsorSignResponse),
+    Ptb(SuiTransactionResponse),
+    DryRun(DryRunTransactionResponse),
     NewEnv(SuiEnv),
 }
 
+#[derive(Serialize, Clone, Debug)]
+pub struct SponsorSignResponse {
+    pub sender: SuiAddress,
+    pub sponsor: SuiAddress,
+    pub gas: Vec<ObjectRef>,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    /// Base64 encoded signature of the sponsor
+    pub signature: String,
+}
+
+impl Display for SponsorSignResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "Sender: {}", self.sender)?;
+        writeln!(writer, "Sponsor: {}", self.sponsor)?;
+        for (id, version, digest) in &self.gas {
+            writeln!(
+                writer,
+                "Gas object: {id} (version {version}, digest {digest})"
+            )?;
+        }
+        writeln!(writer, "Gas budget: {}", self.gas_budget)?;
+        writeln!(writer, "Gas price: {}", self.gas_price)?;
+        writeln!(writer, "Sponsor signature: {}", self.signature)?;
+        write!(f, "{}", writer)
+    }
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct SwitchResponse {
     /// Active address
@@ -6,6 +6,7 @@ use clap::*;
 use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding};
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::KeyPair;
+use inquire::Password;
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -14,13 +15,13 @@ use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{AccountKeystore, EncryptedFileBasedKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
 use sui_types::crypto::{DefaultHash, PublicKey, Signature};
-use sui_types::messages::TransactionData;
+use sui_types::messages::{TransactionData, TransactionDataAPI};
 use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
-use sui_types::signature::GenericSignature;
+use sui_types::signature::{AuthenticatorTrait, GenericSignature};
 use tracing::info;
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
@@ -65,6 +66,10 @@ pub enum KeyToolCommand {
         data: String,
         #[clap(long)]
         intent: Option<Intent>,
+        /// Also write the serialized signature to this file, e.g. to later combine it with
+        /// the signatures of other signers into a MultiSig.
+        #[clap(long)]
+        output_file: Option<PathBuf>,
     },
     /// Add a new key to sui.key based on the input mnemonic phrase, the key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// and an optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or m/54'/784'/0'/0/0 for secp256k1
@@ -93,10 +98,18 @@ pub enum KeyToolCommand {
 
     /// Provides a list of signatures (`flag || sig || pk` encoded in Base64), threshold, a list of public keys.
     /// Returns a valid MultiSig and its sender address. The result can be used as signature field for `sui client execute-signed-tx`.
-    /// The number of sigs must be greater than the threshold. The number of sigs must be smaller than the number of pks.
+    /// The signatures can be given in any order, directly or as files written by `sui keytool sign --output-file`.
+    /// The total weight of the signers must reach the threshold, and each signer may only sign once.
     MultiSigCombinePartialSig {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         sigs: Vec<Signature>,
+        /// Files each holding a Base64 encoded signature `flag || sig || pk`.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        sig_files: Vec<PathBuf>,
+        /// Base64 encoded BCS serialized transaction data which was signed. If provided, the
+        /// combined MultiSig is verified against it.
+        #[clap(long)]
+        tx_bytes: Option<String>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         pks: Vec<PublicKey>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
@@ -104,9 +117,54 @@ pub enum KeyToolCommand {
         #[clap(long)]
         threshold: ThresholdUnit,
     },
+
+    /// Encrypt the plaintext sui.keystore in place with a passphrase. The private keys of an
+    /// encrypted keystore are only decrypted when needed, after prompting for the passphrase.
+    Encrypt,
+}
+
+/// Environment variable holding the passphrase of an encrypted keystore. When set, it is used
+/// instead of prompting for the passphrase, e.g. in scripts.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+/// Unlocks `keystore` if it is locked, prompting for its passphrase.
+pub fn unlock_keystore(keystore: &mut Keystore) -> Result<(), anyhow::Error> {
+    if keystore.is_locked() {
+        let passphrase = match std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+            Ok(passphrase) => passphrase,
+            Err(_) => Password::new("Keystore passphrase:")
+                .without_confirmation()
+                .prompt()?,
+        };
+        keystore.unlock(&passphrase)?;
+    }
+    Ok(())
+}
+
+fn read_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = match std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => Password::new("New keystore passphrase:")
+            .with_custom_confirmation_message("Confirm keystore passphrase:")
+            .with_custom_confirmation_error_message("The passphrases don't match.")
+            .prompt()?,
+    };
+    if passphrase.is_empty() {
+        return Err(anyhow!("Keystore passphrase cannot be empty"));
+    }
+    Ok(passphrase)
 }
 
 impl KeyToolCommand {
+    /// Whether the command needs the private keys of the keystore, which must then be unlocked
+    /// before executing it.
+    pub fn requires_unlocked_keystore(&self) -> bool {
+        matches!(
+            self,
+            KeyToolCommand::Sign { .. } | KeyToolCommand::Import { .. }
+        )
+    }
+
     pub fn execute(self, keystore: &mut Keystore) -> Result<(), anyhow::Error> {
         match self {
             KeyToolCommand::Generate {
@@ -177,6 +235,7 @@ impl KeyToolCommand {
                 address,
                 data,
                 intent,
+                output_file,
             } => {
                 println!("Signer address: {}", address);
                 println!("Raw tx_bytes to execute: {}", data);
@@ -201,6 +260,10 @@ impl KeyToolCommand {
                     "Serialized signature (`flag || sig || pk` in Base64): {:?}",
                     sui_signature.encode_base64()
                 );
+                if let Some(output_file) = output_file {
+                    fs::write(&output_file, sui_signature.encode_base64())?;
+                    println!("Signature written to {:?}", output_file);
+                }
             }
             KeyToolCommand::Import {
                 mnemonic_phrase,
@@ -259,19 +322,66 @@ impl KeyToolCommand {
                 }
             }
             KeyToolCommand::MultiSigCombinePartialSig {
-                sigs,
+                mut sigs,
+                sig_files,
+                tx_bytes,
                 pks,
                 weights,
                 threshold,
             } => {
+                for file in sig_files {
+                    let contents = fs::read_to_string(&file)
+                        .map_err(|e| anyhow!("Cannot read signature file {:?}: {e}", file))?;
+                    sigs.push(
+                        Signature::decode_base64(contents.trim())
+                            .map_err(|e| anyhow!("Invalid signature in {:?}: {e}", file))?,
+                    );
+                }
                 let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
                 let address: SuiAddress = multisig_pk.clone().into();
-                let multisig = MultiSig::combine(sigs, multisig_pk)?;
+                let multisig = MultiSig::combine_checked(sigs, multisig_pk)?;
+                if let Some(tx_bytes) = tx_bytes {
+                    let data: TransactionData =
+                        bcs::from_bytes(&Base64::decode(&tx_bytes).map_err(|e| {
+                            anyhow!("Cannot deserialize data as TransactionData {:?}", e)
+                        })?)?;
+                    if data.sender() != address {
+                        return Err(anyhow!(
+                            "Transaction sender {} is not the MultiSig address {address}",
+                            data.sender()
+                        ));
+                    }
+                    multisig.verify_secure_generic(
+                        &IntentMessage::new(Intent::default(), data),
+                        address,
+                    )?;
+                    println!("MultiSig verified against the transaction data");
+                }
                 let generic_sig: GenericSignature = multisig.into();
                 println!("MultiSig address: {address}");
                 println!("MultiSig parsed: {:?}", generic_sig);
                 println!("MultiSig serialized: {:?}", generic_sig.encode_base64());
             }
+            KeyToolCommand::Encrypt => {
+                let path = match keystore {
+                    Keystore::File(file) => file
+                        .path()
+                        .ok_or_else(|| anyhow!("Keystore has no file to encrypt"))?
+                        .to_path_buf(),
+                    Keystore::Encrypted(file) => {
+                        return Err(anyhow!("Keystore {:?} is already encrypted", file.path()))
+                    }
+                    Keystore::InMem(_) | Keystore::External(_) => {
+                        return Err(anyhow!("Only file based keystores can be encrypted"))
+                    }
+                };
+                let passphrase = read_new_passphrase()?;
+                *keystore = Keystore::Encrypted(EncryptedFileBasedKeystore::encrypt_in_place(
+                    &path,
+                    &passphrase,
+                )?);
+                println!("Keystore {:?} encrypted", path);
+            }
         }
 
         Ok(())
@@ -0,0 +1,351 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Programmable transaction blocks described by a script file, for `sui client ptb`.
+//!
+//! A script is a YAML (or JSON) list of steps, each holding one command and optionally naming
+//! its result so that later commands can use it:
+//! ```yaml
+//! - split-coins:
+//!     coin: gas
+//!     amounts:
+//!       - pure: { type: u64, value: 1000 }
+//!       - pure: { type: u64, value: 2000 }
+//!   result: coins
+//! - move-call:
+//!     package: "0x2"
+//!     module: pay
+//!     function: join
+//!     type-arguments: ["0x2::sui::SUI"]
+//!     arguments: [{ result: coins.0 }, { result: coins.1 }]
+//! - transfer-objects:
+//!     objects: [{ result: coins.0 }]
+//!     recipient: { address: "0x..." }
+//! ```
+//! Arguments are one of:
+//! * `gas`, the gas coin of the transaction,
+//! * `{ object: <id> }`, an object input. Shared objects are taken by mutable reference,
+//!   `{ read-only-object: <id> }` takes them by immutable reference instead,
+//! * `{ result: <name> }` or `{ result: <name>.<index> }`, the result of an earlier step, or
+//!   one of its results if the step returned several values,
+//! * `{ address: <address> }`, a pure address,
+//! * `{ pure: { type: <type>, value: <value> } }`, a pure value of a primitive type, a string
+//!   (`0x1::string::String` or `0x1::ascii::String`) or a vector of those. IDs are passed as
+//!   addresses.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::value::{MoveStructLayout, MoveTypeLayout};
+use serde::Deserialize;
+use serde_json::Value;
+use sui_json::SuiJsonValue;
+use sui_types::base_types::{
+    ObjectID, ObjectRef, SuiAddress, STD_ASCII_MODULE_NAME, STD_ASCII_STRUCT_NAME,
+    STD_UTF8_MODULE_NAME, STD_UTF8_STRUCT_NAME,
+};
+use sui_types::messages::{Argument, Command, ObjectArg, ProgrammableTransaction};
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::{parse_sui_type_tag, MOVE_STDLIB_ADDRESS};
+
+use crate::client_commands::convert_number_to_string;
+
+#[cfg(test)]
+#[path = "unit_tests/ptb_tests.rs"]
+mod ptb_tests;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct PtbScript {
+    pub steps: Vec<PtbStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PtbStep {
+    #[serde(flatten)]
+    pub command: PtbCommand,
+    /// Name under which later steps can refer to the result of this one
+    #[serde(default)]
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PtbCommand {
+    #[serde(rename_all = "kebab-case")]
+    MoveCall {
+        package: String,
+        module: String,
+        function: String,
+        #[serde(default)]
+        type_arguments: Vec<String>,
+        #[serde(default)]
+        arguments: Vec<PtbArgument>,
+    },
+    SplitCoins {
+        coin: PtbArgument,
+        amounts: Vec<PtbArgument>,
+    },
+    MergeCoins {
+        coin: PtbArgument,
+        coins: Vec<PtbArgument>,
+    },
+    TransferObjects {
+        objects: Vec<PtbArgument>,
+        recipient: PtbArgument,
+    },
+    #[serde(rename_all = "kebab-case")]
+    MakeMoveVec {
+        #[serde(default)]
+        type_argument: Option<String>,
+        elements: Vec<PtbArgument>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PtbArgument {
+    Gas,
+    Object(String),
+    ReadOnlyObject(String),
+    Result(String),
+    Address(String),
+    Pure(PureValue),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PureValue {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub value: Value,
+}
+
+impl FromStr for PtbScript {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // JSON is a subset of YAML, so this reads both formats.
+        serde_yaml::from_str(s).map_err(|e| anyhow!("Invalid PTB script: {e}"))
+    }
+}
+
+impl PtbScript {
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let script = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read PTB script {:?}: {e}", path))?;
+        script.parse()
+    }
+
+    /// Ids of all the objects used as arguments in the script, which must be resolved to object
+    /// references before the transaction can be built.
+    pub fn object_ids(&self) -> Result<BTreeSet<ObjectID>, anyhow::Error> {
+        let mut ids = BTreeSet::new();
+        for step in &self.steps {
+            for arg in step.command.arguments() {
+                if let PtbArgument::Object(id) | PtbArgument::ReadOnlyObject(id) = arg {
+                    ids.insert(parse_object_id(id)?);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Builds the programmable transaction described by the script, given the reference and
+    /// owner of every object returned by [PtbScript::object_ids].
+    pub fn build(
+        &self,
+        objects: &BTreeMap<ObjectID, (ObjectRef, Owner)>,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        ensure!(!self.steps.is_empty(), "PTB script has no commands");
+        let mut builder = PtbBuilder {
+            builder: ProgrammableTransactionBuilder::new(),
+            results: BTreeMap::new(),
+            objects,
+        };
+        for (i, step) in self.steps.iter().enumerate() {
+            let command = builder
+                .command(&step.command)
+                .map_err(|e| anyhow!("Invalid command {i} of PTB script: {e}"))?;
+            builder.builder.command(command);
+            if let Some(name) = &step.result {
+                ensure!(
+                    !name.is_empty() && !name.contains('.'),
+                    "Invalid result name {name:?}, names cannot be empty or contain '.'"
+                );
+                ensure!(
+                    builder.results.insert(name.clone(), i as u16).is_none(),
+                    "Result name {name:?} is used more than once"
+                );
+            }
+        }
+        Ok(builder.builder.finish())
+    }
+}
+
+impl PtbCommand {
+    fn arguments(&self) -> Vec<&PtbArgument> {
+        match self {
+            PtbCommand::MoveCall { arguments, .. } => arguments.iter().collect(),
+            PtbCommand::SplitCoins { coin, amounts } => {
+                std::iter::once(coin).chain(amounts).collect()
+            }
+            PtbCommand::MergeCoins { coin, coins } => std::iter::once(coin).chain(coins).collect(),
+            PtbCommand::TransferObjects { objects, recipient } => {
+                objects.iter().chain(std::iter::once(recipient)).collect()
+            }
+            PtbCommand::MakeMoveVec { elements, .. } => elements.iter().collect(),
+        }
+    }
+}
+
+struct PtbBuilder<'a> {
+    builder: ProgrammableTransactionBuilder,
+    /// Command index of every named result
+    results: BTreeMap<String, u16>,
+    objects: &'a BTreeMap<ObjectID, (ObjectRef, Owner)>,
+}
+
+impl PtbBuilder<'_> {
+    fn command(&mut self, command: &PtbCommand) -> Result<Command, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+            } => Command::move_call(
+                parse_object_id(package)?,
+                Identifier::new(module.as_str())?,
+                Identifier::new(function.as_str())?,
+                type_arguments
+                    .iter()
+                    .map(|t| parse_sui_type_tag(t))
+                    .collect::<Result<_, _>>()?,
+                self.arguments(arguments)?,
+            ),
+            PtbCommand::SplitCoins { coin, amounts } => {
+                Command::SplitCoins(self.argument(coin)?, self.arguments(amounts)?)
+            }
+            PtbCommand::MergeCoins { coin, coins } => {
+                Command::MergeCoins(self.argument(coin)?, self.arguments(coins)?)
+            }
+            PtbCommand::TransferObjects { objects, recipient } => {
+                Command::TransferObjects(self.arguments(objects)?, self.argument(recipient)?)
+            }
+            PtbCommand::MakeMoveVec {
+                type_argument,
+                elements,
+            } => Command::MakeMoveVec(
+                type_argument
+                    .as_deref()
+                    .map(parse_sui_type_tag)
+                    .transpose()?,
+                self.arguments(elements)?,
+            ),
+        })
+    }
+
+    fn arguments(&mut self, args: &[PtbArgument]) -> Result<Vec<Argument>, anyhow::Error> {
+        args.iter().map(|arg| self.argument(arg)).collect()
+    }
+
+    fn argument(&mut self, arg: &PtbArgument) -> Result<Argument, anyhow::Error> {
+        match arg {
+            PtbArgument::Gas => Ok(Argument::GasCoin),
+            PtbArgument::Object(id) => self.object(id, true),
+            PtbArgument::ReadOnlyObject(id) => self.object(id, false),
+            PtbArgument::Result(name) => {
+                let (name, index) = match name.split_once('.') {
+                    Some((name, index)) => (
+                        name,
+                        Some(
+                            index
+                                .parse::<u16>()
+                                .map_err(|e| anyhow!("Invalid result index {index:?}: {e}"))?,
+                        ),
+                    ),
+                    None => (name.as_str(), None),
+                };
+                let command = *self
+                    .results
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Unknown result {name:?}"))?;
+                Ok(match index {
+                    Some(index) => Argument::NestedResult(command, index),
+                    None => Argument::Result(command),
+                })
+            }
+            PtbArgument::Address(address) => self.builder.pure(parse_address(address)?),
+            PtbArgument::Pure(PureValue { type_, value }) => {
+                let layout = type_layout(&parse_sui_type_tag(type_)?)?;
+                let bytes = SuiJsonValue::new(convert_number_to_string(value.clone()))?
+                    .to_bcs_bytes(&layout)?;
+                Ok(self.builder.pure_bytes(bytes, false))
+            }
+        }
+    }
+
+    fn object(&mut self, id: &str, mutable: bool) -> Result<Argument, anyhow::Error> {
+        let id = parse_object_id(id)?;
+        let (object_ref, owner) = self
+            .objects
+            .get(&id)
+            .ok_or_else(|| anyhow!("Object [{id}] was not resolved"))?;
+        self.builder.obj(match owner {
+            Owner::Shared {
+                initial_shared_version,
+            } => ObjectArg::SharedObject {
+                id,
+                initial_shared_version: *initial_shared_version,
+                mutable,
+            },
+            Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
+                ObjectArg::ImmOrOwnedObject(*object_ref)
+            }
+        })
+    }
+}
+
+fn parse_object_id(id: &str) -> Result<ObjectID, anyhow::Error> {
+    ObjectID::from_str(id).map_err(|e| anyhow!("Invalid object id {id:?}: {e}"))
+}
+
+fn parse_address(address: &str) -> Result<SuiAddress, anyhow::Error> {
+    // Accept short hex literals such as `0x2`, like object ids.
+    SuiAddress::from_str(address)
+        .or_else(|_| ObjectID::from_hex_literal(address).map(SuiAddress::from))
+        .map_err(|e| anyhow!("Invalid address {address:?}: {e}"))
+}
+
+/// Layout used to encode a pure value of type `tag`.
+fn type_layout(tag: &TypeTag) -> Result<MoveTypeLayout, anyhow::Error> {
+    Ok(match tag {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(type_layout(inner)?)),
+        TypeTag::Struct(tag) if is_string(tag) => MoveTypeLayout::Struct(
+            MoveStructLayout::Runtime(vec![MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8))]),
+        ),
+        _ => bail!("Values of type {tag} cannot be passed as pure arguments"),
+    })
+}
+
+fn is_string(tag: &StructTag) -> bool {
+    tag.address == MOVE_STDLIB_ADDRESS
+        && ((tag.module.as_ident_str() == STD_UTF8_MODULE_NAME
+            && tag.name.as_ident_str() == STD_UTF8_STRUCT_NAME)
+            || (tag.module.as_ident_str() == STD_ASCII_MODULE_NAME
+                && tag.name.as_ident_str() == STD_ASCII_STRUCT_NAME))
+}
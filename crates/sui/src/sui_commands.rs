@@ -31,7 +31,7 @@ use crate::config::{SuiClientConfig, SuiEnv};
 use crate::console::start_console;
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
-use crate::keytool::KeyToolCommand;
+use crate::keytool::{unlock_keystore, KeyToolCommand};
 use crate::validator_commands::SuiValidatorCommand;
 use sui_move::{self, execute_move_command};
 
@@ -249,13 +249,25 @@ impl SuiCommand {
             SuiCommand::KeyTool { keystore_path, cmd } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
-                cmd.execute(&mut keystore)
+                let mut keystore = Keystore::open(&keystore_path)?;
+                if cmd.requires_unlocked_keystore() {
+                    unlock_keystore(&mut keystore)?;
+                }
+                let encrypt = matches!(cmd, KeyToolCommand::Encrypt);
+                cmd.execute(&mut keystore)?;
+                if encrypt {
+                    use_encrypted_keystore(
+                        &sui_config_dir()?.join(SUI_CLIENT_CONFIG),
+                        &keystore_path,
+                    )?;
+                }
+                Ok(())
             }
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config, false).await?;
-                let context = WalletContext::new(&config, None).await?;
+                let mut context = WalletContext::new(&config, None).await?;
+                unlock_keystore(&mut context.config.keystore)?;
                 start_console(context, &mut stdout(), &mut stderr()).await
             }
             SuiCommand::Client {
@@ -268,6 +280,9 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None).await?;
                 if let Some(cmd) = cmd {
+                    if cmd.requires_unlocked_keystore() {
+                        unlock_keystore(&mut context.config.keystore)?;
+                    }
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
                     // Print help
@@ -287,6 +302,8 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None).await?;
                 if let Some(cmd) = cmd {
+                    // Most validator commands submit transactions signed by the active address.
+                    unlock_keystore(&mut context.config.keystore)?;
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
                     // Print help
@@ -573,6 +590,36 @@ async fn prompt_if_no_config(
     Ok(())
 }
 
+/// Points the client config at `config_path` to the encrypted keystore at `keystore_path`, if it
+/// was using it as a plaintext keystore. The config is edited as YAML, as the plaintext keystore
+/// it refers to can no longer be loaded.
+fn use_encrypted_keystore(config_path: &Path, keystore_path: &Path) -> Result<(), anyhow::Error> {
+    if !config_path.exists() {
+        return Ok(());
+    }
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(config_path)?)?;
+    if let Some(keystore) = config.get_mut("keystore") {
+        let uses_keystore = keystore
+            .get("File")
+            .and_then(|path| path.as_str())
+            .map_or(false, |path| Path::new(path) == keystore_path);
+        if uses_keystore {
+            let mut encrypted = serde_yaml::Mapping::new();
+            encrypted.insert(
+                "Encrypted".into(),
+                keystore_path.to_string_lossy().into_owned().into(),
+            );
+            *keystore = serde_yaml::Value::Mapping(encrypted);
+            fs::write(config_path, serde_yaml::to_string(&config)?)?;
+            println!(
+                "Client config {:?} updated to use the encrypted keystore",
+                config_path
+            );
+        }
+    }
+    Ok(())
+}
+
 fn read_line() -> Result<String, anyhow::Error> {
     let mut s = String::new();
     let _ = stdout().flush();
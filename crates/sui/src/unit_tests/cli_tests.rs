@@ -5,8 +5,11 @@ use std::io::Read;
 use std::os::unix::prelude::FileExt;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
+use anyhow::anyhow;
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use serde_json::json;
+use shared_crypto::intent::Intent;
 use sui_types::object::Owner;
 use tokio::time::sleep;
 
@@ -34,9 +37,11 @@ use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_macros::sim_test;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
-    Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    Ed25519SuiSignature, EncodeDecodeBase64, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair,
+    SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
+use sui_types::messages::{TransactionData, TransactionDataAPI};
 use sui_types::SUI_FRAMEWORK_ADDRESS;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use test_utils::messages::make_transactions_with_wallet_context;
@@ -390,6 +395,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: 50000,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -515,6 +521,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: 20_000,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -552,6 +559,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        serialize_output: false,
     }
     .execute(context)
     .await;
@@ -576,6 +584,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        serialize_output: false,
     }
     .execute(context)
     .await;
@@ -601,6 +610,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -1107,6 +1117,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         to: recipient,
         object_id: obj_id,
         gas_budget: 50000,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -1206,6 +1217,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         to: recipient,
         object_id: obj_id,
         gas_budget: 50000,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -2042,3 +2054,168 @@ async fn test_get_owned_objects_owned_by_address_and_check_pagination() -> Resul
 
     Ok(())
 }
+
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coins = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(SuiObjectResponseQuery::new_with_options(
+                SuiObjectDataOptions::new(),
+            )),
+            None,
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let gas = coins.get(0).unwrap().object().unwrap().object_id;
+    let coin = coins.get(1).unwrap().object().unwrap().object_id;
+
+    let script = format!(
+        r#"
+- split-coins:
+    coin: gas
+    amounts:
+      - pure: {{ type: u64, value: 1000 }}
+      - pure: {{ type: u64, value: 2000 }}
+  result: coins
+- merge-coins:
+    coin: {{ result: coins.0 }}
+    coins: [{{ result: coins.1 }}]
+- transfer-objects:
+    objects: [{{ result: coins.0 }}, {{ object: "{coin}" }}]
+    recipient: {{ address: "{address1}" }}
+"#
+    );
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("script.yaml");
+    std::fs::write(&file, script)?;
+
+    let ptb = |dry_run, serialize_output| SuiClientCommands::Ptb {
+        file: file.clone(),
+        gas: Some(gas),
+        gas_budget: 10000,
+        gas_sponsor: None,
+        dry_run,
+        serialize_output,
+    };
+
+    let result = ptb(false, true).execute(context).await?;
+    assert!(matches!(
+        result,
+        SuiClientCommandResult::SerializeTransaction(_)
+    ));
+
+    let response = match ptb(true, false).execute(context).await? {
+        SuiClientCommandResult::DryRun(response) => response,
+        _ => panic!("Command failed"),
+    };
+    assert!(response.effects.status().is_ok());
+    // Dry running leaves the objects untouched.
+    assert_eq!(context.get_object_owner(&coin).await?, address);
+
+    let response = match ptb(false, false).execute(context).await? {
+        SuiClientCommandResult::Ptb(response) => response,
+        _ => panic!("Command failed"),
+    };
+    assert!(response.effects.unwrap().status().is_ok());
+    assert_eq!(context.get_object_owner(&coin).await?, address1);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_sponsored_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let sender = test_cluster.get_address_0();
+    let sponsor = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coin = client
+        .read_api()
+        .get_owned_objects(sender, None, None, None, None)
+        .await?
+        .data
+        .get(0)
+        .unwrap()
+        .object()?
+        .object_id;
+
+    let script = format!(
+        r#"
+- transfer-objects:
+    objects: [{{ object: "{coin}" }}]
+    recipient: {{ address: "{sponsor}" }}
+"#
+    );
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("script.yaml");
+    std::fs::write(&file, script)?;
+
+    // The sender builds the transaction, with gas paid by the sponsor.
+    let tx_bytes = match (SuiClientCommands::Ptb {
+        file: file.clone(),
+        gas: None,
+        gas_budget: 10000,
+        gas_sponsor: Some(sponsor),
+        dry_run: false,
+        serialize_output: true,
+    })
+    .execute(context)
+    .await?
+    {
+        SuiClientCommandResult::SerializeTransaction(tx_bytes) => tx_bytes,
+        _ => panic!("Command failed"),
+    };
+    let data: TransactionData =
+        bcs::from_bytes(&Base64::decode(&tx_bytes).map_err(|e| anyhow!(e))?)?;
+    assert_eq!(data.sender(), sender);
+    assert_eq!(data.gas_owner(), sponsor);
+
+    // Only the sponsor can sponsor-sign it.
+    assert!(SuiClientCommands::SponsorSign {
+        tx_bytes: tx_bytes.clone(),
+        address: Some(sender),
+    }
+    .execute(context)
+    .await
+    .is_err());
+    let sponsor_signature = match (SuiClientCommands::SponsorSign {
+        tx_bytes: tx_bytes.clone(),
+        address: Some(sponsor),
+    })
+    .execute(context)
+    .await?
+    {
+        SuiClientCommandResult::SponsorSign(response) => response.signature,
+        _ => panic!("Command failed"),
+    };
+    let sender_signature = context
+        .config
+        .keystore
+        .sign_secure(&sender, &data, Intent::default())?
+        .encode_base64();
+
+    // Signatures can be given in any order.
+    let response = match (SuiClientCommands::ExecuteSignedTx {
+        tx_bytes,
+        signatures: vec![sponsor_signature, sender_signature],
+    })
+    .execute(context)
+    .await?
+    {
+        SuiClientCommandResult::ExecuteSignedTx(response) => response,
+        _ => panic!("Command failed"),
+    };
+    let effects = response.effects.unwrap();
+    assert!(effects.status().is_ok());
+    assert_eq!(effects.gas_object().owner, Owner::AddressOwner(sponsor));
+    assert_eq!(context.get_object_owner(&coin).await?, sponsor);
+    Ok(())
+}
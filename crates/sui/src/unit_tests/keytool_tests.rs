@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::keytool::read_authority_keypair_from_file;
@@ -31,6 +32,7 @@ use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
 use sui_types::messages::TransactionData;
+use sui_types::multisig::MultiSigPublicKey;
 use tempfile::TempDir;
 
 const TEST_MNEMONIC: &str = "result crisp session latin must fruit genuine question prevent start coconut brave speak student dismiss";
@@ -368,6 +370,7 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
         address: *sender,
         data: Base64::encode(bcs::to_bytes(&tx_data)?),
         intent: Some(Intent::default().with_scope(IntentScope::PersonalMessage)),
+        output_file: None,
     }
     .execute(&mut keystore)?;
 
@@ -376,7 +379,68 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
         address: *sender,
         data: Base64::encode(bcs::to_bytes(&tx_data)?),
         intent: None,
+        output_file: None,
     }
     .execute(&mut keystore)?;
     Ok(())
 }
+
+#[test]
+fn test_multisig_combine_partial_sig_files() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new()?;
+    let mut keystore = Keystore::from(InMemKeystore::new(3));
+    let pks = keystore.keys();
+    let multisig_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 2)?;
+    let multisig_address = SuiAddress::from(multisig_pk);
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui_with_dummy_gas_price(
+        multisig_address,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        1000,
+    )
+    .unwrap();
+    let tx_bytes = Base64::encode(bcs::to_bytes(&tx_data)?);
+
+    // Every signer writes its partial signature to a file
+    let mut sig_files = vec![];
+    for pk in &pks {
+        let output_file = temp_dir
+            .path()
+            .join(format!("{}.sig", SuiAddress::from(pk)));
+        KeyToolCommand::Sign {
+            address: pk.into(),
+            data: tx_bytes.clone(),
+            intent: None,
+            output_file: Some(output_file.clone()),
+        }
+        .execute(&mut keystore)?;
+        sig_files.push(output_file);
+    }
+
+    let combine = |sig_files: Vec<PathBuf>| KeyToolCommand::MultiSigCombinePartialSig {
+        sigs: vec![],
+        sig_files,
+        tx_bytes: Some(tx_bytes.clone()),
+        pks: pks.clone(),
+        weights: vec![1, 1, 1],
+        threshold: 2,
+    };
+    // Any two signatures, in any order, reach the threshold
+    combine(vec![sig_files[2].clone(), sig_files[0].clone()]).execute(&mut keystore)?;
+    // A single signature, or the same one twice, does not
+    assert!(combine(vec![sig_files[1].clone()])
+        .execute(&mut keystore)
+        .is_err());
+    assert!(combine(vec![sig_files[1].clone(), sig_files[1].clone()])
+        .execute(&mut keystore)
+        .is_err());
+    Ok(())
+}
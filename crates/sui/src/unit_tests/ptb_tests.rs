@@ -0,0 +1,134 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sui_types::base_types::{random_object_ref, ObjectID, SuiAddress};
+use sui_types::messages::{Argument, CallArg, Command, ObjectArg};
+use sui_types::object::Owner;
+
+use super::PtbScript;
+
+#[test]
+fn test_build_ptb_script() {
+    let owned = random_object_ref();
+    let shared = ObjectID::random();
+    let recipient = SuiAddress::random_for_testing_only();
+    let script: PtbScript = format!(
+        r#"
+- split-coins:
+    coin: gas
+    amounts: [{{ pure: {{ type: u64, value: 1000 }} }}]
+  result: coin
+- move-call:
+    package: "0x2"
+    module: example
+    function: use_shared
+    type-arguments: ["0x2::sui::SUI"]
+    arguments:
+      - read-only-object: "{shared}"
+      - pure: {{ type: "vector<0x1::string::String>", value: ["a", "b"] }}
+  result: pair
+- transfer-objects:
+    objects: [{{ result: coin }}, {{ result: pair.1 }}, {{ object: "{}" }}]
+    recipient: {{ address: "{recipient}" }}
+"#,
+        owned.0
+    )
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        script.object_ids().unwrap(),
+        BTreeSet::from([shared, owned.0])
+    );
+
+    let objects = BTreeMap::from([
+        (owned.0, (owned, Owner::AddressOwner(recipient))),
+        (
+            shared,
+            (
+                random_object_ref(),
+                Owner::Shared {
+                    initial_shared_version: 1.into(),
+                },
+            ),
+        ),
+    ]);
+    let pt = script.build(&objects).unwrap();
+
+    assert_eq!(
+        pt.inputs,
+        vec![
+            CallArg::Pure(bcs::to_bytes(&1000u64).unwrap()),
+            CallArg::Object(ObjectArg::SharedObject {
+                id: shared,
+                initial_shared_version: 1.into(),
+                mutable: false,
+            }),
+            CallArg::Pure(bcs::to_bytes(&vec!["a", "b"]).unwrap()),
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(owned)),
+            CallArg::Pure(bcs::to_bytes(&recipient).unwrap()),
+        ]
+    );
+    assert_eq!(
+        pt.commands[0],
+        Command::SplitCoins(Argument::GasCoin, vec![Argument::Input(0)])
+    );
+    assert_eq!(
+        pt.commands[2],
+        Command::TransferObjects(
+            vec![
+                Argument::Result(0),
+                Argument::NestedResult(1, 1),
+                Argument::Input(3)
+            ],
+            Argument::Input(4)
+        )
+    );
+}
+
+#[test]
+fn test_invalid_ptb_script() {
+    let build = |script: &str| {
+        script
+            .parse::<PtbScript>()
+            .and_then(|script| script.build(&BTreeMap::new()))
+    };
+
+    // Results can only refer to earlier steps.
+    assert!(build(
+        r#"
+- transfer-objects:
+    objects: [{ result: coin }]
+    recipient: { address: "0x2" }
+- split-coins:
+    coin: gas
+    amounts: [{ pure: { type: u64, value: 1 } }]
+  result: coin
+"#
+    )
+    .is_err());
+
+    // Objects must be resolved before building.
+    assert!(build(
+        r#"
+- merge-coins:
+    coin: gas
+    coins: [{ object: "0x5" }]
+"#
+    )
+    .is_err());
+
+    // Only primitive values and strings can be passed as pure arguments.
+    assert!(build(
+        r#"
+- split-coins:
+    coin: gas
+    amounts: [{ pure: { type: "0x2::coin::Coin<0x2::sui::SUI>", value: 1 } }]
+"#
+    )
+    .is_err());
+
+    assert!(build("[]").is_err());
+}
@@ -15,14 +15,14 @@ use serde_json::json;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
 use sui_json_rpc_types::EventFilter;
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionEffectsAPI,
-    SuiTransactionResponse, SuiTransactionResponseOptions,
+    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionDataAPI,
+    SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
 use sui_node::SuiNode;
 use sui_tool::restore_from_db_checkpoint;
-use sui_types::base_types::{ObjectRef, SequenceNumber};
+use sui_types::base_types::{ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::crypto::{get_key_pair, SuiKeyPair};
 use sui_types::event::{Event, EventID};
 use sui_types::message_envelope::Message;
@@ -30,6 +30,7 @@ use sui_types::messages::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse, GasData,
     QuorumDriverResponse, TransactionData, TransactionKind,
 };
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::{Object, ObjectRead, Owner, PastObjectRead};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::query::TransactionFilter;
@@ -284,6 +285,49 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     )?;
     assert_eq!(txes.len(), 0);
 
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender)
+                .and(TransactionFilter::ChangedObject(transferred_object)),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(receiver)
+                .and(TransactionFilter::ChangedObject(transferred_object)),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes.len(), 0);
+
+    // The union is deduplicated and paginated in sequence order.
+    let filter = TransactionFilter::ChangedObject(transferred_object)
+        .or(TransactionFilter::FromAddress(sender));
+    let txes = node
+        .state()
+        .get_transactions(Some(filter.clone()), None, None, false)?;
+    assert_eq!(txes.len(), 2);
+    assert_eq!(txes[1], digest);
+    let first_page = node
+        .state()
+        .get_transactions(Some(filter.clone()), None, Some(1), false)?;
+    assert_eq!(first_page, vec![txes[0]]);
+    let second_page =
+        node.state()
+            .get_transactions(Some(filter.clone()), Some(txes[0]), Some(1), false)?;
+    assert_eq!(second_page, vec![digest]);
+    let reversed = node
+        .state()
+        .get_transactions(Some(filter), Some(digest), None, true)?;
+    assert_eq!(reversed, vec![txes[0]]);
+
     // timestamp is recorded
     let ts = node.state().get_timestamp_ms(&digest).await?;
     assert!(ts.is_some());
@@ -421,6 +465,109 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_checkpoint_and_time_range_filters() -> Result<(), anyhow::Error> {
+    telemetry_subscribers::init_for_testing();
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let node = &test_cluster.fullnode_handle.sui_node;
+    let context = &mut test_cluster.wallet;
+
+    let (_, sender, _, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // The checkpoint index is written once the checkpoint including the tx is executed.
+    let all_checkpoints = TransactionFilter::Checkpoint {
+        start: 0,
+        end: CheckpointSequenceNumber::MAX,
+    };
+    timeout(Duration::from_secs(60), async {
+        while !node
+            .state()
+            .get_transactions(Some(all_checkpoints.clone()), None, None, false)
+            .unwrap()
+            .contains(&digest)
+        {
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await?;
+
+    let (_, checkpoint) = node
+        .state()
+        .database
+        .get_transaction_checkpoint(&digest)?
+        .unwrap();
+    let timestamp_ms = node
+        .state()
+        .get_checkpoint_summary_by_sequence_number(checkpoint)?
+        .timestamp_ms;
+
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::Checkpoint {
+            start: checkpoint,
+            end: checkpoint + 1,
+        }),
+        None,
+        None,
+        false,
+    )?;
+    assert!(txes.contains(&digest));
+
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::Checkpoint {
+            start: checkpoint + 1,
+            end: CheckpointSequenceNumber::MAX,
+        }),
+        None,
+        None,
+        false,
+    )?;
+    assert!(!txes.contains(&digest));
+
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender).and(TransactionFilter::TimeRange {
+                start_ms: timestamp_ms,
+                end_ms: timestamp_ms + 1,
+            }),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender).and(TransactionFilter::TimeRange {
+                start_ms: 0,
+                end_ms: timestamp_ms,
+            }),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert!(txes.is_empty());
+
+    // Nested in `Or`, the transactions of the range are read from the checkpoint index.
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::Checkpoint {
+                start: checkpoint,
+                end: checkpoint + 1,
+            }
+            .or(TransactionFilter::ToAddress(SuiAddress::ZERO)),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert!(txes.contains(&digest));
+
+    Ok(())
+}
+
 // Test for syncing a node to an authority that already has many txes.
 #[sim_test]
 async fn test_full_node_cold_sync() -> Result<(), anyhow::Error> {
@@ -645,6 +792,145 @@ async fn test_full_node_sub_and_query_move_event_ok() -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_resume_event_subscription_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+
+    let fullnode = start_fullnode_from_config(
+        test_cluster
+            .fullnode_config_builder()
+            .with_event_store()
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+
+    let context = &mut test_cluster.wallet;
+    let package_id = publish_nfts_package(context, /* sender */ None).await.0;
+    let struct_tag = parse_struct_tag(&format!("{package_id}::devnet_nft::MintNFTEvent")).unwrap();
+
+    // Mint two NFTs before subscribing, the subscription resumes after the first one.
+    let mut digests = vec![];
+    for _ in 0..2 {
+        let (_, _, digest) = create_devnet_nft(context, package_id).await?;
+        wait_for_tx(digest, node.state().clone()).await;
+        digests.push(digest);
+    }
+    // A small delay is needed for post processing operations following the transaction to finish.
+    sleep(Duration::from_secs(1)).await;
+
+    let cursor = EventID {
+        tx_digest: digests[0],
+        event_seq: 0,
+    };
+    let mut sub: Subscription<SuiEvent> = ws_client
+        .subscribe(
+            "suix_subscribeEvent",
+            rpc_params![EventFilter::MoveEventType(struct_tag.clone()), cursor],
+            "suix_unsubscribeEvent",
+        )
+        .await
+        .unwrap();
+
+    let (_, _, digest) = create_devnet_nft(context, package_id).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+    digests.push(digest);
+
+    // The replayed event comes first, followed by the live one, each exactly once.
+    for expected in &digests[1..] {
+        match timeout(Duration::from_secs(5), sub.next()).await {
+            Ok(Some(Ok(event))) => {
+                assert_eq!(&event.id.tx_digest, expected);
+                assert_eq!(event.type_, struct_tag);
+            }
+            other => panic!("Failed to get SuiEvent, but {:?}", other),
+        }
+    }
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because no new events are coming in. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_full_node_sub_transaction_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+
+    let fullnode = start_fullnode_from_config(
+        test_cluster
+            .fullnode_config_builder()
+            .with_event_store()
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+
+    let context = &mut test_cluster.wallet;
+    let package_id = publish_nfts_package(context, /* sender */ None).await.0;
+
+    let mut sub: Subscription<SuiTransactionResponse> = ws_client
+        .subscribe(
+            "suix_subscribeTransaction",
+            rpc_params![TransactionFilter::MoveFunction {
+                package: package_id,
+                module: Some("devnet_nft".to_string()),
+                function: Some("mint".to_string()),
+            }],
+            "suix_unsubscribeTransaction",
+        )
+        .await
+        .unwrap();
+
+    let (sender, object_id, digest) = create_devnet_nft(context, package_id).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Ok(Some(Ok(tx))) => {
+            assert_eq!(tx.digest, digest);
+            let transaction = tx.transaction.unwrap();
+            assert_eq!(transaction.data.sender(), &sender);
+            let effects = tx.effects.unwrap();
+            assert!(effects
+                .created()
+                .iter()
+                .any(|o| o.reference.object_id == object_id));
+            assert_eq!(tx.events.unwrap().data.len(), 1);
+        }
+        other => panic!("Failed to get SuiTransactionResponse, but {:?}", other),
+    };
+
+    // Transactions not matching the filter are not streamed
+    transfer_coin(context).await?;
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because no matching transactions are coming in. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
 // Test fullnode has event read jsonrpc endpoints working
 #[sim_test]
 async fn test_full_node_event_read_api_ok() {
@@ -289,6 +289,7 @@ pub async fn create_devnet_nft(
         args,
         gas: Some(*gas_object),
         gas_budget: GAS_BUDGET,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -395,6 +396,7 @@ pub async fn transfer_coin(
         object_id: object_to_send,
         gas: None,
         gas_budget: GAS_BUDGET,
+        serialize_output: false,
     }
     .execute(context)
     .await?;
@@ -175,6 +175,19 @@ pub struct Parameters {
     /// Anemo network settings.
     #[serde(default = "AnemoParameters::default")]
     pub anemo: AnemoParameters,
+    /// The protocol the primary orders certificates with, when it runs consensus internally.
+    /// Every authority of the committee must run the same protocol: authorities running different
+    /// ones commit different sub dags, and fork without noticing. Only Bullshark is meant for
+    /// production, and primaries warn when started with another protocol.
+    #[serde(default)]
+    pub consensus_protocol: ConsensusProtocolKind,
+    /// How batches are compressed when broadcast to, or fetched from, workers that support it.
+    #[serde(default)]
+    pub batch_compression: BatchCompression,
+    /// The parameters for the gRPC server streaming the committed sub dags, which only runs when
+    /// set and the primary runs consensus internally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus_commits_grpc: Option<ConsensusCommitsGrpcParameters>,
 }
 
 impl Parameters {
@@ -219,6 +232,22 @@ impl Parameters {
     }
 }
 
+/// The consensus protocols a primary can run.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ConsensusProtocolKind {
+    #[default]
+    Bullshark,
+    Tusk,
+}
+
+/// The compressions of the batches exchanged by workers.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum BatchCompression {
+    #[default]
+    Uncompressed,
+    Zstd,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NetworkAdminServerParameters {
     /// Primary network admin server port number
@@ -336,6 +365,37 @@ impl ConsensusAPIGrpcParameters {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConsensusCommitsGrpcParameters {
+    /// Socket address the server should be listening to.
+    pub socket_addr: Multiaddr,
+    /// The timeout configuration when requesting the payloads of the committed sub dags from
+    /// workers.
+    #[serde(with = "duration_format")]
+    pub get_batches_timeout: Duration,
+}
+
+impl Default for ConsensusCommitsGrpcParameters {
+    fn default() -> Self {
+        let host = "127.0.0.1";
+        Self {
+            socket_addr: format!("/ip4/{}/tcp/{}/http", host, get_available_port(host))
+                .parse()
+                .unwrap(),
+            get_batches_timeout: Duration::from_millis(5_000),
+        }
+    }
+}
+
+impl ConsensusCommitsGrpcParameters {
+    fn with_available_port(&self) -> Self {
+        let mut params = self.clone();
+        let default = Self::default();
+        params.socket_addr = default.socket_addr;
+        params
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct BlockSynchronizerParameters {
@@ -431,6 +491,9 @@ impl Default for Parameters {
             prometheus_metrics: PrometheusMetricsParameters::default(),
             network_admin_server: NetworkAdminServerParameters::default(),
             anemo: AnemoParameters::default(),
+            consensus_protocol: ConsensusProtocolKind::default(),
+            batch_compression: BatchCompression::default(),
+            consensus_commits_grpc: None,
         }
     }
 }
@@ -441,6 +504,10 @@ impl Parameters {
         params.consensus_api_grpc = params.consensus_api_grpc.with_available_port();
         params.prometheus_metrics = params.prometheus_metrics.with_available_port();
         params.network_admin_server = params.network_admin_server.with_available_port();
+        params.consensus_commits_grpc = params
+            .consensus_commits_grpc
+            .as_ref()
+            .map(ConsensusCommitsGrpcParameters::with_available_port);
         params
     }
 
@@ -531,6 +598,18 @@ impl Parameters {
             self.network_admin_server
                 .worker_network_admin_server_base_port
         );
+        info!("Consensus protocol set to {:?}", self.consensus_protocol);
+        info!("Batch compression set to {:?}", self.batch_compression);
+        if let Some(consensus_commits_grpc) = &self.consensus_commits_grpc {
+            info!(
+                "Consensus commits gRPC Server set to listen on {}",
+                consensus_commits_grpc.socket_addr
+            );
+            info!(
+                "Get batches timeout set to {} ms",
+                consensus_commits_grpc.get_batches_timeout.as_millis()
+            );
+        }
     }
 }
 
@@ -4,9 +4,11 @@
 
 #![allow(clippy::mutable_key_type)]
 
+use crate::bullshark::Bullshark;
+use crate::tusk::Tusk;
 use crate::utils::gc_round;
 use crate::{metrics::ConsensusMetrics, ConsensusError, Outcome, SequenceNumber};
-use config::{AuthorityIdentifier, Committee};
+use config::{AuthorityIdentifier, Committee, ConsensusProtocolKind};
 use fastcrypto::hash::Hash;
 use mysten_metrics::spawn_logged_monitored_task;
 use std::{
@@ -241,6 +243,37 @@ pub trait ConsensusProtocol {
     ) -> Result<(Outcome, Vec<CommittedSubDag>), ConsensusError>;
 }
 
+impl<P: ConsensusProtocol + ?Sized> ConsensusProtocol for Box<P> {
+    fn process_certificate(
+        &mut self,
+        state: &mut ConsensusState,
+        certificate: Certificate,
+    ) -> Result<(Outcome, Vec<CommittedSubDag>), ConsensusError> {
+        (**self).process_certificate(state, certificate)
+    }
+}
+
+/// Creates the consensus protocol of the given kind.
+pub fn make_consensus_protocol(
+    kind: ConsensusProtocolKind,
+    committee: Committee,
+    store: Arc<ConsensusStore>,
+    metrics: Arc<ConsensusMetrics>,
+    gc_depth: Round,
+    // The number of committed sub dags after which Bullshark changes its leader schedule.
+    num_sub_dags_per_schedule: u64,
+) -> Box<dyn ConsensusProtocol + Send> {
+    match kind {
+        ConsensusProtocolKind::Bullshark => Box::new(Bullshark::new(
+            committee,
+            store,
+            metrics,
+            num_sub_dags_per_schedule,
+        )),
+        ConsensusProtocolKind::Tusk => Box::new(Tusk::new(committee, store, gc_depth)),
+    }
+}
+
 /// Holds information about a committed round in consensus. When a certificate gets committed then
 /// the corresponding certificate's round is considered a "committed" round. It bears both the
 /// committed round and the corresponding garbage collection round.
@@ -272,7 +305,8 @@ impl ConsensusRound {
     /// the updated instance.
     fn update(&self, new_committed_round: Round, gc_depth: Round) -> Self {
         let last_committed_round = max(self.committed_round, new_committed_round);
-        let last_gc_round = gc_round(last_committed_round, gc_depth);
+        // The GC round never goes back, also when the state starts at a round it did not commit.
+        let last_gc_round = max(self.gc_round, gc_round(last_committed_round, gc_depth));
 
         ConsensusRound {
             committed_round: last_committed_round,
@@ -8,12 +8,19 @@
 )]
 
 pub mod bullshark;
+#[cfg(test)]
+#[path = "tests/conformance_tests.rs"]
+mod conformance_tests;
 pub mod consensus;
 #[cfg(test)]
 #[path = "tests/consensus_utils.rs"]
 pub mod consensus_utils;
 pub mod dag;
 pub mod metrics;
+pub mod replay;
+#[cfg(test)]
+#[path = "tests/replay_tests.rs"]
+mod replay_tests;
 pub mod tusk;
 pub mod utils;
 
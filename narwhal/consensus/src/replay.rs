@@ -0,0 +1,179 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline replay of the certificates stored by a node, to reproduce the sub dags its consensus
+//! committed and the leader elections that led to them.
+
+use crate::consensus::{make_consensus_protocol, ConsensusRound, ConsensusState};
+use crate::metrics::ConsensusMetrics;
+use crate::{ConsensusError, Outcome};
+use config::{AuthorityIdentifier, Committee, ConsensusProtocolKind};
+use fastcrypto::hash::Hash;
+use prometheus::Registry;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
+use storage::CertificateStore;
+use types::{Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore, Round};
+
+/// The decision a consensus protocol took on a leader when processing a certificate.
+#[derive(Debug)]
+pub struct LeaderElection {
+    /// The round of the processed certificate.
+    pub round: Round,
+    /// The author of the processed certificate.
+    pub origin: AuthorityIdentifier,
+    pub certificate: CertificateDigest,
+    pub outcome: Outcome,
+}
+
+/// What a consensus protocol did with a sequence of certificates.
+#[derive(Debug, Default)]
+pub struct Replay {
+    /// The leader elections attempted while processing the certificates, in processing order.
+    pub elections: Vec<LeaderElection>,
+    /// The committed sub dags, in commit order.
+    pub sub_dags: Vec<CommittedSubDagShell>,
+}
+
+/// A difference between the sub dags a node committed and the ones committed by a replay.
+#[derive(Debug)]
+pub enum CommitDiff {
+    /// Both committed a sub dag for the leader round, but with a different leader or different
+    /// certificates.
+    Mismatch {
+        recorded: CommittedSubDagShell,
+        replayed: CommittedSubDagShell,
+    },
+    /// The node committed a sub dag for a leader round the replay did not commit.
+    MissingFromReplay(CommittedSubDagShell),
+    /// The replay committed a sub dag for a leader round the node did not commit.
+    UnexpectedInReplay(CommittedSubDagShell),
+}
+
+/// Reads the certificates of rounds `from_round` to `to_round` (both included) from the store,
+/// sorted by round.
+pub fn export_certificates(
+    store: &CertificateStore,
+    from_round: Round,
+    to_round: Round,
+) -> Result<Vec<Certificate>, ConsensusError> {
+    let mut certificates = store.after_round(from_round)?;
+    certificates.retain(|x| x.round() <= to_round);
+    Ok(certificates)
+}
+
+/// Reads the sub dags the node committed for the leaders of rounds `from_round` to `to_round`
+/// (both included), in commit order.
+pub fn recorded_commits(
+    store: &ConsensusStore,
+    from_round: Round,
+    to_round: Round,
+) -> Result<Vec<CommittedSubDagShell>, ConsensusError> {
+    let mut sub_dags = store.read_committed_sub_dags_from(&0)?;
+    sub_dags.retain(|x| (from_round..=to_round).contains(&x.leader_round));
+    Ok(sub_dags)
+}
+
+/// Feeds the certificates, sorted by round, to a fresh instance of the consensus protocol.
+///
+/// The replay starts at the round of the first certificate: the rounds before it are considered
+/// garbage collected, so the parents of the first round are not needed. The protocol persists its
+/// commits to `store`, which should be a scratch store rather than the store of the node.
+pub fn replay(
+    kind: ConsensusProtocolKind,
+    committee: &Committee,
+    certificates: Vec<Certificate>,
+    store: Arc<ConsensusStore>,
+    gc_depth: Round,
+    num_sub_dags_per_schedule: u64,
+) -> Result<Replay, ConsensusError> {
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let mut state = ConsensusState::new(metrics.clone(), committee, gc_depth);
+    if let Some(first_round) = certificates.first().map(|x| x.round()) {
+        let start_round = first_round.saturating_sub(1);
+        state.last_round = ConsensusRound::new(start_round, start_round);
+    }
+    let mut protocol = make_consensus_protocol(
+        kind,
+        committee.clone(),
+        store,
+        metrics,
+        gc_depth,
+        num_sub_dags_per_schedule,
+    );
+
+    let mut replay = Replay::default();
+    for certificate in certificates {
+        let (round, origin, digest) = (
+            certificate.round(),
+            certificate.origin(),
+            certificate.digest(),
+        );
+        let (outcome, sub_dags) = protocol.process_certificate(&mut state, certificate)?;
+        if !matches!(
+            outcome,
+            Outcome::NoLeaderElectedForOddRound | Outcome::CertificateBelowCommitRound
+        ) {
+            replay.elections.push(LeaderElection {
+                round,
+                origin,
+                certificate: digest,
+                outcome,
+            });
+        }
+        replay
+            .sub_dags
+            .extend(sub_dags.iter().map(CommittedSubDagShell::from_sub_dag));
+    }
+    Ok(replay)
+}
+
+/// Compares the sub dags a node committed with the ones committed by a replay, leader round by
+/// leader round. Sub dag indexes are not compared, since a replay numbers its sub dags from 1.
+///
+/// Only the recorded certificates that are part of the replayed `certificates` are compared, so
+/// that a replay that does not start at round 1 matches the sub dags whose history goes back
+/// further than its first round.
+pub fn diff_commits(
+    recorded: &[CommittedSubDagShell],
+    replayed: &[CommittedSubDagShell],
+    certificates: &[Certificate],
+) -> Vec<CommitDiff> {
+    let replayed_certificates: HashSet<_> = certificates.iter().map(|x| x.digest()).collect();
+    let recorded_by_round: BTreeMap<_, _> = recorded.iter().map(|x| (x.leader_round, x)).collect();
+    let replayed_by_round: BTreeMap<_, _> = replayed.iter().map(|x| (x.leader_round, x)).collect();
+
+    let rounds: BTreeSet<_> = recorded_by_round
+        .keys()
+        .chain(replayed_by_round.keys())
+        .copied()
+        .collect();
+
+    let mut diffs = Vec::new();
+    for round in rounds {
+        match (recorded_by_round.get(&round), replayed_by_round.get(&round)) {
+            (Some(recorded), Some(replayed)) => {
+                let recorded_certificates: HashSet<_> = recorded
+                    .certificates
+                    .iter()
+                    .filter(|x| replayed_certificates.contains(x))
+                    .collect();
+                let certificates: HashSet<_> = replayed.certificates.iter().collect();
+                if recorded.leader != replayed.leader || recorded_certificates != certificates {
+                    diffs.push(CommitDiff::Mismatch {
+                        recorded: (*recorded).clone(),
+                        replayed: (*replayed).clone(),
+                    });
+                }
+            }
+            (Some(recorded), None) => {
+                diffs.push(CommitDiff::MissingFromReplay((*recorded).clone()))
+            }
+            (None, Some(replayed)) => {
+                diffs.push(CommitDiff::UnexpectedInReplay((*replayed).clone()))
+            }
+            (None, None) => unreachable!("Every round comes from one of the sequences"),
+        }
+    }
+    diffs
+}
@@ -0,0 +1,243 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that a consensus protocol behaves the same on the same randomized DAGs, whichever order
+//! the certificates are delivered in. Every protocol the primary can be configured with should
+//! have a test here.
+
+#![allow(clippy::mutable_key_type)]
+
+use crate::consensus::{make_consensus_protocol, ConsensusProtocol, ConsensusState};
+use crate::consensus_utils::make_consensus_store;
+use crate::metrics::ConsensusMetrics;
+use config::{AuthorityIdentifier, Committee, ConsensusProtocolKind, Stake};
+use fastcrypto::hash::Hash;
+use prometheus::Registry;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use test_utils::CommitteeFixture;
+use types::{Certificate, CertificateDigest, CommittedSubDag, HeaderAPI, ReputationScores, Round};
+
+/// The seeds of the DAGs every protocol is checked against.
+const SEEDS: [u64; 5] = [0, 1, 2, 3, 4];
+/// The number of rounds of each DAG. It is kept below the GC depth, so that no part of the DAG
+/// gets garbage collected.
+const ROUNDS: Round = 30;
+/// The number of nodes each DAG is delivered to, each in a different order.
+const NODES: usize = 4;
+const GC_DEPTH: Round = 50;
+const NUM_SUB_DAGS_PER_SCHEDULE: u64 = 100;
+/// A schedule window short enough for the schedule to change several times within a DAG.
+const SHORT_NUM_SUB_DAGS_PER_SCHEDULE: u64 = 2;
+
+/// What a node committed in a sub dag.
+#[derive(Debug, PartialEq, Eq)]
+struct CommittedDigests {
+    sub_dag_index: u64,
+    leader_round: Round,
+    leader: CertificateDigest,
+    certificates: Vec<CertificateDigest>,
+    reputation_score: ReputationScores,
+}
+
+impl From<CommittedSubDag> for CommittedDigests {
+    fn from(sub_dag: CommittedSubDag) -> Self {
+        Self {
+            sub_dag_index: sub_dag.sub_dag_index,
+            leader_round: sub_dag.leader.round(),
+            leader: sub_dag.leader.digest(),
+            certificates: sub_dag.certificates.iter().map(|x| x.digest()).collect(),
+            reputation_score: sub_dag.reputation_score,
+        }
+    }
+}
+
+#[tokio::test]
+async fn bullshark_conformance() {
+    check_conformance(ConsensusProtocolKind::Bullshark, NUM_SUB_DAGS_PER_SCHEDULE);
+}
+
+#[tokio::test]
+async fn bullshark_conformance_with_schedule_changes() {
+    let outputs = check_conformance(
+        ConsensusProtocolKind::Bullshark,
+        SHORT_NUM_SUB_DAGS_PER_SCHEDULE,
+    );
+
+    // The schedule changed, and the scores only grew within each schedule.
+    for output in outputs {
+        assert!(output
+            .iter()
+            .any(|sub_dag| sub_dag.reputation_score.final_of_schedule));
+        for pair in output.windows(2) {
+            if pair[0].reputation_score.final_of_schedule {
+                continue;
+            }
+            for (id, score) in &pair[0].reputation_score.scores_per_authority {
+                assert!(pair[1].reputation_score.scores_per_authority[id] >= *score);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn tusk_conformance() {
+    check_conformance(ConsensusProtocolKind::Tusk, NUM_SUB_DAGS_PER_SCHEDULE);
+}
+
+/// Returns what the nodes committed on the DAG of each seed.
+fn check_conformance(
+    kind: ConsensusProtocolKind,
+    num_sub_dags_per_schedule: u64,
+) -> Vec<Vec<CommittedDigests>> {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let ids: Vec<_> = fixture.authorities().map(|a| a.id()).collect();
+
+    let mut committed_per_seed = Vec::new();
+    for seed in SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dag = random_dag(&committee, &ids, &mut rng);
+
+        let outputs: Vec<_> = (0..NODES)
+            .map(|_| {
+                run_node(
+                    kind,
+                    num_sub_dags_per_schedule,
+                    &committee,
+                    random_causal_order(&dag, &mut rng),
+                )
+            })
+            .collect();
+
+        // Safety: every node commits the same sub dags, in the same order.
+        for output in &outputs[1..] {
+            assert_eq!(
+                output, &outputs[0],
+                "{kind:?} nodes diverged on the DAG of seed {seed}"
+            );
+        }
+
+        // No certificate is committed twice.
+        let committed: Vec<_> = outputs[0]
+            .iter()
+            .flat_map(|sub_dag| sub_dag.certificates.iter())
+            .collect();
+        let unique: HashSet<_> = committed.iter().collect();
+        assert_eq!(
+            unique.len(),
+            committed.len(),
+            "{kind:?} committed a certificate twice on the DAG of seed {seed}"
+        );
+
+        // Liveness: leaders keep being committed up to the last few rounds of the DAG, which lack
+        // the descendants needed to commit them.
+        let last_leader_round = outputs[0]
+            .last()
+            .map(|sub_dag| sub_dag.leader_round)
+            .unwrap_or_default();
+        assert!(
+            last_leader_round + 6 >= ROUNDS,
+            "{kind:?} stopped committing at round {last_leader_round} on the DAG of seed {seed}"
+        );
+        committed_per_seed.extend(outputs.into_iter().next());
+    }
+    committed_per_seed
+}
+
+/// Makes `ROUNDS` rounds of certificates, one per authority and round. Every certificate refers to
+/// its author's certificate of the previous round, and to randomly picked other certificates of
+/// that round, until it has parents worth a quorum of stake.
+fn random_dag(
+    committee: &Committee,
+    ids: &[AuthorityIdentifier],
+    rng: &mut StdRng,
+) -> Vec<Certificate> {
+    let mut certificates = Vec::new();
+    let mut parents = Certificate::genesis(committee);
+
+    for round in 1..=ROUNDS {
+        let mut next_parents = Vec::new();
+        for id in ids {
+            let mut candidates: Vec<_> = parents.iter().collect();
+            candidates.shuffle(rng);
+            // Our own certificate goes first.
+            candidates.sort_by_key(|x| x.origin() != *id);
+
+            let mut stake: Stake = 0;
+            let mut this_cert_parents = BTreeSet::new();
+            for parent in candidates {
+                // Certificates of the first round refer to the whole genesis.
+                if round > 1 && stake >= committee.quorum_threshold() {
+                    break;
+                }
+                stake += committee.stake_by_id(parent.origin());
+                this_cert_parents.insert(parent.digest());
+            }
+
+            let (_, certificate) =
+                test_utils::mock_certificate(committee, *id, round, this_cert_parents);
+            next_parents.push(certificate);
+        }
+        certificates.extend(next_parents.iter().cloned());
+        parents = next_parents;
+    }
+    certificates
+}
+
+/// Shuffles the certificates in a random order in which every certificate comes after its parents.
+fn random_causal_order(certificates: &[Certificate], rng: &mut StdRng) -> Vec<Certificate> {
+    let mut delivered = HashSet::new();
+    let mut pending: Vec<_> = certificates.iter().collect();
+    let mut ordered = Vec::with_capacity(certificates.len());
+
+    while !pending.is_empty() {
+        let ready: Vec<_> = pending
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| {
+                x.round() == 1
+                    || x.header
+                        .parents()
+                        .iter()
+                        .all(|parent| delivered.contains(parent))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let next = *ready.choose(rng).expect("The DAG should have no cycles");
+        let certificate = pending.swap_remove(next);
+        delivered.insert(certificate.digest());
+        ordered.push(certificate.clone());
+    }
+    ordered
+}
+
+/// Feeds the certificates to a fresh instance of the protocol, and returns what it committed.
+fn run_node(
+    kind: ConsensusProtocolKind,
+    num_sub_dags_per_schedule: u64,
+    committee: &Committee,
+    certificates: Vec<Certificate>,
+) -> Vec<CommittedDigests> {
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let mut state = ConsensusState::new(metrics.clone(), committee, GC_DEPTH);
+    let mut protocol = make_consensus_protocol(
+        kind,
+        committee.clone(),
+        store,
+        metrics,
+        GC_DEPTH,
+        num_sub_dags_per_schedule,
+    );
+
+    let mut committed = Vec::new();
+    for certificate in certificates {
+        let (_, sub_dags) = protocol
+            .process_certificate(&mut state, certificate)
+            .unwrap();
+        committed.extend(sub_dags.into_iter().map(CommittedDigests::from));
+    }
+    committed
+}
@@ -0,0 +1,161 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![allow(clippy::mutable_key_type)]
+
+use crate::consensus::{make_consensus_protocol, ConsensusState};
+use crate::consensus_utils::{make_certificate_store, make_consensus_store};
+use crate::metrics::ConsensusMetrics;
+use crate::replay::{diff_commits, export_certificates, recorded_commits, replay, CommitDiff};
+use crate::Outcome;
+use config::{AuthorityIdentifier, Committee, ConsensusProtocolKind};
+use fastcrypto::hash::Hash;
+use prometheus::Registry;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use storage::CertificateStore;
+use test_utils::CommitteeFixture;
+use types::{Certificate, ConsensusStore, Round};
+
+const GC_DEPTH: Round = 50;
+const NUM_SUB_DAGS_PER_SCHEDULE: u64 = 100;
+
+/// Runs Bullshark on `rounds` rounds of certificates in ideal conditions, the way a node would,
+/// and returns the stores it left behind.
+fn run_node(
+    committee: &Committee,
+    ids: &[AuthorityIdentifier],
+    rounds: Round,
+) -> (CertificateStore, Arc<ConsensusStore>) {
+    let genesis = Certificate::genesis(committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (certificates, _) =
+        test_utils::make_optimal_certificates(committee, 1..=rounds, &genesis, ids);
+
+    let certificate_store = make_certificate_store(&test_utils::temp_dir());
+    let consensus_store = make_consensus_store(&test_utils::temp_dir());
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let mut state = ConsensusState::new(metrics.clone(), committee, GC_DEPTH);
+    let mut protocol = make_consensus_protocol(
+        ConsensusProtocolKind::Bullshark,
+        committee.clone(),
+        consensus_store.clone(),
+        metrics,
+        GC_DEPTH,
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    );
+    for certificate in certificates {
+        certificate_store.write(certificate.clone()).unwrap();
+        protocol
+            .process_certificate(&mut state, certificate)
+            .unwrap();
+    }
+    (certificate_store, consensus_store)
+}
+
+#[tokio::test]
+async fn replay_matches_recorded_commits() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let ids: Vec<_> = fixture.authorities().map(|a| a.id()).collect();
+    let (certificate_store, consensus_store) = run_node(&committee, &ids, 10);
+
+    let certificates = export_certificates(&certificate_store, 1, 10).unwrap();
+    assert_eq!(certificates.len(), 10 * ids.len());
+    let recorded = recorded_commits(&consensus_store, 1, 10).unwrap();
+    assert!(!recorded.is_empty());
+
+    let replayed = replay(
+        ConsensusProtocolKind::Bullshark,
+        &committee,
+        certificates.clone(),
+        make_consensus_store(&test_utils::temp_dir()),
+        GC_DEPTH,
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    )
+    .unwrap();
+
+    // Leaders of rounds 2 to 8 are committed once round 9 has enough support for them.
+    let leader_rounds: Vec<_> = replayed.sub_dags.iter().map(|x| x.leader_round).collect();
+    assert_eq!(leader_rounds, vec![2, 4, 6, 8]);
+    assert!(replayed
+        .elections
+        .iter()
+        .any(|x| x.outcome == Outcome::Commit));
+
+    let diffs = diff_commits(&recorded, &replayed.sub_dags, &certificates);
+    assert!(diffs.is_empty(), "{diffs:?}");
+}
+
+#[tokio::test]
+async fn replay_from_the_middle_of_the_dag() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let ids: Vec<_> = fixture.authorities().map(|a| a.id()).collect();
+    let (certificate_store, consensus_store) = run_node(&committee, &ids, 10);
+
+    // The parents of round 5 are not replayed.
+    let certificates = export_certificates(&certificate_store, 5, 10).unwrap();
+    assert!(certificates.iter().all(|x| (5..=10).contains(&x.round())));
+    let recorded = recorded_commits(&consensus_store, 5, 10).unwrap();
+
+    let replayed = replay(
+        ConsensusProtocolKind::Bullshark,
+        &committee,
+        certificates.clone(),
+        make_consensus_store(&test_utils::temp_dir()),
+        GC_DEPTH,
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    )
+    .unwrap();
+
+    let leader_rounds: Vec<_> = replayed.sub_dags.iter().map(|x| x.leader_round).collect();
+    assert_eq!(leader_rounds, vec![6, 8]);
+
+    // The sub dags match the recorded ones, but for the certificates of the rounds before 5.
+    assert!(recorded[0].certificates.len() > replayed.sub_dags[0].certificates.len());
+    let diffs = diff_commits(&recorded, &replayed.sub_dags, &certificates);
+    assert!(diffs.is_empty(), "{diffs:?}");
+}
+
+#[tokio::test]
+async fn diff_reports_missing_and_unexpected_commits() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let ids: Vec<_> = fixture.authorities().map(|a| a.id()).collect();
+    let (certificate_store, consensus_store) = run_node(&committee, &ids, 10);
+
+    let certificates = export_certificates(&certificate_store, 1, 10).unwrap();
+    let recorded = recorded_commits(&consensus_store, 1, 10).unwrap();
+
+    // Replaying fewer rounds cannot commit the last leaders.
+    let truncated: Vec<_> = certificates
+        .iter()
+        .filter(|x| x.round() <= 7)
+        .cloned()
+        .collect();
+    let replayed = replay(
+        ConsensusProtocolKind::Bullshark,
+        &committee,
+        truncated.clone(),
+        make_consensus_store(&test_utils::temp_dir()),
+        GC_DEPTH,
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    )
+    .unwrap();
+    let diffs = diff_commits(&recorded, &replayed.sub_dags, &truncated);
+    assert_eq!(diffs.len(), 1, "{diffs:?}");
+    assert!(matches!(&diffs[0], CommitDiff::MissingFromReplay(x) if x.leader_round == 8));
+
+    // And the other way around.
+    let diffs = diff_commits(&recorded[..2], &recorded, &certificates);
+    let unexpected: Vec<_> = diffs
+        .iter()
+        .map(|x| match x {
+            CommitDiff::UnexpectedInReplay(x) => x.leader_round,
+            _ => panic!("Unexpected diff {x:?}"),
+        })
+        .collect();
+    assert_eq!(unexpected, vec![6, 8]);
+}
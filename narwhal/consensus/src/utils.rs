@@ -59,7 +59,10 @@ fn linked(leader: &Certificate, prev_leader: &Certificate, dag: &Dag) -> bool {
 pub fn order_dag(leader: &Certificate, state: &ConsensusState) -> Vec<Certificate> {
     debug!("Processing sub-dag of {:?}", leader);
     assert!(leader.round() > 0);
-    let gc_round = leader.round().saturating_sub(state.gc_depth);
+    let gc_round = leader
+        .round()
+        .saturating_sub(state.gc_depth)
+        .max(state.last_round.gc_round);
 
     let mut ordered = Vec::new();
     let mut already_ordered = HashSet::new();
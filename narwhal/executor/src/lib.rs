@@ -12,7 +12,7 @@ use tracing::info;
 
 use crate::metrics::ExecutorMetrics;
 use async_trait::async_trait;
-use config::{AuthorityIdentifier, Committee, WorkerCache};
+use config::{AuthorityIdentifier, BatchCompression, Committee, WorkerCache};
 
 use prometheus::Registry;
 
@@ -60,6 +60,7 @@ impl Executor {
         rx_sequence: metered_channel::Receiver<CommittedSubDag>,
         registry: &Registry,
         restored_consensus_output: Vec<CommittedSubDag>,
+        batch_compression: BatchCompression,
     ) -> SubscriberResult<Vec<JoinHandle<()>>>
     where
         State: ExecutionState + Send + Sync + 'static,
@@ -79,6 +80,7 @@ impl Executor {
             rx_sequence,
             arc_metrics,
             restored_consensus_output,
+            batch_compression,
             execution_state,
         );
 
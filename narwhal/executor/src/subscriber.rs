@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{errors::SubscriberResult, metrics::ExecutorMetrics, ExecutionState};
 
-use config::{AuthorityIdentifier, Committee, WorkerCache, WorkerId};
+use config::{AuthorityIdentifier, BatchCompression, Committee, WorkerCache, WorkerId};
 use crypto::NetworkPublicKey;
 
 use futures::stream::{FuturesOrdered, FuturesUnordered};
@@ -15,7 +15,7 @@ use prometheus::IntGauge;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::{sync::Arc, time::Duration, vec};
-use types::RequestBatchesRequest;
+use types::{RequestBatchesRequest, RequestCompressedBatchesRequest};
 
 use async_trait::async_trait;
 use fastcrypto::hash::Hash;
@@ -57,6 +57,7 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
     rx_sequence: metered_channel::Receiver<CommittedSubDag>,
     metrics: Arc<ExecutorMetrics>,
     restored_consensus_output: Vec<CommittedSubDag>,
+    batch_compression: BatchCompression,
     state: State,
 ) -> Vec<JoinHandle<()>> {
     // This is ugly but has to be done this way for now
@@ -89,6 +90,7 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
                 rx_sequence,
                 metrics,
                 restored_consensus_output,
+                batch_compression,
                 tx_notifier,
             ),
             "SubscriberTask"
@@ -124,6 +126,7 @@ async fn create_and_run_subscriber(
     rx_sequence: metered_channel::Receiver<CommittedSubDag>,
     metrics: Arc<ExecutorMetrics>,
     restored_consensus_output: Vec<CommittedSubDag>,
+    batch_compression: BatchCompression,
     tx_notifier: metered_channel::Sender<ConsensusOutput>,
 ) {
     let network = network.await.expect("Failed to receive network");
@@ -133,6 +136,7 @@ async fn create_and_run_subscriber(
         worker_cache,
         committee,
         network,
+        batch_compression,
     };
     let fetcher = Fetcher {
         network,
@@ -608,6 +612,7 @@ struct SubscriberNetworkImpl {
     network: anemo::Network,
     worker_cache: WorkerCache,
     committee: Committee,
+    batch_compression: BatchCompression,
 }
 
 #[async_trait]
@@ -655,6 +660,16 @@ impl SubscriberNetwork for SubscriberNetworkImpl {
         worker: NetworkPublicKey,
         timeout: Duration,
     ) -> anyhow::Result<RequestBatchesResponse> {
+        if self.batch_compression != BatchCompression::Uncompressed {
+            let request = RequestCompressedBatchesRequest {
+                batch_digests,
+                compression: self.batch_compression,
+            };
+            return self
+                .network
+                .request_compressed_batches(worker, request, timeout)
+                .await;
+        }
         let request =
             anemo::Request::new(RequestBatchesRequest { batch_digests }).with_timeout(timeout);
         self.network.request_batches(worker, request).await
@@ -6,21 +6,58 @@ use crate::{
     traits::{ReliableNetwork, UnreliableNetwork},
     CancelOnDropHandler, RetryConfig,
 };
+use anemo::types::response::StatusCode;
 use anemo::PeerId;
 use anyhow::format_err;
 use anyhow::Result;
 use async_trait::async_trait;
 use crypto::NetworkPublicKey;
-use std::time::Duration;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use types::{
     Batch, BatchDigest, FetchCertificatesRequest, FetchCertificatesResponse,
     GetCertificatesRequest, GetCertificatesResponse, PrimaryToPrimaryClient, PrimaryToWorkerClient,
-    RequestBatchRequest, RequestBatchesRequest, RequestBatchesResponse, WorkerBatchMessage,
+    RequestBatchRequest, RequestBatchesRequest, RequestBatchesResponse,
+    RequestCompressedBatchesRequest, WorkerBatchBroadcast, WorkerBatchMessage,
     WorkerDeleteBatchesMessage, WorkerOthersBatchMessage, WorkerOurBatchMessage,
     WorkerSynchronizeMessage, WorkerToPrimaryClient, WorkerToWorkerClient,
 };
 
+/// How long peers which do not support compressed batches are sent uncompressed ones before they
+/// are probed again, in case they were upgraded meanwhile.
+const COMPRESSION_PROBE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The peers found not to support compressed batches, and when they were.
+static PEERS_WITHOUT_COMPRESSION: Lazy<DashMap<PeerId, Instant>> = Lazy::new(DashMap::new);
+
+fn supports_compressed_batches(peer_id: &PeerId) -> bool {
+    match PEERS_WITHOUT_COMPRESSION.get(peer_id) {
+        Some(since) => since.elapsed() >= COMPRESSION_PROBE_INTERVAL,
+        None => true,
+    }
+}
+
+/// Records whether the peer supports compressed batches, from the response to a compressed
+/// batch RPC.
+fn record_compressed_batches_support<T>(
+    peer_id: PeerId,
+    response: &Result<T, anemo::rpc::Status>,
+) -> bool {
+    match response {
+        Err(status) if status.status() == StatusCode::NotFound => {
+            PEERS_WITHOUT_COMPRESSION.insert(peer_id, Instant::now());
+            false
+        }
+        _ => {
+            PEERS_WITHOUT_COMPRESSION.remove(&peer_id);
+            true
+        }
+    }
+}
+
 fn unreliable_send<F, R, Fut>(
     network: &anemo::Network,
     peer: NetworkPublicKey,
@@ -228,6 +265,37 @@ impl ReliableNetwork<WorkerBatchMessage> for anemo::Network {
     }
 }
 
+impl ReliableNetwork<WorkerBatchBroadcast> for anemo::Network {
+    type Response = ();
+    fn send(
+        &self,
+        peer: NetworkPublicKey,
+        message: &WorkerBatchBroadcast,
+    ) -> CancelOnDropHandler<Result<anemo::Response<()>>> {
+        let message = Arc::new(message.to_owned());
+        let f = move |peer: anemo::Peer| {
+            let message = message.clone();
+            async move {
+                let peer_id = peer.peer_id();
+                if supports_compressed_batches(&peer_id) {
+                    let response = WorkerToWorkerClient::new(peer.clone())
+                        .report_compressed_batch(message.compressed.clone())
+                        .await;
+                    if record_compressed_batches_support(peer_id, &response) {
+                        return response;
+                    }
+                }
+                // The peer does not support compressed batches, send it the batch as is.
+                WorkerToWorkerClient::new(peer)
+                    .report_batch(message.batch.clone())
+                    .await
+            }
+        };
+
+        send(self.clone(), peer, f)
+    }
+}
+
 #[async_trait]
 impl PrimaryToWorkerRpc for anemo::Network {
     async fn delete_batches(
@@ -295,4 +363,50 @@ impl WorkerRpc for anemo::Network {
             .map_err(|e| format_err!("Network error {:?}", e))?;
         Ok(response.into_body())
     }
+
+    async fn request_compressed_batches(
+        &self,
+        peer: NetworkPublicKey,
+        request: RequestCompressedBatchesRequest,
+        timeout: Duration,
+    ) -> Result<RequestBatchesResponse> {
+        let peer_id = PeerId(peer.0.to_bytes());
+        let peer = self
+            .peer(peer_id)
+            .ok_or_else(|| format_err!("Network has no connection with peer {peer_id}"))?;
+        if supports_compressed_batches(&peer_id) {
+            let compressed_request = anemo::Request::new(request.clone()).with_timeout(timeout);
+            let response = WorkerToWorkerClient::new(peer.clone())
+                .request_compressed_batches(compressed_request)
+                .await;
+            if record_compressed_batches_support(peer_id, &response) {
+                let response = response
+                    .map_err(|e| format_err!("Network error {:?}", e))?
+                    .into_body();
+                let batches = response
+                    .batches
+                    .iter()
+                    .map(|batch| batch.decompress())
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| {
+                        format_err!("Invalid compressed batch from peer {peer_id}: {e}")
+                    })?;
+                return Ok(RequestBatchesResponse {
+                    batches,
+                    is_size_limit_reached: response.is_size_limit_reached,
+                });
+            }
+        }
+
+        // The peer does not support compressed batches, request them as is.
+        let request = anemo::Request::new(RequestBatchesRequest {
+            batch_digests: request.batch_digests,
+        })
+        .with_timeout(timeout);
+        let response = WorkerToWorkerClient::new(peer)
+            .request_batches(request)
+            .await
+            .map_err(|e| format_err!("Network error {:?}", e))?;
+        Ok(response.into_body())
+    }
 }
@@ -4,10 +4,12 @@ use crate::CancelOnDropHandler;
 use anyhow::Result;
 use async_trait::async_trait;
 use crypto::NetworkPublicKey;
+use std::time::Duration;
 use tokio::task::JoinHandle;
 use types::{
     Batch, BatchDigest, FetchCertificatesRequest, FetchCertificatesResponse,
     GetCertificatesRequest, GetCertificatesResponse, RequestBatchesRequest, RequestBatchesResponse,
+    RequestCompressedBatchesRequest,
 };
 
 pub trait UnreliableNetwork<Request: Clone + Send + Sync> {
@@ -91,4 +93,13 @@ pub trait WorkerRpc {
         peer: NetworkPublicKey,
         request: impl anemo::types::request::IntoRequest<RequestBatchesRequest> + Send,
     ) -> Result<RequestBatchesResponse>;
+
+    /// Requests the batches compressed, or as is from peers that do not support compressed
+    /// batches, and returns them decompressed.
+    async fn request_compressed_batches(
+        &self,
+        peer: NetworkPublicKey,
+        request: RequestCompressedBatchesRequest,
+        timeout: Duration,
+    ) -> Result<RequestBatchesResponse>;
 }
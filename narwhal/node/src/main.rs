@@ -10,6 +10,7 @@
 
 use clap::{crate_name, crate_version, App, AppSettings, ArgMatches, SubCommand};
 use config::{Committee, Import, Parameters, WorkerCache, WorkerId};
+use consensus::replay::{diff_commits, export_certificates, recorded_commits, replay, CommitDiff};
 use crypto::{KeyPair, NetworkKeyPair};
 use eyre::Context;
 use fastcrypto::traits::KeyPair as _;
@@ -36,6 +37,7 @@ use tracing::subscriber::set_global_default;
 use tracing::{info, warn};
 #[cfg(feature = "benchmark")]
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use types::Round;
 use worker::TrivialTransactionValidator;
 
 #[tokio::main]
@@ -80,6 +82,16 @@ async fn main() -> Result<(), eyre::Report> {
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp),
         )
+        .subcommand(
+            SubCommand::with_name("replay_consensus")
+                .about("Replay the certificates of a range of rounds through consensus, and compare its commits with the ones recorded by the node")
+                .args_from_usage("--committee=<FILE> 'The file containing committee information'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters'")
+                .args_from_usage("--store=<PATH> 'The path of the data store of the node, which must not be running'")
+                .args_from_usage("--from-round=<INT> 'The first round to replay'")
+                .args_from_usage("--to-round=<INT> 'The last round to replay'")
+                .args_from_usage("--export=[FILE] 'The file where to save the BCS encoded certificates of the replayed rounds'"),
+        )
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .get_matches();
 
@@ -132,6 +144,10 @@ async fn main() -> Result<(), eyre::Report> {
                 }
             }
         }
+        ("replay_consensus", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            replay_consensus(sub_matches)?;
+        }
         ("run", Some(sub_matches)) => {
             let primary_key_file = sub_matches.value_of("primary-keys").unwrap();
             let primary_keypair = read_authority_keypair_from_file(primary_key_file)
@@ -238,6 +254,102 @@ fn setup_benchmark_telemetry(
     Ok(())
 }
 
+// Replays the certificates of the store through consensus and prints what it committed.
+fn replay_consensus(matches: &ArgMatches<'_>) -> Result<(), eyre::Report> {
+    let committee_file = matches.value_of("committee").unwrap();
+    let mut committee =
+        Committee::import(committee_file).context("Failed to load the committee information")?;
+    committee.load();
+
+    let parameters = match matches.value_of("parameters") {
+        Some(filename) => {
+            Parameters::import(filename).context("Failed to load the node's parameters")?
+        }
+        None => Parameters::default(),
+    };
+    let from_round = matches
+        .value_of("from-round")
+        .unwrap()
+        .parse::<Round>()
+        .context("The first round must be a positive integer")?;
+    let to_round = matches
+        .value_of("to-round")
+        .unwrap()
+        .parse::<Round>()
+        .context("The last round must be a positive integer")?;
+
+    let store = NodeStorage::reopen(matches.value_of("store").unwrap(), None);
+    let certificates = export_certificates(&store.certificate_store, from_round, to_round)?;
+    println!(
+        "Replaying {} certificates of rounds {from_round} to {to_round} with {:?}",
+        certificates.len(),
+        parameters.consensus_protocol
+    );
+    if let Some(filename) = matches.value_of("export") {
+        std::fs::write(filename, bcs::to_bytes(&certificates)?)
+            .context("Failed to export the certificates")?;
+    }
+
+    // The replay persists its commits, so it gets a store of its own.
+    let scratch_dir = tempfile::tempdir()?;
+    let scratch_store = NodeStorage::reopen(scratch_dir.path(), None);
+    let replayed = replay(
+        parameters.consensus_protocol,
+        &committee,
+        certificates.clone(),
+        scratch_store.consensus_store,
+        parameters.gc_depth,
+        PrimaryNode::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
+    )?;
+
+    println!("Leader elections:");
+    for election in &replayed.elections {
+        println!(
+            "  round {} certificate {} from {}: {:?}",
+            election.round, election.certificate, election.origin, election.outcome
+        );
+    }
+    println!("Committed sub dags:");
+    for sub_dag in &replayed.sub_dags {
+        println!(
+            "  sub dag {}: leader {} of round {}, {} certificates",
+            sub_dag.sub_dag_index,
+            sub_dag.leader,
+            sub_dag.leader_round,
+            sub_dag.certificates.len()
+        );
+    }
+
+    let recorded = recorded_commits(&store.consensus_store, from_round, to_round)?;
+    let diffs = diff_commits(&recorded, &replayed.sub_dags, &certificates);
+    println!(
+        "{} differences with the {} sub dags recorded by the node:",
+        diffs.len(),
+        recorded.len()
+    );
+    for diff in &diffs {
+        match diff {
+            CommitDiff::Mismatch { recorded, replayed } => println!(
+                "  round {}: recorded leader {} with {} certificates, replayed leader {} with {} certificates",
+                recorded.leader_round,
+                recorded.leader,
+                recorded.certificates.len(),
+                replayed.leader,
+                replayed.certificates.len()
+            ),
+            CommitDiff::MissingFromReplay(recorded) => println!(
+                "  round {}: recorded leader {} was not committed by the replay",
+                recorded.leader_round, recorded.leader
+            ),
+            CommitDiff::UnexpectedInReplay(replayed) => println!(
+                "  round {}: replayed leader {} was not committed by the node",
+                replayed.leader_round, replayed.leader
+            ),
+        }
+    }
+    Ok(())
+}
+
 // Runs either a worker or a primary.
 async fn run(
     matches: &ArgMatches<'_>,
@@ -2,24 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::metrics::new_registry;
 use crate::{try_join_all, FuturesUnordered, NodeError};
-use config::{AuthorityIdentifier, Committee, Parameters, WorkerCache};
-use consensus::bullshark::Bullshark;
-use consensus::consensus::ConsensusRound;
+use config::{AuthorityIdentifier, Committee, ConsensusProtocolKind, Parameters, WorkerCache};
+use consensus::consensus::{make_consensus_protocol, ConsensusRound};
 use consensus::dag::Dag;
 use consensus::metrics::{ChannelMetrics, ConsensusMetrics};
 use consensus::Consensus;
 use crypto::{KeyPair, NetworkKeyPair, PublicKey};
 use executor::{get_restored_consensus_output, ExecutionState, Executor, SubscriberResult};
 use fastcrypto::traits::{KeyPair as _, VerifyingKey};
-use mysten_metrics::{RegistryID, RegistryService};
-use primary::{NetworkModel, Primary, PrimaryChannelMetrics, NUM_SHUTDOWN_RECEIVERS};
+use mysten_metrics::{spawn_monitored_task, RegistryID, RegistryService};
+use primary::{
+    ConsensusCommitsGrpc, NetworkModel, Primary, PrimaryChannelMetrics, NUM_SHUTDOWN_RECEIVERS,
+};
 use prometheus::{IntGauge, Registry};
 use std::sync::Arc;
 use std::time::Instant;
 use storage::NodeStorage;
 use tokio::sync::{oneshot, watch, RwLock};
 use tokio::task::JoinHandle;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 use types::{
     metered_channel, Certificate, ConditionalBroadcastReceiver, PreSubscribedBroadcastSender, Round,
 };
@@ -226,7 +227,7 @@ impl PrimaryNodeInner {
         let (tx_consensus_round_updates, rx_consensus_round_updates) =
             watch::channel(ConsensusRound::new(0, 0));
         let (dag, network_model) = if !internal_consensus {
-            debug!("Consensus is disabled: the primary will run w/o Bullshark");
+            debug!("Consensus is disabled: the primary will run w/o an ordering protocol");
             let consensus_metrics = Arc::new(ConsensusMetrics::new(registry));
             let (handle, dag) = Dag::new(
                 &committee,
@@ -239,6 +240,34 @@ impl PrimaryNodeInner {
 
             (Some(Arc::new(dag)), NetworkModel::Asynchronous)
         } else {
+            let rx_executor_network = match &parameters.consensus_commits_grpc {
+                Some(commits_parameters) => {
+                    // The executor and the commits server both need the network of the primary.
+                    let (tx_subscriber_network, rx_subscriber_network) = oneshot::channel();
+                    let (tx_commits_network, rx_commits_network) = oneshot::channel();
+                    spawn_monitored_task!(async move {
+                        if let Ok(network) = rx_executor_network.await {
+                            let _ = tx_subscriber_network.send(network.clone());
+                            let _ = tx_commits_network.send(network);
+                        }
+                    });
+
+                    handles.push(ConsensusCommitsGrpc::spawn(
+                        authority.id(),
+                        committee.clone(),
+                        worker_cache.clone(),
+                        commits_parameters.clone(),
+                        store.consensus_store.clone(),
+                        store.certificate_store.clone(),
+                        rx_consensus_round_updates.clone(),
+                        rx_commits_network,
+                        tx_shutdown.subscribe(),
+                    ));
+                    rx_subscriber_network
+                }
+                None => rx_executor_network,
+            };
+
             let consensus_handles = Self::spawn_consensus(
                 authority.id(),
                 rx_executor_network,
@@ -332,10 +361,19 @@ impl PrimaryNodeInner {
             .inc_by(num_sub_dags);
 
         // Spawn the consensus core who only sequences transactions.
-        let ordering_engine = Bullshark::new(
+        if parameters.consensus_protocol != ConsensusProtocolKind::Bullshark {
+            warn!(
+                "Running consensus with {:?}: every authority of the committee must run it too, \
+                 or their commits diverge",
+                parameters.consensus_protocol
+            );
+        }
+        let ordering_engine = make_consensus_protocol(
+            parameters.consensus_protocol,
             committee.clone(),
             store.consensus_store.clone(),
             consensus_metrics.clone(),
+            parameters.gc_depth,
             Self::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
         );
         let consensus_handles = Consensus::spawn(
@@ -364,6 +402,7 @@ impl PrimaryNodeInner {
             rx_sequence,
             registry,
             restored_consensus_output,
+            parameters.batch_compression,
         )?;
 
         Ok(executor_handles
@@ -379,6 +418,10 @@ pub struct PrimaryNode {
 }
 
 impl PrimaryNode {
+    /// The window where the schedule change takes place in consensus, in committed sub dags.
+    pub const CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS: u64 =
+        PrimaryNodeInner::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS;
+
     pub fn new(
         parameters: Parameters,
         internal_consensus: bool,
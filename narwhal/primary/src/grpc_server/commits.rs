@@ -0,0 +1,343 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use config::{AuthorityIdentifier, Committee, ConsensusCommitsGrpcParameters, WorkerCache};
+use consensus::consensus::ConsensusRound;
+use fastcrypto::hash::Hash;
+use mysten_metrics::{spawn_logged_monitored_task, spawn_monitored_task};
+use network::WorkerRpc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use storage::CertificateStore;
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    task::JoinHandle,
+    time::timeout,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+use types::{
+    Batch, BatchDigest, Certificate, Commits, CommitsServer, CommittedBatchProto,
+    CommittedCertificateProto, CommittedSubDagProto, CommittedSubDagShell,
+    ConditionalBroadcastReceiver, ConsensusStore, HeaderAPI, RequestBatchesRequest,
+    SubscribeCommitsRequest,
+};
+
+/// The number of sub dags read at once from the consensus store.
+const SUB_DAGS_READ_CHUNK_SIZE: usize = 100;
+/// The number of sub dags buffered for each subscriber.
+const SUBSCRIBER_CHANNEL_CAPACITY: usize = 100;
+
+/// A gRPC server streaming the sub dags committed by the consensus of the primary, for external
+/// observers.
+pub struct ConsensusCommitsGrpc {
+    authority_id: AuthorityIdentifier,
+    committee: Committee,
+    worker_cache: WorkerCache,
+    parameters: ConsensusCommitsGrpcParameters,
+    consensus_store: Arc<ConsensusStore>,
+    certificate_store: CertificateStore,
+    rx_consensus_round_updates: watch::Receiver<ConsensusRound>,
+    // The network of the primary, to fetch the payloads from our workers.
+    // See comments in Subscriber::spawn
+    rx_network: oneshot::Receiver<anemo::Network>,
+    rx_shutdown: ConditionalBroadcastReceiver,
+}
+
+impl ConsensusCommitsGrpc {
+    #[must_use]
+    pub fn spawn(
+        authority_id: AuthorityIdentifier,
+        committee: Committee,
+        worker_cache: WorkerCache,
+        parameters: ConsensusCommitsGrpcParameters,
+        consensus_store: Arc<ConsensusStore>,
+        certificate_store: CertificateStore,
+        rx_consensus_round_updates: watch::Receiver<ConsensusRound>,
+        rx_network: oneshot::Receiver<anemo::Network>,
+        rx_shutdown: ConditionalBroadcastReceiver,
+    ) -> JoinHandle<()> {
+        spawn_logged_monitored_task!(
+            async move {
+                let _ = Self {
+                    authority_id,
+                    committee,
+                    worker_cache,
+                    parameters,
+                    consensus_store,
+                    certificate_store,
+                    rx_consensus_round_updates,
+                    rx_network,
+                    rx_shutdown,
+                }
+                .run()
+                .await
+                .map_err(|e| error!("{:?}", e));
+            },
+            "ConsensusCommitsGrpcTask"
+        )
+    }
+
+    async fn run(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        const GRACEFUL_SHUTDOWN_DURATION: Duration = Duration::from_millis(2_000);
+
+        let network = self.rx_network.await?;
+        let narwhal_commits = NarwhalCommits {
+            authority_id: self.authority_id,
+            committee: self.committee,
+            worker_cache: self.worker_cache,
+            consensus_store: self.consensus_store,
+            certificate_store: self.certificate_store,
+            rx_consensus_round_updates: self.rx_consensus_round_updates,
+            network,
+            get_batches_timeout: self.parameters.get_batches_timeout,
+        };
+
+        let config = mysten_network::config::Config::default();
+        let mut server = config
+            .server_builder()
+            .add_service(CommitsServer::new(narwhal_commits))
+            .bind(&self.parameters.socket_addr)
+            .await?;
+        let local_addr = server.local_addr();
+        info!("Consensus commits gRPC Server listening on {local_addr}");
+
+        let shutdown_handle = server.take_cancel_handle().unwrap();
+
+        let server_handle = spawn_logged_monitored_task!(server.serve());
+
+        // wait to receive a shutdown signal
+        let _ = self.rx_shutdown.receiver.recv().await;
+
+        // once do just gracefully shutdown the node
+        shutdown_handle.send(()).unwrap();
+
+        // now wait until the handle completes or timeout if it takes long time
+        match timeout(GRACEFUL_SHUTDOWN_DURATION, server_handle).await {
+            Ok(_) => {
+                info!("Successfully shutting down gracefully consensus commits grpc server");
+            }
+            Err(err) => {
+                warn!(
+                    "Time out while waiting to gracefully shutdown consensus commits grpc server: {}",
+                    err
+                )
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct NarwhalCommits {
+    /// The id of this authority.
+    authority_id: AuthorityIdentifier,
+    /// The committee information.
+    committee: Committee,
+    /// The worker information cache.
+    worker_cache: WorkerCache,
+    /// The store of the committed sub dags.
+    consensus_store: Arc<ConsensusStore>,
+    /// The store of the committed certificates.
+    certificate_store: CertificateStore,
+    /// Updated every time consensus commits.
+    rx_consensus_round_updates: watch::Receiver<ConsensusRound>,
+    /// The network to fetch the payloads from our workers.
+    network: anemo::Network,
+    /// Timeout when fetching the payloads from our workers.
+    get_batches_timeout: Duration,
+}
+
+impl NarwhalCommits {
+    /// Sends the sub dags committed from the requested index on, until the subscriber goes
+    /// away or consensus shuts down.
+    async fn stream_commits(
+        &self,
+        request: SubscribeCommitsRequest,
+        tx_commits: &mpsc::Sender<Result<CommittedSubDagProto, Status>>,
+    ) -> Result<(), Status> {
+        let mut rx_consensus_round_updates = self.rx_consensus_round_updates.clone();
+        let mut next_sub_dag_index = request.from_sub_dag_index;
+
+        loop {
+            // Mark the latest commit as seen before reading the store, so that a commit
+            // persisted after the read wakes us up.
+            rx_consensus_round_updates.borrow_and_update();
+            let sub_dags = self
+                .consensus_store
+                .read_committed_sub_dags(&next_sub_dag_index, SUB_DAGS_READ_CHUNK_SIZE)
+                .map_err(|e| Status::internal(format!("Couldn't read committed sub dags: {e}")))?;
+
+            if sub_dags.is_empty() {
+                tokio::select! {
+                    result = rx_consensus_round_updates.changed() => {
+                        if result.is_err() {
+                            // Consensus is shutting down.
+                            return Ok(());
+                        }
+                    }
+                    _ = tx_commits.closed() => return Ok(()),
+                }
+                continue;
+            }
+
+            for sub_dag in sub_dags {
+                next_sub_dag_index = sub_dag.sub_dag_index + 1;
+                let sub_dag = self
+                    .committed_sub_dag(sub_dag, request.include_payloads)
+                    .await?;
+                if tx_commits.send(Ok(sub_dag)).await.is_err() {
+                    // The subscriber went away.
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Reads the certificates of a committed sub dag and, if requested, fetches their payloads.
+    async fn committed_sub_dag(
+        &self,
+        sub_dag: CommittedSubDagShell,
+        include_payloads: bool,
+    ) -> Result<CommittedSubDagProto, Status> {
+        let certificates = self
+            .certificate_store
+            .read_all(sub_dag.certificates.iter().copied())
+            .map_err(|e| Status::internal(format!("Couldn't read certificates: {e}")))?
+            .into_iter()
+            .zip(&sub_dag.certificates)
+            .map(|(certificate, digest)| {
+                certificate.ok_or_else(|| {
+                    Status::not_found(format!(
+                        "Certificate {digest} of sub dag {} not found",
+                        sub_dag.sub_dag_index
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut payloads = if include_payloads {
+            self.fetch_payloads(&certificates).await?
+        } else {
+            HashMap::new()
+        };
+
+        let certificates = certificates
+            .iter()
+            .map(|certificate| CommittedCertificateProto {
+                digest: Some(certificate.digest().into()),
+                origin: certificate.origin().0.into(),
+                round: certificate.round(),
+                created_at: *certificate.header.created_at(),
+                batches: certificate
+                    .header
+                    .payload()
+                    .iter()
+                    .map(|(digest, (worker_id, _))| CommittedBatchProto {
+                        digest: Some((*digest).into()),
+                        worker_id: *worker_id,
+                        transactions: payloads
+                            .remove(digest)
+                            .map(|batch| batch.transactions.into_iter().map(Into::into).collect())
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(CommittedSubDagProto {
+            sub_dag_index: sub_dag.sub_dag_index,
+            leader: Some(sub_dag.leader.into()),
+            leader_round: sub_dag.leader_round,
+            certificates,
+        })
+    }
+
+    /// Fetches the batches of the certificates from our workers, which hold the payloads of
+    /// every certificate our primary accepted.
+    async fn fetch_payloads(
+        &self,
+        certificates: &[Certificate],
+    ) -> Result<HashMap<BatchDigest, Batch>, Status> {
+        let mut digests_by_worker: BTreeMap<_, HashSet<_>> = BTreeMap::new();
+        for certificate in certificates {
+            for (digest, (worker_id, _)) in certificate.header.payload() {
+                digests_by_worker
+                    .entry(*worker_id)
+                    .or_default()
+                    .insert(*digest);
+            }
+        }
+
+        let our_key = self
+            .committee
+            .authority(&self.authority_id)
+            .expect("Our id is not in the committee")
+            .protocol_key();
+        let mut batches = HashMap::new();
+        for (worker_id, mut digests) in digests_by_worker {
+            let worker = self
+                .worker_cache
+                .worker(our_key, &worker_id)
+                .map_err(|e| Status::internal(format!("Worker {worker_id} not found: {e}")))?
+                .name;
+
+            // Responses are bounded in size, so several requests may be needed.
+            while !digests.is_empty() {
+                let request = anemo::Request::new(RequestBatchesRequest {
+                    batch_digests: digests.iter().copied().collect(),
+                })
+                .with_timeout(self.get_batches_timeout);
+                let response = self
+                    .network
+                    .request_batches(worker.clone(), request)
+                    .await
+                    .map_err(|e| {
+                        Status::unavailable(format!(
+                            "Couldn't fetch batches from worker {worker_id}: {e}"
+                        ))
+                    })?;
+
+                let num_missing = digests.len();
+                for batch in response.batches {
+                    let digest = batch.digest();
+                    if digests.remove(&digest) {
+                        batches.insert(digest, batch);
+                    }
+                }
+                if digests.len() == num_missing {
+                    return Err(Status::not_found(format!(
+                        "Batches {digests:?} not found on worker {worker_id}"
+                    )));
+                }
+            }
+        }
+        Ok(batches)
+    }
+}
+
+#[tonic::async_trait]
+impl Commits for NarwhalCommits {
+    type SubscribeCommitsStream = ReceiverStream<Result<CommittedSubDagProto, Status>>;
+
+    async fn subscribe_commits(
+        &self,
+        request: Request<SubscribeCommitsRequest>,
+    ) -> Result<Response<Self::SubscribeCommitsStream>, Status> {
+        let request = request.into_inner();
+        let (tx_commits, rx_commits) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+
+        let commits = self.clone();
+        spawn_monitored_task!(async move {
+            if let Err(status) = commits.stream_commits(request, &tx_commits).await {
+                let _ = tx_commits.send(Err(status)).await;
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx_commits)))
+    }
+}
@@ -18,6 +18,7 @@ use tokio::time::timeout;
 use tracing::{error, info, warn};
 use types::{ConditionalBroadcastReceiver, ConfigurationServer, ProposerServer, ValidatorServer};
 
+pub mod commits;
 mod configuration;
 pub mod metrics;
 mod proposer;
@@ -34,7 +34,7 @@ pub use crate::{
     block_remover::BlockRemover,
     block_synchronizer::{mock::MockBlockSynchronizer, BlockHeader},
     block_waiter::{BlockWaiter, GetBlockResponse},
-    grpc_server::metrics::EndpointMetrics,
+    grpc_server::{commits::ConsensusCommitsGrpc, metrics::EndpointMetrics},
     metrics::PrimaryChannelMetrics,
     primary::{NetworkModel, Primary, CHANNEL_CAPACITY, NUM_SHUTDOWN_RECEIVERS},
 };
@@ -0,0 +1,202 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use config::ConsensusCommitsGrpcParameters;
+use consensus::consensus::ConsensusRound;
+use fastcrypto::hash::Hash;
+use indexmap::IndexMap;
+use narwhal_primary::{ConsensusCommitsGrpc, NUM_SHUTDOWN_RECEIVERS};
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+use storage::NodeStorage;
+use test_utils::{temp_dir, CommitteeFixture};
+use tokio::sync::{oneshot, watch};
+use tonic::transport::Channel;
+use types::{
+    Certificate, CommitsClient, CommittedSubDag, Header, HeaderV1Builder, MockWorkerToWorker,
+    PreSubscribedBroadcastSender, ReputationScores, RequestBatchesResponse,
+    SubscribeCommitsRequest, WorkerToWorkerServer,
+};
+
+#[tokio::test]
+async fn test_subscribe_commits() {
+    telemetry_subscribers::init_for_testing();
+
+    // GIVEN keys
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.worker_cache();
+    let author = fixture.authorities().next().unwrap();
+
+    // AND a store holding two committed sub dags of one certificate each, the second one with
+    // a known batch
+    let store = NodeStorage::reopen(temp_dir(), None);
+    let batch = test_utils::batch();
+    let genesis: BTreeSet<_> = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect();
+    let (_, certificate_1) = test_utils::mock_certificate(&committee, author.id(), 1, genesis);
+    let header = HeaderV1Builder::default()
+        .author(author.id())
+        .round(2)
+        .epoch(0)
+        .parents(BTreeSet::from([certificate_1.digest()]))
+        .payload(IndexMap::from([(batch.digest(), (0, 0))]))
+        .build()
+        .unwrap();
+    let certificate_2 = Certificate::new_unsigned(&committee, Header::V1(header), vec![]).unwrap();
+
+    let mut sub_dags = Vec::new();
+    for (index, certificate) in [certificate_1, certificate_2].into_iter().enumerate() {
+        store.certificate_store.write(certificate.clone()).unwrap();
+        let sub_dag = CommittedSubDag {
+            certificates: vec![certificate.clone()],
+            leader: certificate,
+            sub_dag_index: index as u64 + 1,
+            reputation_score: ReputationScores::default(),
+        };
+        store
+            .consensus_store
+            .write_consensus_state(&HashMap::new(), &sub_dag)
+            .unwrap();
+        sub_dags.push(sub_dag);
+    }
+
+    // AND our worker serving the batch
+    let mut mock_server = MockWorkerToWorker::new();
+    let mock_batch = batch.clone();
+    mock_server.expect_request_batches().returning(move |_| {
+        Ok(anemo::Response::new(RequestBatchesResponse {
+            batches: vec![mock_batch.clone()],
+            is_size_limit_reached: false,
+        }))
+    });
+    let routes = anemo::Router::new().add_rpc_service(WorkerToWorkerServer::new(mock_server));
+    let worker = author.worker(0);
+    let _worker_network = worker.new_network(routes);
+
+    let network = test_utils::random_network();
+    network
+        .connect_with_peer_id(
+            worker.info().worker_address.to_anemo_address().unwrap(),
+            anemo::PeerId(worker.info().name.0.to_bytes()),
+        )
+        .await
+        .unwrap();
+
+    // AND the commits server
+    let parameters = ConsensusCommitsGrpcParameters::default();
+    let (tx_consensus_round_updates, rx_consensus_round_updates) =
+        watch::channel(ConsensusRound::default());
+    let (tx_network, rx_network) = oneshot::channel();
+    tx_network.send(network).unwrap();
+    let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
+
+    let _handle = ConsensusCommitsGrpc::spawn(
+        author.id(),
+        committee.clone(),
+        worker_cache,
+        parameters.clone(),
+        store.consensus_store.clone(),
+        store.certificate_store.clone(),
+        rx_consensus_round_updates,
+        rx_network,
+        tx_shutdown.subscribe(),
+    );
+
+    // AND Wait for tasks to start
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let mut client = connect_to_commits_client(&parameters);
+
+    // WHEN we subscribe from the second sub dag with the payloads
+    let request = SubscribeCommitsRequest {
+        from_sub_dag_index: 2,
+        include_payloads: true,
+    };
+    let mut stream = client
+        .subscribe_commits(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    // THEN we receive the second sub dag and the transactions of its batch
+    let sub_dag = stream.message().await.unwrap().unwrap();
+    assert_eq!(sub_dag.sub_dag_index, 2);
+    assert_eq!(sub_dag.leader_round, 2);
+    assert_eq!(sub_dag.leader, Some(sub_dags[1].leader.digest().into()));
+    assert_eq!(sub_dag.certificates.len(), 1);
+    let certificate = &sub_dag.certificates[0];
+    assert_eq!(certificate.origin, u32::from(author.id().0));
+    assert_eq!(certificate.round, 2);
+    assert_eq!(certificate.batches.len(), 1);
+    assert_eq!(certificate.batches[0].digest, Some(batch.digest().into()));
+    let transactions: Vec<_> = certificate.batches[0]
+        .transactions
+        .iter()
+        .map(|x| x.transaction.to_vec())
+        .collect();
+    assert_eq!(transactions, batch.transactions);
+
+    // WHEN we subscribe from the start without the payloads
+    let request = SubscribeCommitsRequest {
+        from_sub_dag_index: 0,
+        include_payloads: false,
+    };
+    let mut stream = client
+        .subscribe_commits(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    // THEN we receive the history, with the batch digests only
+    for expected in &sub_dags {
+        let sub_dag = stream.message().await.unwrap().unwrap();
+        assert_eq!(sub_dag.sub_dag_index, expected.sub_dag_index);
+        assert_eq!(sub_dag.leader, Some(expected.leader.digest().into()));
+        assert!(sub_dag.certificates[0]
+            .batches
+            .iter()
+            .all(|x| x.transactions.is_empty()));
+    }
+
+    // AND the sub dags committed afterwards
+    let (_, certificate_3) = test_utils::mock_certificate(
+        &committee,
+        author.id(),
+        3,
+        BTreeSet::from([sub_dags[1].leader.digest()]),
+    );
+    store
+        .certificate_store
+        .write(certificate_3.clone())
+        .unwrap();
+    let sub_dag_3 = CommittedSubDag {
+        certificates: vec![certificate_3.clone()],
+        leader: certificate_3,
+        sub_dag_index: 3,
+        reputation_score: ReputationScores::default(),
+    };
+    store
+        .consensus_store
+        .write_consensus_state(&HashMap::new(), &sub_dag_3)
+        .unwrap();
+    tx_consensus_round_updates
+        .send(ConsensusRound::new(3, 0))
+        .unwrap();
+
+    let sub_dag = stream.message().await.unwrap().unwrap();
+    assert_eq!(sub_dag.sub_dag_index, 3);
+    assert_eq!(sub_dag.leader_round, 3);
+}
+
+fn connect_to_commits_client(
+    parameters: &ConsensusCommitsGrpcParameters,
+) -> CommitsClient<Channel> {
+    let config = mysten_network::config::Config::new();
+    let channel = config.connect_lazy(&parameters.socket_addr).unwrap();
+    CommitsClient::new(channel)
+}
@@ -39,9 +39,11 @@ use types::{
     GetCertificatesResponse, Header, HeaderAPI, HeaderV1Builder, PayloadAvailabilityRequest,
     PayloadAvailabilityResponse, PrimaryToPrimary, PrimaryToPrimaryServer, PrimaryToWorker,
     PrimaryToWorkerServer, RequestBatchRequest, RequestBatchResponse, RequestBatchesRequest,
-    RequestBatchesResponse, RequestVoteRequest, RequestVoteResponse, Round, SendCertificateRequest,
+    RequestBatchesResponse, RequestCompressedBatchesRequest, RequestCompressedBatchesResponse,
+    RequestVoteRequest, RequestVoteResponse, Round, SendCertificateRequest,
     SendCertificateResponse, SequenceNumber, TimestampMs, Transaction, Vote, WorkerBatchMessage,
-    WorkerDeleteBatchesMessage, WorkerSynchronizeMessage, WorkerToWorker, WorkerToWorkerServer,
+    WorkerCompressedBatchMessage, WorkerDeleteBatchesMessage, WorkerSynchronizeMessage,
+    WorkerToWorker, WorkerToWorkerServer,
 };
 
 pub mod cluster;
@@ -323,6 +325,23 @@ impl WorkerToWorker for WorkerToWorkerMockServer {
 
         Ok(anemo::Response::new(()))
     }
+    async fn report_compressed_batch(
+        &self,
+        request: anemo::Request<WorkerCompressedBatchMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        let batch = request
+            .into_body()
+            .batch
+            .decompress()
+            .map_err(|e| anemo::rpc::Status::internal(e.to_string()))?;
+
+        self.batch_sender
+            .send(WorkerBatchMessage { batch })
+            .await
+            .unwrap();
+
+        Ok(anemo::Response::new(()))
+    }
     async fn request_batch(
         &self,
         _request: anemo::Request<RequestBatchRequest>,
@@ -338,6 +357,14 @@ impl WorkerToWorker for WorkerToWorkerMockServer {
         tracing::error!("Not implemented WorkerToWorkerMockServer::request_batches");
         Err(anemo::rpc::Status::internal("Unimplemented"))
     }
+
+    async fn request_compressed_batches(
+        &self,
+        _request: anemo::Request<RequestCompressedBatchesRequest>,
+    ) -> Result<anemo::Response<RequestCompressedBatchesResponse>, anemo::rpc::Status> {
+        tracing::error!("Not implemented WorkerToWorkerMockServer::request_compressed_batches");
+        Err(anemo::rpc::Status::internal("Unimplemented"))
+    }
 }
 
 ////////////////////////////////////////////////////////////////
@@ -220,4 +220,19 @@ impl ConsensusStore {
             .map(|(_, sub_dag)| sub_dag)
             .collect())
     }
+
+    /// Load at most `limit` sub dags committed with sequence number of at least `from`.
+    pub fn read_committed_sub_dags(
+        &self,
+        from: &SequenceNumber,
+        limit: usize,
+    ) -> StoreResult<Vec<CommittedSubDagShell>> {
+        Ok(self
+            .committed_sub_dags_by_index
+            .iter()
+            .skip_to(from)?
+            .take(limit)
+            .map(|(_, sub_dag)| sub_dag)
+            .collect())
+    }
 }
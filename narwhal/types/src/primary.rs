@@ -4,7 +4,7 @@
 use crate::{
     error::{DagError, DagResult},
     serde::NarwhalBitmap,
-    CertificateDigestProto,
+    BatchDigestProto, CertificateDigestProto,
 };
 use bytes::Bytes;
 use config::{AuthorityIdentifier, Committee, Epoch, Stake, WorkerCache, WorkerId, WorkerInfo};
@@ -145,6 +145,14 @@ impl BatchDigest {
     }
 }
 
+impl From<BatchDigest> for BatchDigestProto {
+    fn from(digest: BatchDigest) -> Self {
+        BatchDigestProto {
+            digest: Bytes::from(digest.0.to_vec()),
+        }
+    }
+}
+
 impl Hash<{ crypto::DIGEST_LENGTH }> for Batch {
     type TypedDigest = BatchDigest;
 
@@ -19,6 +19,8 @@ use crypto::PublicKey;
 pub use narwhal::{
     collection_error::CollectionErrorType,
     collection_retrieval_result::RetrievalResult,
+    commits_client::CommitsClient,
+    commits_server::{Commits, CommitsServer},
     configuration_client::ConfigurationClient,
     configuration_server::{Configuration, ConfigurationServer},
     primary_to_primary_client::PrimaryToPrimaryClient,
@@ -35,11 +37,13 @@ pub use narwhal::{
     worker_to_primary_server::{MockWorkerToPrimary, WorkerToPrimary, WorkerToPrimaryServer},
     worker_to_worker_client::WorkerToWorkerClient,
     worker_to_worker_server::{MockWorkerToWorker, WorkerToWorker, WorkerToWorkerServer},
-    CertificateDigest as CertificateDigestProto, Collection, CollectionError,
-    CollectionRetrievalResult, Empty, GetCollectionsRequest, GetCollectionsResponse,
-    GetPrimaryAddressResponse, MultiAddr as MultiAddrProto, NewEpochRequest, NewNetworkInfoRequest,
-    NodeReadCausalRequest, NodeReadCausalResponse, PublicKey as PublicKeyProto, ReadCausalRequest,
-    ReadCausalResponse, RemoveCollectionsRequest, RoundsRequest, RoundsResponse,
+    BatchDigest as BatchDigestProto, CertificateDigest as CertificateDigestProto, Collection,
+    CollectionError, CollectionRetrievalResult, CommittedBatch as CommittedBatchProto,
+    CommittedCertificate as CommittedCertificateProto, CommittedSubDag as CommittedSubDagProto,
+    Empty, GetCollectionsRequest, GetCollectionsResponse, GetPrimaryAddressResponse,
+    MultiAddr as MultiAddrProto, NewEpochRequest, NewNetworkInfoRequest, NodeReadCausalRequest,
+    NodeReadCausalResponse, PublicKey as PublicKeyProto, ReadCausalRequest, ReadCausalResponse,
+    RemoveCollectionsRequest, RoundsRequest, RoundsResponse, SubscribeCommitsRequest,
     Transaction as TransactionProto, ValidatorData,
 };
 
@@ -0,0 +1,40 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Batch, CompressedBatch, CompressionError, MAX_DECOMPRESSED_BATCH_SIZE};
+use config::BatchCompression;
+
+#[test]
+fn test_batch_compression_roundtrip() {
+    let batch = Batch::new(vec![vec![1; 1_000], vec![2; 1_000]]);
+
+    for compression in [BatchCompression::Uncompressed, BatchCompression::Zstd] {
+        let compressed = CompressedBatch::new(&batch, compression).unwrap();
+        assert_eq!(compressed.compression, compression);
+        assert_eq!(compressed.decompress().unwrap(), batch);
+    }
+
+    let uncompressed = CompressedBatch::new(&batch, BatchCompression::Uncompressed).unwrap();
+    let compressed = CompressedBatch::new(&batch, BatchCompression::Zstd).unwrap();
+    assert!(compressed.bytes.len() < uncompressed.bytes.len());
+}
+
+#[test]
+fn test_batch_decompression_is_bounded() {
+    let batch = Batch::new(vec![vec![0; MAX_DECOMPRESSED_BATCH_SIZE as usize]]);
+    let compressed = CompressedBatch::new(&batch, BatchCompression::Zstd).unwrap();
+
+    assert!(matches!(
+        compressed.decompress(),
+        Err(CompressionError::TooLarge)
+    ));
+}
+
+#[test]
+fn test_invalid_compressed_batch() {
+    let compressed = CompressedBatch {
+        compression: BatchCompression::Zstd,
+        bytes: vec![1, 2, 3],
+    };
+    assert!(compressed.decompress().is_err());
+}
@@ -3,19 +3,78 @@
 
 use crate::{Batch, BatchDigest};
 
+use config::BatchCompression;
 use serde::{Deserialize, Serialize};
+use std::io::Read;
 use thiserror::Error;
 
 #[cfg(test)]
 #[path = "tests/batch_serde.rs"]
 mod batch_serde;
 
+#[cfg(test)]
+#[path = "tests/batch_compression_tests.rs"]
+mod batch_compression_tests;
+
+/// The largest size a compressed batch may decompress to, so that a peer cannot exhaust our memory
+/// with a small message.
+pub const MAX_DECOMPRESSED_BATCH_SIZE: u64 = 64 * 1024 * 1024;
+
 /// Used by workers to send a new batch.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct WorkerBatchMessage {
     pub batch: Batch,
 }
 
+/// Used by workers to send a new batch to the workers that support compressed batches.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkerCompressedBatchMessage {
+    pub batch: CompressedBatch,
+}
+
+/// A batch to broadcast to the other workers, along with its compressed form for the workers
+/// that support compressed batches.
+#[derive(Clone, Debug)]
+pub struct WorkerBatchBroadcast {
+    pub batch: WorkerBatchMessage,
+    pub compressed: WorkerCompressedBatchMessage,
+}
+
+/// A serialized batch, compressed.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompressedBatch {
+    pub compression: BatchCompression,
+    pub bytes: Vec<u8>,
+}
+
+impl CompressedBatch {
+    pub fn new(batch: &Batch, compression: BatchCompression) -> Result<Self, CompressionError> {
+        let bytes = bcs::to_bytes(batch)?;
+        let bytes = match compression {
+            BatchCompression::Uncompressed => bytes,
+            BatchCompression::Zstd => zstd::stream::encode_all(bytes.as_slice(), 0)?,
+        };
+        Ok(Self { compression, bytes })
+    }
+
+    pub fn decompress(&self) -> Result<Batch, CompressionError> {
+        let batch = match self.compression {
+            BatchCompression::Uncompressed => bcs::from_bytes(&self.bytes)?,
+            BatchCompression::Zstd => {
+                let mut bytes = Vec::new();
+                zstd::stream::read::Decoder::new(self.bytes.as_slice())?
+                    .take(MAX_DECOMPRESSED_BATCH_SIZE + 1)
+                    .read_to_end(&mut bytes)?;
+                if bytes.len() as u64 > MAX_DECOMPRESSED_BATCH_SIZE {
+                    return Err(CompressionError::TooLarge);
+                }
+                bcs::from_bytes(&bytes)?
+            }
+        };
+        Ok(batch)
+    }
+}
+
 /// Used by primary to ask worker for the request.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RequestBatchRequest {
@@ -41,9 +100,34 @@ pub struct RequestBatchesResponse {
     pub is_size_limit_reached: bool,
 }
 
+/// Used by primary to bulk request batches from workers that support compressed batches.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestCompressedBatchesRequest {
+    pub batch_digests: Vec<BatchDigest>,
+    pub compression: BatchCompression,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestCompressedBatchesResponse {
+    pub batches: Vec<CompressedBatch>,
+    // If true, the primary should request the batches from the workers again.
+    // This may not be something that can be trusted from a remote worker.
+    pub is_size_limit_reached: bool,
+}
+
 pub type TxResponse = tokio::sync::oneshot::Sender<BatchDigest>;
 pub type PrimaryResponse = Option<tokio::sync::oneshot::Sender<()>>;
 
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("Failed to (de)serialize the batch: {0}")]
+    Serialization(#[from] bcs::Error),
+    #[error("Failed to (de)compress the batch: {0}")]
+    Compression(#[from] std::io::Error),
+    #[error("The batch decompresses to more than {MAX_DECOMPRESSED_BATCH_SIZE} bytes")]
+    TooLarge,
+}
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum DigestError {
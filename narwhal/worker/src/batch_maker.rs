@@ -2,6 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::metrics::WorkerMetrics;
+use crate::TransactionValidator;
 #[cfg(feature = "trace_transaction")]
 use byteorder::{BigEndian, ReadBytesExt};
 use fastcrypto::hash::Hash;
@@ -17,6 +18,8 @@ use std::convert::TryInto;
 use futures::{Future, StreamExt};
 
 use mysten_metrics::spawn_logged_monitored_task;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tokio::{
     task::JoinHandle,
@@ -32,12 +35,19 @@ use types::{
 // The number of batches to store / transmit in parallel.
 pub const MAX_PARALLEL_BATCH: usize = 100;
 
+// The number of batches worth of transactions to queue, waiting to be sealed.
+pub const MAX_QUEUED_BATCHES: usize = 10;
+
+// The number of max batch delays after which a queued transaction goes in the next batch,
+// whatever its priority, so that transactions of low priority are not starved.
+pub const MAX_QUEUE_DELAY_BATCH_DELAYS: u32 = 5;
+
 #[cfg(test)]
 #[path = "tests/batch_maker_tests.rs"]
 pub mod batch_maker_tests;
 
 /// Assemble clients transactions into batches.
-pub struct BatchMaker {
+pub struct BatchMaker<V> {
     // Our worker's id.
     id: WorkerId,
     /// The preferred batch size (in bytes).
@@ -47,7 +57,7 @@ pub struct BatchMaker {
     /// Receiver for shutdown.
     rx_shutdown: ConditionalBroadcastReceiver,
     /// Channel to receive transactions from the network.
-    rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+    rx_batch_maker: Receiver<(Transaction, Option<u64>, TxResponse)>,
     /// Output channel to deliver sealed batches to the `QuorumWaiter`.
     tx_quorum_waiter: Sender<(Batch, tokio::sync::oneshot::Sender<()>)>,
     /// Metrics handler
@@ -59,20 +69,23 @@ pub struct BatchMaker {
     store: DBMap<BatchDigest, Batch>,
     // Output channel to send out batches' digests.
     tx_our_batch: Sender<(WorkerOurBatchMessage, PrimaryResponse)>,
+    /// Determines the priority of the received transactions.
+    validator: V,
 }
 
-impl BatchMaker {
+impl<V: TransactionValidator> BatchMaker<V> {
     #[must_use]
     pub fn spawn(
         id: WorkerId,
         batch_size_limit: usize,
         max_batch_delay: Duration,
         rx_shutdown: ConditionalBroadcastReceiver,
-        rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+        rx_batch_maker: Receiver<(Transaction, Option<u64>, TxResponse)>,
         tx_quorum_waiter: Sender<(Batch, tokio::sync::oneshot::Sender<()>)>,
         node_metrics: Arc<WorkerMetrics>,
         store: DBMap<BatchDigest, Batch>,
         tx_our_batch: Sender<(WorkerOurBatchMessage, PrimaryResponse)>,
+        validator: V,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -87,6 +100,7 @@ impl BatchMaker {
                     node_metrics,
                     store,
                     tx_our_batch,
+                    validator,
                 }
                 .run()
                 .await;
@@ -100,48 +114,34 @@ impl BatchMaker {
         let timer = sleep(self.max_batch_delay);
         tokio::pin!(timer);
 
-        let mut current_batch = Batch::default();
-        let mut current_responses = Vec::new();
-        let mut current_batch_size = 0;
+        let mut queue = TransactionQueue::default();
+        let max_queue_delay = self.max_batch_delay * MAX_QUEUE_DELAY_BATCH_DELAYS;
 
         let mut batch_pipeline = FuturesUnordered::new();
 
         loop {
             tokio::select! {
-                // Assemble client transactions into batches of preset size.
-                // Note that transactions are only consumed when the number of batches
-                // 'in-flight' are below a certain number (MAX_PARALLEL_BATCH). This
-                // condition will be met eventually if the store and network are functioning.
-                Some((transaction, response_sender)) = self.rx_batch_maker.recv(), if batch_pipeline.len() < MAX_PARALLEL_BATCH => {
-                    current_batch_size += transaction.len();
-                    current_batch.transactions.push(transaction);
-                    current_responses.push(response_sender);
-                    if current_batch_size >= self.batch_size_limit {
-                        if let Some(seal) = self.seal(false, current_batch, current_batch_size, current_responses).await{
-                            batch_pipeline.push(seal);
-                        }
-                        self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
-
-                        current_batch = Batch::default();
-                        current_responses = Vec::new();
-                        current_batch_size = 0;
-
-                        timer.as_mut().reset(Instant::now() + self.max_batch_delay);
-                        self.batch_start_timestamp = Instant::now();
-                    }
+                // Queue client transactions by priority. Transactions keep being received while
+                // batches are in flight, until MAX_QUEUED_BATCHES batches worth of them are
+                // queued, so that the ones of higher priority get ahead of the ones queued before.
+                Some((transaction, priority, response_sender)) = self.rx_batch_maker.recv(), if queue.size < MAX_QUEUED_BATCHES * self.batch_size_limit => {
+                    // Transactions submitted locally are trusted, they are only prioritized.
+                    let priority = priority.unwrap_or_else(|| {
+                        self.validator
+                            .validate_and_prioritize(&transaction)
+                            .unwrap_or_default()
+                    });
+                    queue.push(priority, transaction, response_sender, &self.node_metrics);
                 },
 
                 // If the timer triggers, seal the batch even if it contains few transactions.
                 () = &mut timer => {
-                    if !current_batch.transactions.is_empty() {
-                        if let Some(seal) = self.seal(true, current_batch, current_batch_size, current_responses).await {
+                    if !queue.is_empty() {
+                        let (batch, size, responses) = queue.pop_batch(self.batch_size_limit, max_queue_delay, &self.node_metrics);
+                        if let Some(seal) = self.seal(true, batch, size, responses).await {
                             batch_pipeline.push(seal);
                         }
                         self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
-
-                        current_batch = Batch::default();
-                        current_responses = Vec::new();
-                        current_batch_size = 0;
                     }
                     timer.as_mut().reset(Instant::now() + self.max_batch_delay);
                     self.batch_start_timestamp = Instant::now();
@@ -160,6 +160,27 @@ impl BatchMaker {
 
             }
 
+            // Assemble the queued transactions into batches of preset size, the ones queued for
+            // too long first, then the ones of highest priority. Note that batches are only sealed when the number of batches 'in-flight'
+            // are below a certain number (MAX_PARALLEL_BATCH). This condition will be met
+            // eventually if the store and network are functioning.
+            while !queue.is_empty()
+                && queue.size >= self.batch_size_limit
+                && batch_pipeline.len() < MAX_PARALLEL_BATCH
+            {
+                let (batch, size, responses) =
+                    queue.pop_batch(self.batch_size_limit, max_queue_delay, &self.node_metrics);
+                if let Some(seal) = self.seal(false, batch, size, responses).await {
+                    batch_pipeline.push(seal);
+                }
+                self.node_metrics
+                    .parallel_worker_batches
+                    .set(batch_pipeline.len() as i64);
+
+                timer.as_mut().reset(Instant::now() + self.max_batch_delay);
+                self.batch_start_timestamp = Instant::now();
+            }
+
             // Give the change to schedule other tasks.
             tokio::task::yield_now().await;
         }
@@ -318,3 +339,124 @@ impl BatchMaker {
         })
     }
 }
+
+/// The transactions received by the `BatchMaker` that are not in a batch yet, ordered by
+/// priority, then by order of arrival.
+#[derive(Default)]
+struct TransactionQueue {
+    /// The queued transactions, by decreasing priority then order of arrival, so that
+    /// transactions of the same priority are first in, first out.
+    transactions: BTreeMap<(Reverse<u64>, u64), QueuedTransaction>,
+    /// The priority of the queued transactions, by order of arrival.
+    arrivals: BTreeMap<u64, u64>,
+    /// The total size of the queued transactions (in bytes).
+    size: usize,
+    /// The number of transactions queued so far.
+    received: u64,
+}
+
+struct QueuedTransaction {
+    received_at: Instant,
+    transaction: Transaction,
+    response: TxResponse,
+}
+
+impl TransactionQueue {
+    fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    fn push(
+        &mut self,
+        priority: u64,
+        transaction: Transaction,
+        response: TxResponse,
+        metrics: &WorkerMetrics,
+    ) {
+        metrics
+            .queued_transactions
+            .with_label_values(&[priority_class(priority)])
+            .inc();
+        self.size += transaction.len();
+        self.transactions.insert(
+            (Reverse(priority), self.received),
+            QueuedTransaction {
+                received_at: Instant::now(),
+                transaction,
+                response,
+            },
+        );
+        self.arrivals.insert(self.received, priority);
+        self.received += 1;
+    }
+
+    /// The key of the next transaction to batch: the oldest one if it was queued for longer than
+    /// `max_queue_delay`, otherwise the one of highest priority.
+    fn next_key(&self, max_queue_delay: Duration) -> Option<(Reverse<u64>, u64)> {
+        let (sequence, priority) = self.arrivals.iter().next()?;
+        let key = (Reverse(*priority), *sequence);
+        if self.transactions[&key].received_at.elapsed() > max_queue_delay {
+            return Some(key);
+        }
+        self.transactions.keys().next().copied()
+    }
+
+    /// Takes the transactions queued for longer than `max_queue_delay`, then the ones of highest
+    /// priority out of the queue, until they reach the batch size limit.
+    fn pop_batch(
+        &mut self,
+        batch_size_limit: usize,
+        max_queue_delay: Duration,
+        metrics: &WorkerMetrics,
+    ) -> (Batch, usize, Vec<TxResponse>) {
+        let mut batch = Batch::default();
+        let mut size = 0;
+        let mut responses = Vec::new();
+        while size < batch_size_limit {
+            let (Reverse(priority), sequence) = match self.next_key(max_queue_delay) {
+                Some(key) => key,
+                None => break,
+            };
+            let queued = self
+                .transactions
+                .remove(&(Reverse(priority), sequence))
+                .expect("The next key is queued");
+            self.arrivals.remove(&sequence);
+
+            let class = priority_class(priority);
+            metrics
+                .queued_transactions
+                .with_label_values(&[class])
+                .dec();
+            metrics
+                .sealed_transactions
+                .with_label_values(&[class])
+                .inc();
+            metrics
+                .transaction_queue_latency
+                .with_label_values(&[class])
+                .observe(queued.received_at.elapsed().as_secs_f64());
+
+            size += queued.transaction.len();
+            batch.transactions.push(queued.transaction);
+            responses.push(queued.response);
+        }
+        self.size -= size;
+        (batch, size, responses)
+    }
+}
+
+/// The metrics label of a priority: the lower bound of its decimal order of magnitude, to keep the
+/// number of labels small.
+fn priority_class(priority: u64) -> &'static str {
+    match priority {
+        0 => "0",
+        1..=9 => "1",
+        10..=99 => "10",
+        100..=999 => "100",
+        1_000..=9_999 => "1000",
+        10_000..=99_999 => "10000",
+        100_000..=999_999 => "100000",
+        _ => "1000000",
+    }
+}
@@ -40,11 +40,11 @@ pub enum NarwhalError {
 #[derive(Clone)]
 pub struct LocalNarwhalClient {
     /// TODO: maybe use tx_batch_maker for load schedding.
-    tx_batch_maker: Sender<(Transaction, TxResponse)>,
+    tx_batch_maker: Sender<(Transaction, Option<u64>, TxResponse)>,
 }
 
 impl LocalNarwhalClient {
-    pub fn new(tx_batch_maker: Sender<(Transaction, TxResponse)>) -> Arc<Self> {
+    pub fn new(tx_batch_maker: Sender<(Transaction, Option<u64>, TxResponse)>) -> Arc<Self> {
         Arc::new(Self { tx_batch_maker })
     }
 
@@ -76,6 +76,23 @@ impl LocalNarwhalClient {
 
     /// Submits a transaction to the local Narwhal worker.
     pub async fn submit_transaction(&self, transaction: Transaction) -> Result<(), NarwhalError> {
+        self.submit(transaction, None).await
+    }
+
+    /// Submits a transaction already validated, and prioritized, to the local Narwhal worker.
+    pub(crate) async fn submit_validated_transaction(
+        &self,
+        transaction: Transaction,
+        priority: u64,
+    ) -> Result<(), NarwhalError> {
+        self.submit(transaction, Some(priority)).await
+    }
+
+    async fn submit(
+        &self,
+        transaction: Transaction,
+        priority: Option<u64>,
+    ) -> Result<(), NarwhalError> {
         if transaction.len() > MAX_ALLOWED_TRANSACTION_SIZE {
             return Err(NarwhalError::TransactionTooLarge(
                 transaction.len(),
@@ -85,7 +102,7 @@ impl LocalNarwhalClient {
         // Send the transaction to the batch maker.
         let (notifier, when_done) = tokio::sync::oneshot::channel();
         self.tx_batch_maker
-            .send((transaction, notifier))
+            .send((transaction, priority, notifier))
             .await
             .map_err(|_| NarwhalError::ShuttingDown)?;
 
@@ -9,20 +9,21 @@ use fastcrypto::hash::Hash;
 use futures::{stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use rand::seq::SliceRandom;
-use std::{collections::HashSet, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use store::{rocks::DBMap, Map};
 use tokio::time::sleep;
 use tracing::{debug, info, trace, warn};
 use types::{
-    metered_channel::Sender, Batch, BatchDigest, PrimaryToWorker, RequestBatchRequest,
-    RequestBatchResponse, RequestBatchesRequest, RequestBatchesResponse, WorkerBatchMessage,
-    WorkerDeleteBatchesMessage, WorkerOthersBatchMessage, WorkerSynchronizeMessage, WorkerToWorker,
-    WorkerToWorkerClient,
+    metered_channel::Sender, Batch, BatchDigest, CompressedBatch, PrimaryToWorker,
+    RequestBatchRequest, RequestBatchResponse, RequestBatchesRequest, RequestBatchesResponse,
+    RequestCompressedBatchesRequest, RequestCompressedBatchesResponse, WorkerBatchMessage,
+    WorkerCompressedBatchMessage, WorkerDeleteBatchesMessage, WorkerOthersBatchMessage,
+    WorkerSynchronizeMessage, WorkerToWorker, WorkerToWorkerClient,
 };
 
 use mysten_metrics::monitored_future;
 
-use crate::TransactionValidator;
+use crate::{metrics::WorkerMetrics, TransactionValidator};
 
 #[cfg(test)]
 #[path = "tests/handlers_tests.rs"]
@@ -35,24 +36,21 @@ pub struct WorkerReceiverHandler<V> {
     pub tx_others_batch: Sender<WorkerOthersBatchMessage>,
     pub store: DBMap<BatchDigest, Batch>,
     pub validator: V,
+    pub metrics: Arc<WorkerMetrics>,
 }
 
-#[async_trait]
-impl<V: TransactionValidator> WorkerToWorker for WorkerReceiverHandler<V> {
-    async fn report_batch(
-        &self,
-        request: anemo::Request<WorkerBatchMessage>,
-    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
-        let message = request.into_body();
-        if let Err(err) = self.validator.validate_batch(&message.batch).await {
+impl<V: TransactionValidator> WorkerReceiverHandler<V> {
+    /// Validates and stores a batch broadcast by another worker, and reports it to our primary.
+    async fn process_batch(&self, batch: Batch) -> Result<(), anemo::rpc::Status> {
+        if let Err(err) = self.validator.validate_batch(&batch).await {
             // The batch is invalid, we don't want to process it.
             return Err(anemo::rpc::Status::new_with_message(
                 StatusCode::BadRequest,
                 format!("Invalid batch: {err}"),
             ));
         }
-        let digest = message.batch.digest();
-        self.store.insert(&digest, &message.batch).map_err(|e| {
+        let digest = batch.digest();
+        self.store.insert(&digest, &batch).map_err(|e| {
             anemo::rpc::Status::internal(format!("failed to write to batch store: {e:?}"))
         })?;
         self.tx_others_batch
@@ -61,31 +59,18 @@ impl<V: TransactionValidator> WorkerToWorker for WorkerReceiverHandler<V> {
                 worker_id: self.id,
             })
             .await
-            .map(|_| anemo::Response::new(()))
             .map_err(|e| anemo::rpc::Status::internal(e.to_string()))
     }
 
-    async fn request_batch(
-        &self,
-        request: anemo::Request<RequestBatchRequest>,
-    ) -> Result<anemo::Response<RequestBatchResponse>, anemo::rpc::Status> {
-        // TODO [issue #7]: Do some accounting to prevent bad actors from monopolizing our resources
-        let batch = request.into_body().batch;
-        let batch = self.store.get(&batch).map_err(|e| {
-            anemo::rpc::Status::internal(format!("failed to read from batch store: {e:?}"))
-        })?;
-
-        Ok(anemo::Response::new(RequestBatchResponse { batch }))
-    }
-
-    async fn request_batches(
+    /// Reads the requested batches from the store, in order, until their total size reaches the
+    /// size limit of a response. Returns the batches and whether the limit was reached.
+    fn read_batches(
         &self,
-        request: anemo::Request<RequestBatchesRequest>,
-    ) -> Result<anemo::Response<RequestBatchesResponse>, anemo::rpc::Status> {
+        digests_to_fetch: Vec<BatchDigest>,
+    ) -> Result<(Vec<Batch>, bool), anemo::rpc::Status> {
         const MAX_REQUEST_BATCHES_RESPONSE_SIZE: usize = 6_000_000;
         const BATCH_DIGESTS_READ_CHUNK_SIZE: usize = 200;
 
-        let digests_to_fetch = request.into_body().batch_digests;
         let digests_chunks = digests_to_fetch
             .chunks(BATCH_DIGESTS_READ_CHUNK_SIZE)
             .map(|chunk| chunk.to_vec())
@@ -111,11 +96,88 @@ impl<V: TransactionValidator> WorkerToWorker for WorkerReceiverHandler<V> {
             }
         }
 
+        Ok((batches, is_size_limit_reached))
+    }
+}
+
+#[async_trait]
+impl<V: TransactionValidator> WorkerToWorker for WorkerReceiverHandler<V> {
+    async fn report_batch(
+        &self,
+        request: anemo::Request<WorkerBatchMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        let message = request.into_body();
+        self.process_batch(message.batch).await?;
+        Ok(anemo::Response::new(()))
+    }
+
+    async fn report_compressed_batch(
+        &self,
+        request: anemo::Request<WorkerCompressedBatchMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        let compressed = request.into_body().batch;
+        let batch = compressed.decompress().map_err(|err| {
+            anemo::rpc::Status::new_with_message(
+                StatusCode::BadRequest,
+                format!("Invalid compressed batch: {err}"),
+            )
+        })?;
+        self.metrics
+            .record_batch_compression("received", &batch, &compressed);
+        self.process_batch(batch).await?;
+        Ok(anemo::Response::new(()))
+    }
+
+    async fn request_batch(
+        &self,
+        request: anemo::Request<RequestBatchRequest>,
+    ) -> Result<anemo::Response<RequestBatchResponse>, anemo::rpc::Status> {
+        // TODO [issue #7]: Do some accounting to prevent bad actors from monopolizing our resources
+        let batch = request.into_body().batch;
+        let batch = self.store.get(&batch).map_err(|e| {
+            anemo::rpc::Status::internal(format!("failed to read from batch store: {e:?}"))
+        })?;
+
+        Ok(anemo::Response::new(RequestBatchResponse { batch }))
+    }
+
+    async fn request_batches(
+        &self,
+        request: anemo::Request<RequestBatchesRequest>,
+    ) -> Result<anemo::Response<RequestBatchesResponse>, anemo::rpc::Status> {
+        let (batches, is_size_limit_reached) =
+            self.read_batches(request.into_body().batch_digests)?;
+
         Ok(anemo::Response::new(RequestBatchesResponse {
             batches,
             is_size_limit_reached,
         }))
     }
+
+    async fn request_compressed_batches(
+        &self,
+        request: anemo::Request<RequestCompressedBatchesRequest>,
+    ) -> Result<anemo::Response<RequestCompressedBatchesResponse>, anemo::rpc::Status> {
+        let request = request.into_body();
+        let (batches, is_size_limit_reached) = self.read_batches(request.batch_digests)?;
+
+        let batches = batches
+            .iter()
+            .map(|batch| {
+                let compressed = CompressedBatch::new(batch, request.compression).map_err(|e| {
+                    anemo::rpc::Status::internal(format!("failed to compress batch: {e}"))
+                })?;
+                self.metrics
+                    .record_batch_compression("served", batch, &compressed);
+                Ok(compressed)
+            })
+            .collect::<Result<_, anemo::rpc::Status>>()?;
+
+        Ok(anemo::Response::new(RequestCompressedBatchesResponse {
+            batches,
+            is_size_limit_reached,
+        }))
+    }
 }
 
 /// Defines how the network receiver handles incoming primary messages.
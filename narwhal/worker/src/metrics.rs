@@ -4,11 +4,13 @@ use mysten_network::metrics::MetricsCallbackProvider;
 use network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
 use prometheus::{
     default_registry, register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_counter_with_registry, register_int_gauge_with_registry, HistogramVec, IntCounter,
-    IntCounterVec, IntGauge, Registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Registry,
 };
 use std::time::Duration;
 use tonic::Code;
+use types::{Batch, CompressedBatch};
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.15, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.2, 1.4,
@@ -62,6 +64,16 @@ pub struct WorkerMetrics {
     pub created_batch_latency: HistogramVec,
     /// The number of parallel worker batches currently processed by the worker
     pub parallel_worker_batches: IntGauge,
+    /// The number of transactions waiting to be put in a batch, by priority
+    pub queued_transactions: IntGaugeVec,
+    /// The number of transactions put in a batch, by priority
+    pub sealed_transactions: IntCounterVec,
+    /// Time transactions waited to be put in a batch, by priority
+    pub transaction_queue_latency: HistogramVec,
+    /// Size in bytes of the transactions of the compressed batches exchanged with other workers
+    pub batch_compression_raw_bytes: IntCounterVec,
+    /// Size in bytes of the compressed batches exchanged with other workers
+    pub batch_compression_compressed_bytes: IntCounterVec,
 }
 
 impl WorkerMetrics {
@@ -103,8 +115,61 @@ impl WorkerMetrics {
                 registry
             )
             .unwrap(),
+            queued_transactions: register_int_gauge_vec_with_registry!(
+                "batch_maker_queued_transactions",
+                "The number of transactions waiting to be put in a batch, by order of magnitude of their priority",
+                &["priority"],
+                registry
+            )
+            .unwrap(),
+            sealed_transactions: register_int_counter_vec_with_registry!(
+                "batch_maker_sealed_transactions",
+                "The number of transactions put in a batch, by order of magnitude of their priority",
+                &["priority"],
+                registry
+            )
+            .unwrap(),
+            transaction_queue_latency: register_histogram_vec_with_registry!(
+                "batch_maker_transaction_queue_latency",
+                "The time transactions waited to be put in a batch, by order of magnitude of their priority",
+                &["priority"],
+                // buckets in seconds
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+            batch_compression_raw_bytes: register_int_counter_vec_with_registry!(
+                "batch_compression_raw_bytes",
+                "Size in bytes of the transactions of the compressed batches exchanged with other workers",
+                &["direction"],
+                registry
+            )
+            .unwrap(),
+            batch_compression_compressed_bytes: register_int_counter_vec_with_registry!(
+                "batch_compression_compressed_bytes",
+                "Size in bytes of the compressed batches exchanged with other workers",
+                &["direction"],
+                registry
+            )
+            .unwrap(),
         }
     }
+
+    /// Records the size of a batch exchanged with another worker, before and after compression.
+    /// `direction` is one of "broadcast", "received" or "served".
+    pub fn record_batch_compression(
+        &self,
+        direction: &str,
+        batch: &Batch,
+        compressed: &CompressedBatch,
+    ) {
+        self.batch_compression_raw_bytes
+            .with_label_values(&[direction])
+            .inc_by(batch.size() as u64);
+        self.batch_compression_compressed_bytes
+            .with_label_values(&[direction])
+            .inc_by(compressed.bytes.len() as u64);
+    }
 }
 
 impl Default for WorkerMetrics {
@@ -2,16 +2,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch_maker::MAX_PARALLEL_BATCH;
-use config::{Authority, Committee, Stake, WorkerCache, WorkerId};
+use crate::{batch_maker::MAX_PARALLEL_BATCH, metrics::WorkerMetrics};
+use config::{Authority, BatchCompression, Committee, Stake, WorkerCache, WorkerId};
+use crypto::NetworkPublicKey;
 use fastcrypto::hash::Hash;
 use futures::stream::{futures_unordered::FuturesUnordered, StreamExt as _};
 use mysten_metrics::{monitored_future, spawn_logged_monitored_task};
 use network::{CancelOnDropHandler, ReliableNetwork};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::{task::JoinHandle, time::timeout};
 use tracing::{trace, warn};
-use types::{metered_channel::Receiver, Batch, ConditionalBroadcastReceiver, WorkerBatchMessage};
+use types::{
+    metered_channel::Receiver, Batch, CompressedBatch, ConditionalBroadcastReceiver,
+    WorkerBatchBroadcast, WorkerBatchMessage, WorkerCompressedBatchMessage,
+};
 
 #[cfg(test)]
 #[path = "tests/quorum_waiter_tests.rs"]
@@ -33,6 +37,10 @@ pub struct QuorumWaiter {
     rx_quorum_waiter: Receiver<(Batch, tokio::sync::oneshot::Sender<()>)>,
     /// A network sender to broadcast the batches to the other workers.
     network: anemo::Network,
+    /// The compression applied to the broadcast batches.
+    compression: BatchCompression,
+    /// Metrics handler
+    metrics: Arc<WorkerMetrics>,
 }
 
 impl QuorumWaiter {
@@ -46,6 +54,8 @@ impl QuorumWaiter {
         rx_shutdown: ConditionalBroadcastReceiver,
         rx_quorum_waiter: Receiver<(Batch, tokio::sync::oneshot::Sender<()>)>,
         network: anemo::Network,
+        compression: BatchCompression,
+        metrics: Arc<WorkerMetrics>,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -57,6 +67,8 @@ impl QuorumWaiter {
                     rx_shutdown,
                     rx_quorum_waiter,
                     network,
+                    compression,
+                    metrics,
                 }
                 .run()
                 .await;
@@ -74,6 +86,35 @@ impl QuorumWaiter {
         deliver
     }
 
+    /// Broadcasts the batch to the other workers, compressed if so configured. Workers that do
+    /// not support compressed batches are sent the batch as is.
+    fn broadcast(
+        &self,
+        worker_names: Vec<NetworkPublicKey>,
+        batch: &Batch,
+    ) -> Vec<CancelOnDropHandler<anemo::Result<anemo::Response<()>>>> {
+        if self.compression != BatchCompression::Uncompressed {
+            match CompressedBatch::new(batch, self.compression) {
+                Ok(compressed) => {
+                    self.metrics
+                        .record_batch_compression("broadcast", batch, &compressed);
+                    let message = WorkerBatchBroadcast {
+                        batch: WorkerBatchMessage {
+                            batch: batch.clone(),
+                        },
+                        compressed: WorkerCompressedBatchMessage { batch: compressed },
+                    };
+                    return self.network.broadcast(worker_names, &message);
+                }
+                Err(e) => warn!("Failed to compress batch {}: {e}", batch.digest()),
+            }
+        }
+        let message = WorkerBatchMessage {
+            batch: batch.clone(),
+        };
+        self.network.broadcast(worker_names, &message)
+    }
+
     /// Main loop.
     async fn run(&mut self) {
         let mut pipeline = FuturesUnordered::new();
@@ -95,8 +136,7 @@ impl QuorumWaiter {
                         .map(|(name, info)| (name, info.name))
                         .collect();
                     let (primary_names, worker_names): (Vec<_>, _) = workers.into_iter().unzip();
-                    let message  = WorkerBatchMessage{batch: batch.clone()};
-                    let handlers = self.network.broadcast(worker_names, &message);
+                    let handlers = self.broadcast(worker_names, &batch);
 
                     // Collect all the handlers to receive acknowledgements.
                     let mut wait_for_quorum: FuturesUnordered<_> = primary_names
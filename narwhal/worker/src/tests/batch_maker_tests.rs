@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 
-use crate::NUM_SHUTDOWN_RECEIVERS;
+use crate::{TrivialTransactionValidator, NUM_SHUTDOWN_RECEIVERS};
+use async_trait::async_trait;
 use prometheus::Registry;
 use store::rocks;
 use store::rocks::MetricConf;
@@ -44,14 +45,15 @@ async fn make_batch() {
         Arc::new(node_metrics),
         store.clone(),
         tx_our_batch,
+        TrivialTransactionValidator,
     );
 
     // Send enough transactions to seal a batch.
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
     let (s1, r1) = tokio::sync::oneshot::channel();
-    tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
-    tx_batch_maker.send((tx.clone(), s1)).await.unwrap();
+    tx_batch_maker.send((tx.clone(), None, s0)).await.unwrap();
+    tx_batch_maker.send((tx.clone(), None, s1)).await.unwrap();
 
     // Ensure the batch is as expected.
     let expected_batch = Batch::new(vec![tx.clone(), tx.clone()]);
@@ -95,12 +97,13 @@ async fn batch_timeout() {
         Arc::new(node_metrics),
         store.clone(),
         tx_our_batch,
+        TrivialTransactionValidator,
     );
 
     // Do not send enough transactions to seal a batch.
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
-    tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
+    tx_batch_maker.send((tx.clone(), None, s0)).await.unwrap();
 
     // Ensure the batch is as expected.
     let (batch, resp) = rx_quorum_waiter.recv().await.unwrap();
@@ -119,3 +122,122 @@ async fn batch_timeout() {
     // Ensure the batch is stored
     assert!(store.get(&batch.digest()).unwrap().is_some());
 }
+
+// A test validator that takes the first byte of a transaction as its priority.
+#[derive(Clone)]
+struct FirstByteTxValidator;
+#[async_trait]
+impl TransactionValidator for FirstByteTxValidator {
+    type Error = eyre::Report;
+
+    fn validate(&self, _tx: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn validate_batch(&self, _b: &Batch) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn validate_and_prioritize(&self, tx: &[u8]) -> Result<u64, Self::Error> {
+        Ok(tx[0] as u64)
+    }
+}
+
+#[tokio::test]
+async fn make_batch_by_priority() {
+    let store = create_batches_store();
+    let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
+    let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
+    let (tx_quorum_waiter, mut rx_quorum_waiter) = test_utils::test_channel!(1);
+    let (tx_our_batch, mut rx_our_batch) = test_utils::test_channel!(1);
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+
+    // Spawn a `BatchMaker` instance.
+    let id = 0;
+    let _batch_maker_handle = BatchMaker::spawn(
+        id,
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        tx_shutdown.subscribe(),
+        rx_batch_maker,
+        tx_quorum_waiter,
+        node_metrics.clone(),
+        store.clone(),
+        tx_our_batch,
+        FirstByteTxValidator,
+    );
+
+    // Send a transaction of low priority, then one of high priority to seal the batch.
+    let mut low = transaction();
+    low[0] = 1;
+    let mut high = transaction();
+    high[0] = 100;
+    let (s0, r0) = tokio::sync::oneshot::channel();
+    let (s1, r1) = tokio::sync::oneshot::channel();
+    tx_batch_maker.send((low.clone(), None, s0)).await.unwrap();
+    tx_batch_maker.send((high.clone(), None, s1)).await.unwrap();
+
+    // Ensure the transaction of high priority comes first.
+    let (batch, resp) = rx_quorum_waiter.recv().await.unwrap();
+    assert_eq!(batch.transactions, vec![high, low]);
+
+    // Eventually deliver message
+    assert!(resp.send(()).is_ok());
+
+    // Now we send to primary
+    let (_message, respond) = rx_our_batch.recv().await.unwrap();
+    assert!(respond.unwrap().send(()).is_ok());
+
+    assert!(r0.await.is_ok());
+    assert!(r1.await.is_ok());
+
+    // Ensure the metrics account for both priorities.
+    for class in ["1", "100"] {
+        assert_eq!(
+            node_metrics
+                .sealed_transactions
+                .with_label_values(&[class])
+                .get(),
+            1
+        );
+        assert_eq!(
+            node_metrics
+                .queued_transactions
+                .with_label_values(&[class])
+                .get(),
+            0
+        );
+    }
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn pop_batch_ages_transactions() {
+    let metrics = WorkerMetrics::new(&Registry::new());
+    let max_queue_delay = Duration::from_secs(1);
+    let mut queue = TransactionQueue::default();
+
+    // Queue a transaction of low priority, and let it wait for longer than the max queue delay.
+    let low = transaction();
+    queue.push(1, low.clone(), tokio::sync::oneshot::channel().0, &metrics);
+    tokio::time::advance(max_queue_delay * 2).await;
+
+    // Queue enough transactions of high priority to fill two batches.
+    let high: Vec<_> = (0..4).map(|_| transaction()).collect();
+    for tx in &high {
+        queue.push(100, tx.clone(), tokio::sync::oneshot::channel().0, &metrics);
+    }
+
+    // The transaction of low priority goes in the next batch, ahead of the others.
+    let (batch, _, _) = queue.pop_batch(200, max_queue_delay, &metrics);
+    assert_eq!(batch.transactions, vec![low, high[0].clone()]);
+
+    // The transactions which did not wait for long follow by priority.
+    let recent = transaction();
+    queue.push(
+        1_000,
+        recent.clone(),
+        tokio::sync::oneshot::channel().0,
+        &metrics,
+    );
+    let (batch, _, _) = queue.pop_batch(200, max_queue_delay, &metrics);
+    assert_eq!(batch.transactions, vec![recent, high[1].clone()]);
+}
@@ -4,7 +4,9 @@
 use super::*;
 
 use crate::TrivialTransactionValidator;
+use config::BatchCompression;
 use fastcrypto::hash::Hash;
+use prometheus::Registry;
 use test_utils::CommitteeFixture;
 use types::{MockWorkerToWorker, WorkerToWorkerServer};
 
@@ -166,3 +168,61 @@ async fn delete_batches() {
 
     assert!(store.get(&digest).unwrap().is_none());
 }
+
+#[tokio::test]
+async fn compressed_batches() {
+    telemetry_subscribers::init_for_testing();
+
+    let (tx_others_batch, mut rx_others_batch) = test_utils::test_channel!(1);
+    let store = test_utils::open_batch_store();
+    let handler = WorkerReceiverHandler {
+        id: 0,
+        tx_others_batch,
+        store: store.clone(),
+        validator: TrivialTransactionValidator,
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
+    };
+
+    // Report a compressed batch.
+    let batch = test_utils::batch();
+    let digest = batch.digest();
+    let message = WorkerCompressedBatchMessage {
+        batch: CompressedBatch::new(&batch, BatchCompression::Zstd).unwrap(),
+    };
+    handler
+        .report_compressed_batch(anemo::Request::new(message))
+        .await
+        .unwrap();
+
+    // It is stored decompressed and reported to the primary.
+    assert_eq!(store.get(&digest).unwrap(), Some(batch.clone()));
+    assert_eq!(rx_others_batch.recv().await.unwrap().digest, digest);
+
+    // Request it back compressed.
+    let request = RequestCompressedBatchesRequest {
+        batch_digests: vec![digest],
+        compression: BatchCompression::Zstd,
+    };
+    let response = handler
+        .request_compressed_batches(anemo::Request::new(request))
+        .await
+        .unwrap()
+        .into_body();
+    assert!(!response.is_size_limit_reached);
+    assert_eq!(response.batches.len(), 1);
+    assert_eq!(response.batches[0].compression, BatchCompression::Zstd);
+    assert_eq!(response.batches[0].decompress().unwrap(), batch);
+
+    // A batch that does not decompress is rejected.
+    let message = WorkerCompressedBatchMessage {
+        batch: CompressedBatch {
+            compression: BatchCompression::Zstd,
+            bytes: vec![1, 2, 3],
+        },
+    };
+    let status = handler
+        .report_compressed_batch(anemo::Request::new(message))
+        .await
+        .unwrap_err();
+    assert_eq!(status.status(), StatusCode::BadRequest);
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 use crate::NUM_SHUTDOWN_RECEIVERS;
+use prometheus::Registry;
 use test_utils::{batch, test_network, CommitteeFixture, WorkerToWorkerMockServer};
 use types::PreSubscribedBroadcastSender;
 
@@ -28,6 +29,8 @@ async fn wait_for_quorum() {
         tx_shutdown.subscribe(),
         rx_quorum_waiter,
         network.clone(),
+        BatchCompression::Uncompressed,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Make a batch.
@@ -85,6 +88,8 @@ async fn pipeline_for_quorum() {
         tx_shutdown.subscribe(),
         rx_quorum_waiter,
         network.clone(),
+        BatchCompression::Uncompressed,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Make a batch.
@@ -126,3 +131,79 @@ async fn pipeline_for_quorum() {
 
     r1.await.unwrap();
 }
+
+#[tokio::test]
+async fn wait_for_quorum_with_compression() {
+    let (tx_quorum_waiter, rx_quorum_waiter) = test_utils::test_channel!(1);
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.worker_cache();
+    let my_primary = fixture.authorities().next().unwrap();
+    let myself = fixture.authorities().next().unwrap().worker(0);
+
+    let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
+    let metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+
+    // setup network
+    let network = test_network(myself.keypair(), &myself.info().worker_address);
+    // Spawn a `QuorumWaiter` instance compressing the batches.
+    let _quorum_waiter_handler = QuorumWaiter::spawn(
+        my_primary.authority().clone(),
+        /* worker_id */ 0,
+        committee.clone(),
+        worker_cache.clone(),
+        tx_shutdown.subscribe(),
+        rx_quorum_waiter,
+        network.clone(),
+        BatchCompression::Zstd,
+        metrics.clone(),
+    );
+
+    // Make a batch.
+    let batch = batch();
+    let message = WorkerBatchMessage {
+        batch: batch.clone(),
+    };
+
+    // Spawn enough listeners to acknowledge our batches.
+    let mut listener_handles = Vec::new();
+    for worker in fixture.authorities().skip(1).map(|a| a.worker(0)) {
+        let handle =
+            WorkerToWorkerMockServer::spawn(worker.keypair(), worker.info().worker_address.clone());
+        listener_handles.push(handle);
+
+        // ensure that the networks are connected
+        network
+            .connect(worker.info().worker_address.to_anemo_address().unwrap())
+            .await
+            .unwrap();
+    }
+
+    // Forward the batch along with the handlers to the `QuorumWaiter`.
+    let (s, r) = tokio::sync::oneshot::channel();
+    tx_quorum_waiter.send((batch.clone(), s)).await.unwrap();
+
+    // Wait for the `QuorumWaiter` to gather enough acknowledgements and output the batch.
+    r.await.unwrap();
+
+    // Ensure the other listeners received the batch, once decompressed.
+    for (mut handle, _network) in listener_handles {
+        assert_eq!(handle.recv().await.unwrap(), message);
+    }
+
+    // The batch was compressed once, before being broadcast.
+    assert_eq!(
+        metrics
+            .batch_compression_raw_bytes
+            .with_label_values(&["broadcast"])
+            .get(),
+        batch.size() as u64
+    );
+    assert!(
+        metrics
+            .batch_compression_compressed_bytes
+            .with_label_values(&["broadcast"])
+            .get()
+            > 0
+    );
+}
@@ -26,7 +26,7 @@ pub struct TxServer<V: TransactionValidator> {
     address: Multiaddr,
     rx_shutdown: ConditionalBroadcastReceiver,
     endpoint_metrics: WorkerEndpointMetrics,
-    tx_batch_maker: Sender<(Transaction, TxResponse)>,
+    tx_batch_maker: Sender<(Transaction, Option<u64>, TxResponse)>,
     validator: V,
 }
 
@@ -36,7 +36,7 @@ impl<V: TransactionValidator> TxServer<V> {
         address: Multiaddr,
         rx_shutdown: ConditionalBroadcastReceiver,
         endpoint_metrics: WorkerEndpointMetrics,
-        tx_batch_maker: Sender<(Transaction, TxResponse)>,
+        tx_batch_maker: Sender<(Transaction, Option<u64>, TxResponse)>,
         validator: V,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
@@ -142,12 +142,13 @@ impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
         request: Request<TransactionProto>,
     ) -> Result<Response<Empty>, Status> {
         let transaction = request.into_inner().transaction;
-        if self.validator.validate(transaction.as_ref()).is_err() {
-            return Err(Status::invalid_argument("Invalid transaction"));
-        }
+        let priority = match self.validator.validate_and_prioritize(transaction.as_ref()) {
+            Ok(priority) => priority,
+            Err(_) => return Err(Status::invalid_argument("Invalid transaction")),
+        };
         // Send the transaction to Narwhal via the local client.
         self.local_client
-            .submit_transaction(transaction.to_vec())
+            .submit_validated_transaction(transaction.to_vec(), priority)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
         Ok(Response::new(Empty {}))
@@ -161,19 +162,25 @@ impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
         let mut reqeusts = FuturesUnordered::new();
 
         while let Some(Ok(txn)) = transactions.next().await {
-            if let Err(err) = self.validator.validate(txn.transaction.as_ref()) {
+            let priority = match self
+                .validator
+                .validate_and_prioritize(txn.transaction.as_ref())
+            {
+                Ok(priority) => priority,
                 // If the transaction is invalid (often cryptographically), better to drop the client
-                return Err(Status::invalid_argument(format!(
-                    "Stream contains an invalid transaction {err}"
-                )));
-            }
+                Err(err) => {
+                    return Err(Status::invalid_argument(format!(
+                        "Stream contains an invalid transaction {err}"
+                    )))
+                }
+            };
             // Send the transaction to Narwhal via the local client.
             // Note that here we do not wait for a response because this would
             // mean that we process only a single message from this stream at a
             // time. Instead we gather them and resolve them once the stream is over.
             reqeusts.push(
                 self.local_client
-                    .submit_transaction(txn.transaction.to_vec()),
+                    .submit_validated_transaction(txn.transaction.to_vec(), priority),
             );
         }
 
@@ -15,6 +15,13 @@ pub trait TransactionValidator: Clone + Send + Sync + 'static {
     fn validate(&self, t: &[u8]) -> Result<(), Self::Error>;
     /// Determines if this batch can be voted on
     async fn validate_batch(&self, b: &Batch) -> Result<(), Self::Error>;
+    /// Determines if a transaction is valid like `validate`, and its priority, e.g. from the fee
+    /// it pays, from a single decoding of the transaction. Transactions of higher priority are put
+    /// in batches first. All transactions have the same priority by default, so that they are put
+    /// in batches in the order they are received.
+    fn validate_and_prioritize(&self, t: &[u8]) -> Result<u64, Self::Error> {
+        self.validate(t).map(|()| 0)
+    }
 }
 
 /// Simple validator that accepts all transactions and batches.
@@ -122,15 +122,18 @@ impl Worker {
             tx_others_batch,
             store: worker.store.clone(),
             validator: validator.clone(),
+            metrics: node_metrics.clone(),
         });
         // Apply rate limits from configuration as needed.
         if let Some(limit) = parameters.anemo.report_batch_rate_limit {
-            worker_service = worker_service.add_layer_for_report_batch(InboundRequestLayer::new(
-                rate_limit::RateLimitLayer::new(
-                    governor::Quota::per_second(limit),
-                    rate_limit::WaitMode::Block,
-                ),
+            // Batches are reported either compressed or not, both under the same limit.
+            let report_batch_layer = InboundRequestLayer::new(rate_limit::RateLimitLayer::new(
+                governor::Quota::per_second(limit),
+                rate_limit::WaitMode::Block,
             ));
+            worker_service = worker_service.add_layer_for_report_batch(report_batch_layer.clone());
+            worker_service =
+                worker_service.add_layer_for_report_compressed_batch(report_batch_layer);
         }
         if let Some(limit) = parameters.anemo.request_batch_rate_limit {
             worker_service = worker_service.add_layer_for_request_batch(InboundRequestLayer::new(
@@ -465,7 +468,7 @@ impl Worker {
             shutdown_receivers.pop().unwrap(),
             endpoint_metrics,
             tx_batch_maker,
-            validator,
+            validator.clone(),
         );
 
         // The transactions are sent to the `BatchMaker` that assembles them into batches. It then broadcasts
@@ -478,9 +481,10 @@ impl Worker {
             shutdown_receivers.pop().unwrap(),
             rx_batch_maker,
             tx_quorum_waiter,
-            node_metrics,
+            node_metrics.clone(),
             self.store.clone(),
             tx_our_batch,
+            validator,
         );
 
         // The `QuorumWaiter` waits for 2f authorities to acknowledge reception of the batch. It then forwards
@@ -493,6 +497,8 @@ impl Worker {
             shutdown_receivers.pop().unwrap(),
             rx_quorum_waiter,
             network,
+            self.parameters.batch_compression,
+            node_metrics,
         );
 
         info!(